@@ -1,6 +1,11 @@
+use crate::autotag::Autotagger;
+use crate::filtering::FilterOptions;
 use crate::models::Entry;
-use crate::services::EntryService;
-use chrono::{Duration, Local, TimeZone};
+use crate::models::entry::parse_time;
+use crate::services::{BulkAction, EntryService};
+use crate::storage::{Config, parse_title};
+use chrono::{DateTime, Duration, Local, TimeZone};
+use chrono_english::{Dialect, parse_date_string};
 use color_eyre::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
@@ -8,10 +13,19 @@ use ratatui::{
     layout::{Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Tabs, Wrap},
 };
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::SystemTime;
 use tui_textarea::{Input, TextArea};
 
+mod calendar;
+mod timeline;
+
+use calendar::CalendarView;
+use timeline::TimelineView;
+
 /// Different modes the app can be in
 #[derive(Debug, PartialEq)]
 pub enum AppMode {
@@ -19,6 +33,110 @@ pub enum AppMode {
     EditEntry,
     EditNote,
     EditTimestamp,
+    NewEntry,
+    Search,
+    TagFilter,
+    BulkInput,
+    ConfirmDelete,
+}
+
+/// The screens of the app, switched with the number keys
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Screen {
+    List,
+    Calendar,
+    Timeline,
+}
+
+/// Screen names for the tabs, in number key order
+const SCREENS: [&str; 3] = ["1 List", "2 Calendar", "3 Timeline"];
+
+/// What the text typed in [`AppMode::BulkInput`] is for
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BulkPrompt {
+    Tag,
+    Untag,
+    Move,
+}
+
+/// How many more entries are loaded when the selection reaches the end of the list
+const PAGE_SIZE: usize = 50;
+
+/// Lines PageUp and PageDown move the selection by
+const PAGE_STEP: usize = 10;
+
+/// How often running timers are redrawn and the doing file is checked for changes
+const TICK_RATE: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// The fields of the new entry form, in Tab order
+const NEW_ENTRY_FIELDS: [&str; 5] = [
+    "Title (@tags allowed)",
+    "Tags (comma separated)",
+    "Section",
+    "Start (empty for now, or e.g. \"20 minutes ago\")",
+    "Note",
+];
+
+/// The new entry form
+#[derive(Debug, Default)]
+struct NewEntryForm {
+    /// One text area per field of `NEW_ENTRY_FIELDS`
+    fields: [TextArea<'static>; 5],
+    /// Index of the focused field
+    focus: usize,
+    /// Finish the running entry in the section first, like `now --finish_last`
+    finish_last: bool,
+}
+
+impl NewEntryForm {
+    fn new(section: &str, finish_last: bool) -> Self {
+        let mut form = Self {
+            finish_last,
+            ..Default::default()
+        };
+        form.fields[2] = TextArea::new(vec![section.to_string()]);
+        form
+    }
+
+    fn text(&self, field: usize) -> String {
+        self.fields[field].lines().join("\n").trim().to_string()
+    }
+
+    /// Build the entry from the fields
+    fn entry(&self) -> std::result::Result<Entry, String> {
+        let title = self.text(0);
+        if title.is_empty() {
+            return Err("Title cannot be empty".to_string());
+        }
+        let extra_tags: Vec<String> = self
+            .text(1)
+            .split([',', ' '])
+            .map(|tag| tag.trim().trim_start_matches('@'))
+            .filter(|tag| !tag.is_empty())
+            .map(|tag| format!("@{tag}"))
+            .collect();
+        let (description, tags) = parse_title(&format!("{title} {}", extra_tags.join(" ")));
+        if description.is_empty() {
+            return Err("Title cannot be only tags".to_string());
+        }
+
+        let section = match self.text(2) {
+            section if section.is_empty() => "Currently".to_string(),
+            section => section,
+        };
+        let start = match self.text(3) {
+            start if start.is_empty() => Local::now(),
+            start => parse_date_string(&start, Local::now(), Dialect::Us)
+                .map_err(|_| format!("Invalid start time: {start}"))?,
+        };
+
+        let mut entry = Entry::new(description, section).with_started_at(start);
+        entry.tags = tags;
+        entry.sync_finished_at();
+        let note = self.text(4);
+        entry.note = (!note.is_empty()).then_some(note);
+        Ok(entry)
+    }
 }
 
 /// The main application which holds the state and logic of the application.
@@ -34,8 +152,18 @@ pub struct App {
     error: Option<String>,
     /// Show detailed view of selected entry
     show_detail: bool,
-    /// Section filter (if any)
-    section_filter: Option<String>,
+    /// Search, tags and section of the entries shown
+    filter: FilterOptions,
+    /// Sections of the doing file and how many entries each has, for the sidebar
+    sections: Vec<(String, usize)>,
+    /// How many entries match the filter, loaded or not
+    total: usize,
+    /// How many of the matching entries are loaded; grows a page at a time
+    limit: usize,
+    /// Input for the search query, applied as it is typed
+    search_textarea: TextArea<'static>,
+    /// Input for a tag filter, shown as a chip while it is typed
+    tag_textarea: TextArea<'static>,
     /// Current mode of the app
     mode: AppMode,
     /// Text area for editing entries
@@ -48,46 +176,98 @@ pub struct App {
     done_timestamp_textarea: TextArea<'static>,
     /// Original done flag state when entering timestamp edit mode
     original_done_state: bool,
-}
-
-impl Default for App {
-    fn default() -> Self {
-        Self::new()
-    }
+    /// Fields of the entry being added
+    new_entry_form: NewEntryForm,
+    /// Entries started today, for the tracked total in the status bar
+    today: Vec<Entry>,
+    /// The doing file, watched for changes made outside the TUI
+    doing_file_path: PathBuf,
+    /// When the doing file was last changed, as of the last load
+    modified: Option<SystemTime>,
+    /// Entries marked for a bulk action; they stay marked when filtered out
+    marked: HashSet<uuid::Uuid>,
+    /// What the bulk input is asked for
+    bulk_prompt: BulkPrompt,
+    /// Input for the tags or section of a bulk action
+    bulk_textarea: TextArea<'static>,
+    /// Outcome of the last bulk action, shown in the status bar
+    message: Option<String>,
+    /// The screen shown
+    screen: Screen,
+    calendar: CalendarView,
+    timeline: TimelineView,
 }
 
 impl App {
     /// Construct a new instance of [`App`].
-    pub fn new() -> Self {
+    pub fn new() -> Result<Self> {
         Self::new_with_section(None)
     }
 
     /// Construct a new instance of [`App`] with section filter.
-    pub fn new_with_section(section: Option<String>) -> Self {
+    pub fn new_with_section(section: Option<String>) -> Result<Self> {
         let mut app = Self {
             running: false,
             entries: Vec::new(),
             selected: 0,
             error: None,
             show_detail: false,
-            section_filter: section,
+            filter: FilterOptions {
+                sections: section.into_iter().collect(),
+                ..Default::default()
+            },
+            sections: Vec::new(),
+            total: 0,
+            limit: PAGE_SIZE,
+            search_textarea: TextArea::default(),
+            tag_textarea: TextArea::default(),
             mode: AppMode::Normal,
             edit_textarea: TextArea::default(),
             note_textarea: TextArea::default(),
             timestamp_textarea: TextArea::default(),
             done_timestamp_textarea: TextArea::default(),
             original_done_state: false,
+            new_entry_form: NewEntryForm::default(),
+            today: Vec::new(),
+            doing_file_path: Config::try_load()?.doing_file_path(),
+            modified: None,
+            marked: HashSet::new(),
+            bulk_prompt: BulkPrompt::Tag,
+            bulk_textarea: TextArea::default(),
+            message: None,
+            screen: Screen::List,
+            calendar: CalendarView::new(Local::now().date_naive()),
+            timeline: TimelineView::new(Local::now().date_naive()),
         };
         app.load_entries();
-        app
+        Ok(app)
     }
 
     /// Load entries from the doing file
     fn load_entries(&mut self) {
-        match EntryService::get_tui_entries(self.section_filter.as_deref(), 50) {
-            Ok(entries) => {
-                self.entries = entries;
+        // Taken before reading so a change made while loading is seen on the next tick
+        self.modified = self.file_modified();
+        let midnight = Local::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .and_then(|time| Local.from_local_datetime(&time).earliest());
+        let screen = self.screen_filter();
+        match EntryService::get_tui_view(
+            &self.current_filter(),
+            self.limit,
+            midnight,
+            screen.as_ref(),
+        ) {
+            Ok(view) => {
+                self.sections = view.sections;
+                self.entries = view.entries;
+                self.total = view.total;
+                self.today = view.today;
+                self.selected = self.selected.min(self.entries.len().saturating_sub(1));
                 self.error = None;
+                if let Some(entries) = view.screen {
+                    self.set_screen_entries(entries);
+                }
             }
             Err(e) => {
                 self.error = Some(format!("Failed to load entries: {e}"));
@@ -95,12 +275,153 @@ impl App {
         }
     }
 
+    /// The filter of the month or day shown by the calendar or the timeline.
+    /// They show the entries the list would, with the same search, tags and
+    /// section.
+    fn screen_filter(&self) -> Option<FilterOptions> {
+        let (start, end) = match self.screen {
+            Screen::List => return None,
+            Screen::Calendar => self.calendar.range(),
+            Screen::Timeline => self.timeline.range(),
+        };
+        Some(FilterOptions {
+            from: Some((start, Some(end))),
+            ..self.filter.clone()
+        })
+    }
+
+    fn set_screen_entries(&mut self, entries: Vec<Entry>) {
+        match self.screen {
+            Screen::Calendar => self.calendar.set_entries(entries),
+            Screen::Timeline => self.timeline.set_entries(entries),
+            Screen::List => {}
+        }
+    }
+
+    /// Load the month or day shown by the calendar or the timeline
+    fn load_screen(&mut self) {
+        let Some(filter) = self.screen_filter() else {
+            return;
+        };
+        match EntryService::get_tui_entries(&filter, usize::MAX) {
+            Ok((entries, _)) => self.set_screen_entries(entries),
+            Err(e) => self.error = Some(format!("Failed to load entries: {e}")),
+        }
+    }
+
+    /// Show another screen, on the day of the selected entry or calendar day
+    fn switch_screen(&mut self, screen: Screen) {
+        let day = match self.screen {
+            Screen::List => self
+                .entries
+                .get(self.selected)
+                .map(|entry| entry.started_at.date_naive()),
+            Screen::Calendar => Some(self.calendar.selected),
+            Screen::Timeline => Some(self.timeline.day),
+        };
+        if let Some(day) = day {
+            self.calendar.selected = day;
+            self.timeline.day = day;
+        }
+        self.screen = screen;
+        self.load_screen();
+    }
+
+    /// Load the entries again, keeping the same entry selected if it is still shown
+    fn reload(&mut self) {
+        let selected = self.entries.get(self.selected).map(|entry| entry.uuid);
+        self.load_entries();
+        if let Some(uuid) = selected {
+            self.select_uuid(&uuid);
+        }
+    }
+
+    fn file_modified(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.doing_file_path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    /// Reload when another process (the CLI, a hook) changed the doing
+    /// file. While an entry is being edited the reload waits, so the edit is
+    /// saved to the entry it was started on.
+    fn on_tick(&mut self) {
+        let editing = matches!(
+            self.mode,
+            AppMode::EditEntry | AppMode::EditNote | AppMode::EditTimestamp | AppMode::NewEntry
+        );
+        if !editing && self.file_modified() != self.modified {
+            self.reload();
+        }
+    }
+
+    /// Time tracked today: finished entries count their duration and running
+    /// ones the time so far
+    fn tracked_today(&self, now: DateTime<Local>) -> Duration {
+        self.today
+            .iter()
+            .map(|entry| running_time(entry, now).unwrap_or_else(Duration::zero))
+            .sum()
+    }
+
+    /// The filter with the tag being typed added, so results follow the typing
+    fn current_filter(&self) -> FilterOptions {
+        let mut filter = self.filter.clone();
+        let pending = self.tag_textarea.lines().join("").trim().to_string();
+        if self.mode == AppMode::TagFilter && !pending.is_empty() {
+            filter.tags.push(pending);
+        }
+        filter
+    }
+
+    /// Load the first page again after the filter changed
+    fn apply_filter(&mut self) {
+        self.limit = PAGE_SIZE;
+        self.selected = 0;
+        self.load_entries();
+    }
+
+    /// Move the selection, loading more entries when it goes past the last one loaded
+    fn move_selection(&mut self, target: usize) {
+        while target >= self.entries.len() && self.entries.len() < self.total {
+            self.limit += PAGE_SIZE;
+            let loaded = self.entries.len();
+            self.load_entries();
+            if self.entries.len() == loaded {
+                break;
+            }
+        }
+        self.selected = target.min(self.entries.len().saturating_sub(1));
+    }
+
+    /// Show the next (`forward`) or previous section, with all sections
+    /// before the first
+    fn switch_section(&mut self, forward: bool) {
+        let mut choices: Vec<Option<&String>> = vec![None];
+        choices.extend(self.sections.iter().map(|(name, _)| Some(name)));
+        let current = choices
+            .iter()
+            .position(|choice| choice == &self.filter.sections.first())
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % choices.len()
+        } else {
+            (current + choices.len() - 1) % choices.len()
+        };
+        self.filter.sections = choices[next].cloned().into_iter().collect();
+        self.apply_filter();
+    }
+
     /// Run the application's main loop.
     pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
         self.running = true;
         while self.running {
             terminal.draw(|frame| self.render(frame))?;
-            self.handle_crossterm_events()?;
+            if event::poll(TICK_RATE)? {
+                self.handle_crossterm_events()?;
+            } else {
+                self.on_tick();
+            }
         }
         Ok(())
     }
@@ -120,40 +441,47 @@ impl App {
                 self.render_timestamp_mode(frame);
                 return;
             }
+            AppMode::NewEntry => {
+                self.render_new_entry_mode(frame);
+                return;
+            }
             AppMode::Normal => {
                 if self.show_detail {
                     self.render_detail(frame);
                     return;
                 }
             }
+            AppMode::Search | AppMode::TagFilter | AppMode::BulkInput | AppMode::ConfirmDelete => {}
+        }
+        if self.screen != Screen::List {
+            self.render_screen(frame);
+            return;
         }
 
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .margin(1)
             .constraints([
+                Constraint::Length(3),
                 Constraint::Length(3),
                 Constraint::Min(0),
+                Constraint::Length(1),
                 Constraint::Length(3),
             ])
             .split(frame.area());
 
-        // Title
-        let title_text = if let Some(ref section) = self.section_filter {
-            format!("Daily Log - Doing TUI [Section: {section}]")
-        } else {
-            "Daily Log - Doing TUI".to_string()
-        };
-        let title = Paragraph::new(title_text)
-            .style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .block(Block::default().borders(Borders::ALL));
-        frame.render_widget(title, chunks[0]);
+        self.render_title(frame, chunks[0]);
+
+        self.render_filters(frame, chunks[1]);
+
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(26), Constraint::Min(0)])
+            .split(chunks[2]);
+        self.render_sections(frame, body[0]);
 
         // Entries list
+        let now = Local::now();
         let items: Vec<ListItem> = self
             .entries
             .iter()
@@ -161,10 +489,16 @@ impl App {
             .map(|(i, entry)| {
                 let mut lines = vec![];
 
-                // Main entry line
+                // Main entry line, after a mark while entries are marked
+                let mark = match (self.marked.contains(&entry.uuid), self.marked.is_empty()) {
+                    (true, _) => "● ",
+                    (false, false) => "  ",
+                    (false, true) => "",
+                };
                 let mut spans = vec![
+                    Span::styled(mark, Style::default().fg(Color::LightYellow)),
                     Span::styled(
-                        entry.timestamp.format("%Y-%m-%d %H:%M").to_string(),
+                        entry.started_at.format("%Y-%m-%d %H:%M").to_string(),
                         Style::default().fg(Color::Yellow),
                     ),
                     Span::raw(" | "),
@@ -191,30 +525,12 @@ impl App {
                     Style::default().fg(Color::Magenta),
                 ));
 
-                // Add elapsed time if done
-                if let Some(Some(done_time_str)) = entry.tags.get("done") {
-                    // Parse done timestamp
-                    if let Ok(done_time) =
-                        chrono::NaiveDateTime::parse_from_str(done_time_str, "%Y-%m-%d %H:%M")
-                    {
-                        let done_local = Local
-                            .from_local_datetime(&done_time)
-                            .single()
-                            .unwrap_or_else(Local::now);
-                        let duration = done_local.timestamp() - entry.timestamp.timestamp();
-                        if duration > 0 {
-                            let elapsed = Duration::seconds(duration);
-                            let hours = elapsed.num_hours();
-                            let minutes = (elapsed.num_minutes() % 60) as u32;
-                            let seconds = (elapsed.num_seconds() % 60) as u32;
-
-                            spans.push(Span::raw(" "));
-                            spans.push(Span::styled(
-                                format!("{hours:02}:{minutes:02}:{seconds:02}"),
-                                Style::default().fg(Color::Cyan),
-                            ));
-                        }
-                    }
+                // Add elapsed time, counting up while the entry runs
+                if let Some(elapsed) = running_time(entry, now)
+                    && elapsed.num_seconds() > 0
+                {
+                    spans.push(Span::raw(" "));
+                    spans.push(Span::styled(format_elapsed(elapsed), elapsed_style(entry)));
                 }
 
                 lines.push(Line::from(spans));
@@ -239,15 +555,41 @@ impl App {
             })
             .collect();
 
-        let entries_list =
-            List::new(items).block(Block::default().borders(Borders::ALL).title("Entries"));
-        frame.render_widget(entries_list, chunks[1]);
+        let entries_list = List::new(items).block(Block::default().borders(Borders::ALL).title(
+            format!("Entries ({} of {})", self.entries.len(), self.total),
+        ));
+        // The state scrolls the list to keep the selection in view
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(entries_list, body[1], &mut state);
+
+        self.render_status(frame, chunks[3], now);
 
         // Help/status bar
         let help_text = if let Some(error) = &self.error {
             format!("Error: {error} | Press 'q' to quit, 'r' to reload")
         } else {
-            "q: quit | ↑/↓: navigate | Enter: details | e: edit | n: note | t: time | d: delete | Space: toggle done | r: reload".to_string()
+            match self.mode {
+                AppMode::Search => "Type to search, /regex/ or 'exact | Enter: keep | Esc: clear".to_string(),
+                AppMode::TagFilter => "Type a tag, +tag to require or -tag to exclude | Enter: add | Esc: cancel".to_string(),
+                AppMode::BulkInput => {
+                    let prompt = match self.bulk_prompt {
+                        BulkPrompt::Tag => "Tags to add",
+                        BulkPrompt::Untag => "Tags to remove",
+                        BulkPrompt::Move => "Move to section",
+                    };
+                    format!(
+                        "{prompt} ({}): {}█ | Enter: apply | Esc: cancel",
+                        entries_label(self.targets().len()),
+                        self.bulk_textarea.lines().join("")
+                    )
+                }
+                AppMode::ConfirmDelete => format!(
+                    "Delete {}? y: delete | any other key: cancel",
+                    entries_label(self.targets().len())
+                ),
+                _ if !self.marked.is_empty() => "v/Space: mark | *: mark all matches | Esc: unmark all | F: finish | C: cancel | +/-: tag/untag | M: move | A: archive | !: flag | d: delete".to_string(),
+                _ => "q: quit | ↑/↓ PgUp/PgDn: navigate | Enter: details | a: add | f: finish & add | g: resume | e: edit | n: note | t: time | d: delete | Space: toggle done | v: mark | /: search | #: tag | Tab: section | x: clear filters | 2/3: calendar/timeline | r: reload".to_string(),
+            }
         };
         let help = Paragraph::new(help_text)
             .style(Style::default().fg(if self.error.is_some() {
@@ -256,7 +598,199 @@ impl App {
                 Color::Gray
             }))
             .block(Block::default().borders(Borders::ALL));
-        frame.render_widget(help, chunks[2]);
+        frame.render_widget(help, chunks[4]);
+    }
+
+    /// Render the title with a tab for each screen
+    fn render_title(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let title_text = if let Some(section) = self.filter.sections.first() {
+            format!("Daily Log - Doing TUI [Section: {section}]")
+        } else {
+            "Daily Log - Doing TUI".to_string()
+        };
+        let selected = match self.screen {
+            Screen::List => 0,
+            Screen::Calendar => 1,
+            Screen::Timeline => 2,
+        };
+        let tabs = Tabs::new(SCREENS)
+            .select(selected)
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .block(
+                Block::default().borders(Borders::ALL).title(Span::styled(
+                    title_text,
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )),
+            );
+        frame.render_widget(tabs, area);
+    }
+
+    /// Render the calendar or the timeline screen
+    fn render_screen(&self, frame: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(0),
+                Constraint::Length(1),
+                Constraint::Length(3),
+            ])
+            .split(frame.area());
+
+        self.render_title(frame, chunks[0]);
+
+        let now = Local::now();
+        let help_text = match self.screen {
+            Screen::Calendar => {
+                self.calendar.render(frame, chunks[1], now);
+                "1/2/3: screens | ←/→/↑/↓: day | PgUp/PgDn: month | t: today | Enter: timeline | r: reload | Esc: list | q: quit"
+            }
+            _ => {
+                self.timeline.render(frame, chunks[1], now);
+                "1/2/3: screens | ←/→: day | t: today | r: reload | Esc: list | q: quit"
+            }
+        };
+
+        self.render_status(frame, chunks[2], now);
+
+        let help = match &self.error {
+            Some(error) => {
+                Paragraph::new(format!("Error: {error}")).style(Style::default().fg(Color::Red))
+            }
+            None => Paragraph::new(help_text).style(Style::default().fg(Color::Gray)),
+        };
+        frame.render_widget(
+            help.wrap(Wrap { trim: true })
+                .block(Block::default().borders(Borders::ALL)),
+            chunks[3],
+        );
+    }
+
+    /// Render today's tracked total and the running entry
+    fn render_status(&self, frame: &mut Frame, area: ratatui::layout::Rect, now: DateTime<Local>) {
+        let mut spans = vec![
+            Span::styled(" Today: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(
+                format_elapsed(self.tracked_today(now)),
+                Style::default().fg(Color::Cyan),
+            ),
+        ];
+        let running = self
+            .today
+            .iter()
+            .filter(|entry| entry.is_running())
+            .max_by_key(|entry| entry.started_at);
+        if let Some(entry) = running {
+            spans.push(Span::raw(" | Running: "));
+            spans.push(Span::raw(&entry.description));
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                format_elapsed(entry.elapsed(now)),
+                elapsed_style(entry),
+            ));
+        }
+        if !self.marked.is_empty() {
+            spans.push(Span::raw(" | "));
+            spans.push(Span::styled(
+                format!("{} marked", self.marked.len()),
+                Style::default().fg(Color::LightYellow),
+            ));
+        }
+        if let Some(message) = &self.message {
+            spans.push(Span::raw(" | "));
+            spans.push(Span::raw(message));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+
+    /// Render the search query and the tag filters as chips
+    fn render_filters(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        let cursor = Span::styled(" ", Style::default().bg(Color::White));
+
+        let mut search = vec![Span::raw(self.filter.search.clone().unwrap_or_default())];
+        if self.mode == AppMode::Search {
+            search.push(cursor.clone());
+        }
+        let search_border = if self.mode == AppMode::Search {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(search)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(search_border)
+                    .title("Search (/)"),
+            ),
+            halves[0],
+        );
+
+        let chip = Style::default().fg(Color::Black).bg(Color::Green);
+        let mut chips = Vec::new();
+        for tag in &self.filter.tags {
+            chips.push(Span::styled(format!(" {tag} "), chip));
+            chips.push(Span::raw(" "));
+        }
+        if self.mode == AppMode::TagFilter {
+            chips.push(Span::styled(
+                format!(" {}", self.tag_textarea.lines().join("")),
+                Style::default().fg(Color::Black).bg(Color::Yellow),
+            ));
+            chips.push(cursor);
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(chips))
+                .block(Block::default().borders(Borders::ALL).title("Tags (#)")),
+            halves[1],
+        );
+    }
+
+    /// Render the sidebar of sections, marking the one shown
+    fn render_sections(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let all: usize = self.sections.iter().map(|(_, count)| count).sum();
+        let current = self.filter.sections.first();
+
+        let mut items = vec![(None, format!("All ({all})"))];
+        items.extend(
+            self.sections
+                .iter()
+                .map(|(name, count)| (Some(name), format!("{name} ({count})"))),
+        );
+        let items: Vec<ListItem> = items
+            .into_iter()
+            .map(|(section, label)| {
+                let style = if section == current {
+                    Style::default()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+                } else {
+                    Style::default().fg(Color::Magenta)
+                };
+                ListItem::new(label).style(style)
+            })
+            .collect();
+
+        frame.render_widget(
+            List::new(items).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Sections (Tab)"),
+            ),
+            area,
+        );
     }
 
     /// Render detailed view of selected entry
@@ -287,7 +821,7 @@ impl App {
                 Line::from(vec![
                     Span::styled("Time: ", Style::default().add_modifier(Modifier::BOLD)),
                     Span::styled(
-                        entry.timestamp.format("%Y-%m-%d %H:%M").to_string(),
+                        entry.started_at.format("%Y-%m-%d %H:%M").to_string(),
                         Style::default().fg(Color::Yellow),
                     ),
                 ]),
@@ -306,36 +840,18 @@ impl App {
                 ]),
             ];
 
-            // Add elapsed time if done
-            if let Some(Some(done_time_str)) = entry.tags.get("done") {
-                // Parse done timestamp
-                if let Ok(done_time) =
-                    chrono::NaiveDateTime::parse_from_str(done_time_str, "%Y-%m-%d %H:%M")
-                {
-                    let done_local = Local
-                        .from_local_datetime(&done_time)
-                        .single()
-                        .unwrap_or_else(Local::now);
-                    let duration = done_local.timestamp() - entry.timestamp.timestamp();
-                    if duration > 0 {
-                        let elapsed = Duration::seconds(duration);
-                        let hours = elapsed.num_hours();
-                        let minutes = (elapsed.num_minutes() % 60) as u32;
-                        let seconds = (elapsed.num_seconds() % 60) as u32;
-
-                        text.push(Line::from(""));
-                        text.push(Line::from(vec![
-                            Span::styled(
-                                "Elapsed Time: ",
-                                Style::default().add_modifier(Modifier::BOLD),
-                            ),
-                            Span::styled(
-                                format!("{hours:02}:{minutes:02}:{seconds:02}"),
-                                Style::default().fg(Color::Cyan),
-                            ),
-                        ]));
-                    }
-                }
+            // Add elapsed time, counting up while the entry runs
+            if let Some(elapsed) = running_time(entry, Local::now())
+                && elapsed.num_seconds() > 0
+            {
+                text.push(Line::from(""));
+                text.push(Line::from(vec![
+                    Span::styled(
+                        "Elapsed Time: ",
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(format_elapsed(elapsed), elapsed_style(entry)),
+                ]));
             }
 
             // Add tags
@@ -558,6 +1074,72 @@ impl App {
         frame.render_widget(help, chunks[5]);
     }
 
+    /// Render the form for a new entry
+    fn render_new_entry_mode(&mut self, frame: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(0),
+                Constraint::Length(3),
+            ])
+            .split(frame.area());
+
+        let title = Paragraph::new(if self.new_entry_form.finish_last {
+            "Finish Current Entry and Start a New One"
+        } else {
+            "New Entry"
+        })
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(title, chunks[0]);
+
+        let focus = self.new_entry_form.focus;
+        for (i, field) in self.new_entry_form.fields.iter_mut().enumerate() {
+            let border = if i == focus {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            };
+            field.set_style(Style::default().fg(Color::White));
+            field.set_cursor_style(if i == focus {
+                Style::default().bg(Color::White).fg(Color::Black)
+            } else {
+                Style::default()
+            });
+            field.set_cursor_line_style(Style::default());
+            field.set_block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border)
+                    .title(NEW_ENTRY_FIELDS[i]),
+            );
+            frame.render_widget(&*field, chunks[i + 1]);
+        }
+
+        let help_text = match &self.error {
+            Some(error) => format!("Error: {error}"),
+            None => "Ctrl+S: save | Esc: cancel | Tab/Shift+Tab: switch fields".to_string(),
+        };
+        let help = Paragraph::new(help_text)
+            .style(Style::default().fg(if self.error.is_some() {
+                Color::Red
+            } else {
+                Color::Gray
+            }))
+            .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(help, chunks[6]);
+    }
+
     /// Reads the crossterm events and updates the state of [`App`].
     ///
     /// If your application needs to perform work in between handling events, you can use the
@@ -642,6 +1224,119 @@ impl App {
             return;
         }
 
+        // Handle new entry form keys
+        if self.mode == AppMode::NewEntry {
+            let form = &mut self.new_entry_form;
+            match (key.modifiers, key.code) {
+                (_, KeyCode::Esc) => {
+                    self.mode = AppMode::Normal;
+                    self.error = None;
+                }
+                (KeyModifiers::CONTROL, KeyCode::Char('s') | KeyCode::Char('S')) => {
+                    self.save_new_entry();
+                }
+                (_, KeyCode::Tab) => form.focus = (form.focus + 1) % NEW_ENTRY_FIELDS.len(),
+                (_, KeyCode::BackTab) => {
+                    form.focus = (form.focus + NEW_ENTRY_FIELDS.len() - 1) % NEW_ENTRY_FIELDS.len();
+                }
+                // Only the note takes more than one line
+                (_, KeyCode::Enter) if form.focus + 1 < NEW_ENTRY_FIELDS.len() => {
+                    form.focus += 1;
+                }
+                _ => {
+                    form.fields[form.focus].input(Input::from(key));
+                }
+            }
+            return;
+        }
+
+        // Handle search keys: the results follow the query as it is typed
+        if self.mode == AppMode::Search {
+            match key.code {
+                KeyCode::Esc => {
+                    self.filter.search = None;
+                    self.mode = AppMode::Normal;
+                }
+                KeyCode::Enter => self.mode = AppMode::Normal,
+                _ => {
+                    self.search_textarea.input(Input::from(key));
+                    let query = self.search_textarea.lines().join("");
+                    self.filter.search = (!query.trim().is_empty()).then_some(query);
+                }
+            }
+            self.apply_filter();
+            return;
+        }
+
+        // Handle tag filter keys
+        if self.mode == AppMode::TagFilter {
+            match key.code {
+                KeyCode::Esc => self.mode = AppMode::Normal,
+                KeyCode::Enter => {
+                    let tag = self.tag_textarea.lines().join("").trim().to_string();
+                    if !tag.is_empty() {
+                        self.filter.tags.push(tag);
+                    }
+                    self.mode = AppMode::Normal;
+                }
+                _ => {
+                    self.tag_textarea.input(Input::from(key));
+                }
+            }
+            self.apply_filter();
+            return;
+        }
+
+        // Handle the tags or section of a bulk action
+        if self.mode == AppMode::BulkInput {
+            match key.code {
+                KeyCode::Esc => self.mode = AppMode::Normal,
+                KeyCode::Enter => {
+                    self.mode = AppMode::Normal;
+                    if let Some(action) = self.bulk_input_action() {
+                        self.apply_bulk(action);
+                    }
+                }
+                _ => {
+                    self.bulk_textarea.input(Input::from(key));
+                }
+            }
+            return;
+        }
+
+        if self.mode == AppMode::ConfirmDelete {
+            self.mode = AppMode::Normal;
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.apply_bulk(BulkAction::Delete);
+            }
+            return;
+        }
+
+        // Handle the calendar and timeline keys
+        if self.screen != Screen::List {
+            match (key.modifiers, key.code) {
+                (_, KeyCode::Char('q'))
+                | (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => self.quit(),
+                (_, KeyCode::Esc | KeyCode::Char('1')) => self.screen = Screen::List,
+                (_, KeyCode::Char('2')) => self.switch_screen(Screen::Calendar),
+                (_, KeyCode::Char('3')) => self.switch_screen(Screen::Timeline),
+                (_, KeyCode::Char('r')) => self.reload(),
+                (_, KeyCode::Enter) if self.screen == Screen::Calendar => {
+                    self.switch_screen(Screen::Timeline);
+                }
+                (_, code) => {
+                    let moved = match self.screen {
+                        Screen::Calendar => self.calendar.handle_key(code),
+                        _ => self.timeline.handle_key(code),
+                    };
+                    if moved {
+                        self.load_screen();
+                    }
+                }
+            }
+            return;
+        }
+
         // Handle detail view keys separately
         if self.show_detail {
             match (key.modifiers, key.code) {
@@ -671,60 +1366,104 @@ impl App {
         }
 
         // Handle list view keys
+        self.message = None;
         match (key.modifiers, key.code) {
+            (_, KeyCode::Esc)
+                // Unmark everything before Esc quits
+                if !self.marked.is_empty() => {
+                    self.marked.clear();
+                }
             (_, KeyCode::Esc | KeyCode::Char('q'))
             | (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => self.quit(),
-            (_, KeyCode::Up) => {
-                if self.selected > 0 {
+            (_, KeyCode::Char('v')) => self.toggle_mark(),
+            (_, KeyCode::Char(' '))
+                if !self.marked.is_empty() => {
+                    self.toggle_mark();
+                }
+            (_, KeyCode::Char('*')) => self.mark_all_matches(),
+            (_, KeyCode::Char('F')) => self.apply_bulk(BulkAction::Finish),
+            (_, KeyCode::Char('C')) => self.apply_bulk(BulkAction::Cancel),
+            (_, KeyCode::Char('A')) => self.apply_bulk(BulkAction::Archive),
+            (_, KeyCode::Char('!')) => self.apply_bulk(BulkAction::Flag),
+            (_, KeyCode::Char('+')) => self.enter_bulk_input(BulkPrompt::Tag),
+            (_, KeyCode::Char('-')) => self.enter_bulk_input(BulkPrompt::Untag),
+            (_, KeyCode::Char('M')) => self.enter_bulk_input(BulkPrompt::Move),
+            (_, KeyCode::Char('2')) => self.switch_screen(Screen::Calendar),
+            (_, KeyCode::Char('3')) => self.switch_screen(Screen::Timeline),
+            (_, KeyCode::Char('d'))
+                // Deleting marked entries asks first
+                if !self.marked.is_empty() => {
+                    self.mode = AppMode::ConfirmDelete;
+                }
+            (_, KeyCode::Up | KeyCode::Char('k'))
+                if self.selected > 0 => {
                     self.selected -= 1;
                 }
+            (_, KeyCode::Down | KeyCode::Char('j')) => self.move_selection(self.selected + 1),
+            (_, KeyCode::PageUp) => self.selected = self.selected.saturating_sub(PAGE_STEP),
+            (_, KeyCode::PageDown) => self.move_selection(self.selected + PAGE_STEP),
+            (_, KeyCode::Home) => self.selected = 0,
+            (_, KeyCode::End) => self.move_selection(self.total.saturating_sub(1)),
+            (_, KeyCode::Char('/')) => {
+                self.search_textarea =
+                    TextArea::new(vec![self.filter.search.clone().unwrap_or_default()]);
+                self.search_textarea.move_cursor(tui_textarea::CursorMove::End);
+                self.mode = AppMode::Search;
             }
-            (_, KeyCode::Down) => {
-                if self.selected < self.entries.len().saturating_sub(1) {
-                    self.selected += 1;
-                }
+            (_, KeyCode::Char('#')) => {
+                self.tag_textarea = TextArea::default();
+                self.mode = AppMode::TagFilter;
             }
-            (_, KeyCode::Char('r')) => {
-                self.load_entries();
-                if self.selected >= self.entries.len() && !self.entries.is_empty() {
-                    self.selected = self.entries.len() - 1;
+            (_, KeyCode::Backspace)
+                // Remove the last tag chip
+                if !self.filter.tags.is_empty() => {
+                    self.filter.tags.pop();
+                    self.apply_filter();
                 }
+            (_, KeyCode::Char('x')) => {
+                self.filter.search = None;
+                self.filter.tags.clear();
+                self.apply_filter();
             }
-            (_, KeyCode::Enter) => {
-                if !self.entries.is_empty() {
+            (_, KeyCode::Tab) => self.switch_section(true),
+            (_, KeyCode::BackTab) => self.switch_section(false),
+            (_, KeyCode::Char('r')) => self.reload(),
+            (_, KeyCode::Enter)
+                if !self.entries.is_empty() => {
                     self.show_detail = true;
                 }
-            }
-            (_, KeyCode::Char('d')) => {
+            (_, KeyCode::Char('d'))
                 // Delete selected entry
-                if self.selected < self.entries.len() {
+                if self.selected < self.entries.len() => {
                     self.delete_entry();
                 }
-            }
-            (_, KeyCode::Char(' ')) => {
+            (_, KeyCode::Char(' '))
                 // Toggle @done status
-                if self.selected < self.entries.len() {
+                if self.selected < self.entries.len() => {
                     self.toggle_done();
                 }
-            }
-            (_, KeyCode::Char('e')) => {
+            (_, KeyCode::Char('e'))
                 // Edit selected entry
-                if self.selected < self.entries.len() {
+                if self.selected < self.entries.len() => {
                     self.enter_edit_mode();
                 }
-            }
-            (_, KeyCode::Char('n')) => {
+            (_, KeyCode::Char('n'))
                 // Edit note of selected entry
-                if self.selected < self.entries.len() {
+                if self.selected < self.entries.len() => {
                     self.enter_note_mode();
                 }
-            }
-            (_, KeyCode::Char('t')) => {
+            (_, KeyCode::Char('t'))
                 // Edit timestamp of selected entry
-                if self.selected < self.entries.len() {
+                if self.selected < self.entries.len() => {
                     self.enter_timestamp_mode();
                 }
-            }
+            (_, KeyCode::Char('a')) => self.enter_new_entry_mode(false),
+            (_, KeyCode::Char('f')) => self.enter_new_entry_mode(true),
+            (_, KeyCode::Char('g'))
+                // Start the selected entry again
+                if self.selected < self.entries.len() => {
+                    self.resume_entry();
+                }
             _ => {}
         }
     }
@@ -754,6 +1493,106 @@ impl App {
         }
     }
 
+    /// Mark or unmark the selected entry and move to the next one
+    fn toggle_mark(&mut self) {
+        if let Some(entry) = self.entries.get(self.selected) {
+            if !self.marked.remove(&entry.uuid) {
+                self.marked.insert(entry.uuid);
+            }
+            self.move_selection(self.selected + 1);
+        }
+    }
+
+    /// Mark every entry matching the filter, loaded or not; when they are
+    /// all marked already, unmark them
+    fn mark_all_matches(&mut self) {
+        match EntryService::get_tui_entries(&self.filter, usize::MAX) {
+            Ok((entries, _)) => {
+                let uuids: HashSet<uuid::Uuid> = entries.iter().map(|entry| entry.uuid).collect();
+                if uuids.is_subset(&self.marked) {
+                    self.marked.retain(|uuid| !uuids.contains(uuid));
+                } else {
+                    self.marked.extend(uuids);
+                }
+            }
+            Err(e) => self.error = Some(format!("Failed to load entries: {e}")),
+        }
+    }
+
+    /// The marked entries, or the selected one when none are marked
+    fn targets(&self) -> Vec<uuid::Uuid> {
+        if self.marked.is_empty() {
+            self.entries
+                .get(self.selected)
+                .map(|entry| entry.uuid)
+                .into_iter()
+                .collect()
+        } else {
+            self.marked.iter().copied().collect()
+        }
+    }
+
+    fn enter_bulk_input(&mut self, prompt: BulkPrompt) {
+        if self.targets().is_empty() {
+            return;
+        }
+        self.bulk_prompt = prompt;
+        self.bulk_textarea = TextArea::default();
+        self.mode = AppMode::BulkInput;
+    }
+
+    /// The action for what was typed at the bulk prompt, if anything was
+    fn bulk_input_action(&self) -> Option<BulkAction> {
+        let input = self.bulk_textarea.lines().join("").trim().to_string();
+        if input.is_empty() {
+            return None;
+        }
+        // Tags are typed as for the new entry form: with or without @,
+        // separated by commas or spaces
+        let tags: Vec<String> = input
+            .split([',', ' '])
+            .map(|tag| tag.trim().trim_start_matches('@'))
+            .filter(|tag| !tag.is_empty())
+            .map(|tag| format!("@{tag}"))
+            .collect();
+        Some(match self.bulk_prompt {
+            BulkPrompt::Tag => BulkAction::Tag(parse_title(&tags.join(" ")).1),
+            BulkPrompt::Untag => {
+                BulkAction::Untag(parse_title(&tags.join(" ")).1.into_keys().collect())
+            }
+            BulkPrompt::Move => BulkAction::Move(input),
+        })
+    }
+
+    /// Apply a bulk action to the marked entries, or the selected one, in a
+    /// single save, then unmark them
+    fn apply_bulk(&mut self, action: BulkAction) {
+        let targets = self.targets();
+        if targets.is_empty() {
+            return;
+        }
+        let done = match &action {
+            BulkAction::Finish => "Finished",
+            BulkAction::Cancel => "Cancelled",
+            BulkAction::Tag(_) => "Tagged",
+            BulkAction::Untag(_) => "Untagged",
+            BulkAction::Move(_) => "Moved",
+            BulkAction::Archive => "Archived",
+            BulkAction::Delete => "Deleted",
+            BulkAction::Flag => "Toggled the flag on",
+        };
+        match EntryService::bulk_update(&targets, &action) {
+            Ok(changed) => {
+                self.marked.clear();
+                self.message = Some(format!("{done} {}", entries_label(changed)));
+                self.reload();
+            }
+            Err(e) => {
+                self.error = Some(format!("Failed to {} entries: {e}", action.verb()));
+            }
+        }
+    }
+
     /// Toggle the @done status of the selected entry
     fn toggle_done(&mut self) {
         if let Some(entry) = self.entries.get(self.selected) {
@@ -771,6 +1610,65 @@ impl App {
         }
     }
 
+    /// Open the new entry form, in the section being shown or `Currently`
+    fn enter_new_entry_mode(&mut self, finish_last: bool) {
+        let section = self
+            .filter
+            .sections
+            .first()
+            .map_or("Currently", String::as_str);
+        self.new_entry_form = NewEntryForm::new(section, finish_last);
+        self.error = None;
+        self.mode = AppMode::NewEntry;
+    }
+
+    /// Add the entry in the form, autotagged as `now` would
+    fn save_new_entry(&mut self) {
+        let mut entry = match self.new_entry_form.entry() {
+            Ok(entry) => entry,
+            Err(e) => {
+                self.error = Some(e);
+                return;
+            }
+        };
+        let finish_last = self.new_entry_form.finish_last;
+        let added = autotag(&mut entry)
+            .and_then(|()| EntryService::add_tui_entry(entry.clone(), finish_last));
+        match added {
+            Ok(_) => {
+                self.mode = AppMode::Normal;
+                self.show_added(&entry.uuid);
+            }
+            Err(e) => self.error = Some(format!("Failed to add entry: {e}")),
+        }
+    }
+
+    /// Start a copy of the selected entry now, as `again` does
+    fn resume_entry(&mut self) {
+        if let Some(entry) = self.entries.get(self.selected) {
+            let mut resumed = EntryService::resumed_entry(entry, None, Local::now());
+            let added = autotag(&mut resumed)
+                .and_then(|()| EntryService::add_resumed_tui_entry(resumed.clone()));
+            match added {
+                Ok(()) => self.show_added(&resumed.uuid),
+                Err(e) => self.error = Some(format!("Failed to resume entry: {e}")),
+            }
+        }
+    }
+
+    /// Reload after adding an entry and select it
+    fn show_added(&mut self, uuid: &uuid::Uuid) {
+        self.load_entries();
+        self.select_uuid(uuid);
+    }
+
+    /// Move the selection to the entry with this id, if it is listed
+    fn select_uuid(&mut self, uuid: &uuid::Uuid) {
+        if let Some(index) = self.entries.iter().position(|entry| &entry.uuid == uuid) {
+            self.selected = index;
+        }
+    }
+
     /// Enter edit mode for the selected entry
     fn enter_edit_mode(&mut self) {
         if let Some(entry) = self.entries.get(self.selected) {
@@ -863,17 +1761,16 @@ impl App {
     fn enter_timestamp_mode(&mut self) {
         if let Some(entry) = self.entries.get(self.selected) {
             // Initialize timestamp textarea with current timestamp
-            let timestamp_str = entry.timestamp.format("%Y-%m-%d %H:%M").to_string();
+            let timestamp_str = entry.started_at.format("%Y-%m-%d %H:%M").to_string();
             self.timestamp_textarea = TextArea::new(vec![timestamp_str]);
             self.timestamp_textarea
                 .move_cursor(tui_textarea::CursorMove::End);
 
             // Initialize done timestamp textarea
-            let done_str = if let Some(Some(done_time)) = entry.tags.get("done") {
-                done_time.clone()
-            } else {
-                "".to_string()
-            };
+            let done_str = entry
+                .finished_at
+                .map(|finished_at| finished_at.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
             self.done_timestamp_textarea = TextArea::new(vec![done_str]);
 
             // Store original done state
@@ -946,9 +1843,9 @@ impl App {
             let new_done = if new_done_str.is_empty() {
                 None
             } else {
-                match chrono::NaiveDateTime::parse_from_str(&new_done_str, "%Y-%m-%d %H:%M") {
-                    Ok(_) => Some(new_done_str),
-                    Err(_) => {
+                match parse_time(&new_done_str) {
+                    Some(finished_at) => Some(finished_at),
+                    None => {
                         self.error =
                             Some("Invalid done timestamp format. Use YYYY-MM-DD HH:MM".to_string());
                         return;
@@ -975,3 +1872,43 @@ impl App {
         }
     }
 }
+
+/// Time spent on the entry: its duration once finished, or the time so far
+/// while it runs. Entries marked done without a time have none.
+fn running_time(entry: &Entry, now: DateTime<Local>) -> Option<Duration> {
+    entry
+        .duration()
+        .or_else(|| entry.is_running().then(|| entry.elapsed(now)))
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let hours = elapsed.num_hours();
+    let minutes = elapsed.num_minutes() % 60;
+    let seconds = elapsed.num_seconds() % 60;
+    format!("{hours:02}:{minutes:02}:{seconds:02}")
+}
+
+/// Running timers stand out from finished durations
+fn elapsed_style(entry: &Entry) -> Style {
+    if entry.is_running() {
+        Style::default()
+            .fg(Color::LightGreen)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Cyan)
+    }
+}
+
+/// Tag a new entry as `now` would
+fn autotag(entry: &mut Entry) -> Result<()> {
+    Autotagger::new(&Config::try_load()?)?.tag_new_entry(entry);
+    Ok(())
+}
+
+fn entries_label(count: usize) -> String {
+    if count == 1 {
+        "1 entry".to_string()
+    } else {
+        format!("{count} entries")
+    }
+}
@@ -0,0 +1,263 @@
+use super::{format_elapsed, running_time};
+use crate::display::format_duration;
+use crate::models::Entry;
+use crate::report::period;
+use chrono::{DateTime, Datelike, Days, Duration, Local, Months, NaiveDate, Weekday};
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+/// A month with the time tracked on each day, and the entries of the
+/// selected day beside it
+#[derive(Debug)]
+pub(super) struct CalendarView {
+    /// The selected day; the month shown is its month
+    pub(super) selected: NaiveDate,
+    /// Entries started in the month shown
+    entries: Vec<Entry>,
+}
+
+impl CalendarView {
+    pub(super) fn new(selected: NaiveDate) -> Self {
+        Self {
+            selected,
+            entries: Vec::new(),
+        }
+    }
+
+    /// The month shown, to load entries from
+    pub(super) fn range(&self) -> (DateTime<Local>, DateTime<Local>) {
+        let first = self.first_day();
+        period(first, first + Months::new(1))
+    }
+
+    pub(super) fn set_entries(&mut self, mut entries: Vec<Entry>) {
+        entries.sort_by_key(|entry| entry.started_at);
+        self.entries = entries;
+    }
+
+    fn first_day(&self) -> NaiveDate {
+        self.selected.with_day(1).unwrap_or(self.selected)
+    }
+
+    fn day_entries(&self, day: NaiveDate) -> impl Iterator<Item = &Entry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.started_at.date_naive() == day)
+    }
+
+    /// Time tracked by the entries started on `day`
+    fn tracked(&self, day: NaiveDate, now: DateTime<Local>) -> Duration {
+        self.day_entries(day)
+            .filter_map(|entry| running_time(entry, now))
+            .sum()
+    }
+
+    /// Move the selected day; returns true when it moved to another month,
+    /// so the entries have to be loaded again
+    pub(super) fn handle_key(&mut self, code: KeyCode) -> bool {
+        let month = self.first_day();
+        let selected = match code {
+            KeyCode::Left | KeyCode::Char('h') => self.selected.checked_sub_days(Days::new(1)),
+            KeyCode::Right | KeyCode::Char('l') => self.selected.checked_add_days(Days::new(1)),
+            KeyCode::Up | KeyCode::Char('k') => self.selected.checked_sub_days(Days::new(7)),
+            KeyCode::Down | KeyCode::Char('j') => self.selected.checked_add_days(Days::new(7)),
+            KeyCode::PageUp => self.selected.checked_sub_months(Months::new(1)),
+            KeyCode::PageDown => self.selected.checked_add_months(Months::new(1)),
+            KeyCode::Char('t') | KeyCode::Home => Some(Local::now().date_naive()),
+            _ => None,
+        };
+        if let Some(selected) = selected {
+            self.selected = selected;
+        }
+        self.first_day() != month
+    }
+
+    pub(super) fn render(&self, frame: &mut Frame, area: Rect, now: DateTime<Local>) {
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(area);
+        self.render_month(frame, halves[0], now);
+        self.render_day(frame, halves[1], now);
+    }
+
+    fn render_month(&self, frame: &mut Frame, area: Rect, now: DateTime<Local>) {
+        let first = self.first_day();
+        let total: Duration = self
+            .entries
+            .iter()
+            .filter_map(|entry| running_time(entry, now))
+            .sum();
+        let block = Block::default().borders(Borders::ALL).title(format!(
+            "{} - {} tracked",
+            first.format("%B %Y"),
+            format_duration(&total)
+        ));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let weeks = weeks(first);
+        let mut constraints = vec![Constraint::Length(1)];
+        constraints.extend(
+            weeks
+                .iter()
+                .map(|_| Constraint::Ratio(1, weeks.len() as u32)),
+        );
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(inner);
+        let columns = |row: Rect| {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Ratio(1, 7); 7])
+                .split(row)
+        };
+
+        let header = columns(rows[0]);
+        for (i, day) in weeks[0].iter().enumerate() {
+            frame.render_widget(
+                Paragraph::new(day.format("%a").to_string())
+                    .alignment(Alignment::Center)
+                    .style(Style::default().add_modifier(Modifier::BOLD)),
+                header[i],
+            );
+        }
+
+        let today = now.date_naive();
+        for (week, row) in weeks.iter().zip(rows.iter().skip(1)) {
+            for (day, cell) in week.iter().zip(columns(*row).iter()) {
+                let tracked = self.tracked(*day, now);
+                let mut style = if day.month() != first.month() {
+                    Style::default().fg(Color::DarkGray)
+                } else if tracked > Duration::zero() {
+                    Style::default().fg(Color::Green)
+                } else {
+                    Style::default()
+                };
+                if *day == today {
+                    style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                }
+                if *day == self.selected {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+
+                let mut lines = vec![Line::from(day.day().to_string())];
+                if tracked > Duration::zero() {
+                    lines.push(Line::from(format_duration(&tracked)));
+                }
+                frame.render_widget(
+                    Paragraph::new(lines)
+                        .alignment(Alignment::Center)
+                        .style(style),
+                    *cell,
+                );
+            }
+        }
+    }
+
+    /// The entries of the selected day
+    fn render_day(&self, frame: &mut Frame, area: Rect, now: DateTime<Local>) {
+        let items: Vec<ListItem> = self
+            .day_entries(self.selected)
+            .map(|entry| {
+                let end = match entry.finished_at {
+                    Some(end) => end.format("%H:%M").to_string(),
+                    None if entry.is_running() => "now".to_string(),
+                    None => "-".to_string(),
+                };
+                let mut spans = vec![
+                    Span::styled(
+                        format!("{}-{end} ", entry.started_at.format("%H:%M")),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::raw(entry.description.as_str()),
+                ];
+                if let Some(elapsed) = running_time(entry, now) {
+                    spans.push(Span::styled(
+                        format!(" {}", format_elapsed(elapsed)),
+                        Style::default().fg(Color::Cyan),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let title = format!(
+            "{} - {}",
+            self.selected.format("%A, %B %-d"),
+            format_elapsed(self.tracked(self.selected, now))
+        );
+        let list = if items.is_empty() {
+            List::new(vec![
+                ListItem::new("No entries").style(Style::default().fg(Color::Gray)),
+            ])
+        } else {
+            List::new(items)
+        };
+        frame.render_widget(
+            list.block(Block::default().borders(Borders::ALL).title(title)),
+            area,
+        );
+    }
+}
+
+/// The weeks, Monday to Sunday, that the month starting on `first` spans
+fn weeks(first: NaiveDate) -> Vec<[NaiveDate; 7]> {
+    let next_month = first + Months::new(1);
+    let mut monday = first.week(Weekday::Mon).first_day();
+    let mut weeks = Vec::new();
+    while monday < next_month {
+        let mut week = [monday; 7];
+        for (offset, day) in week.iter_mut().enumerate() {
+            *day = monday + Days::new(offset as u64);
+        }
+        weeks.push(week);
+        monday = monday + Days::new(7);
+    }
+    weeks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::utils::timed_entry;
+
+    #[test]
+    fn test_month_weeks_and_tracked_days() {
+        let first = NaiveDate::from_ymd_opt(2025, 7, 1).unwrap();
+        let weeks = weeks(first);
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(weeks[0][0], NaiveDate::from_ymd_opt(2025, 6, 30).unwrap());
+        assert_eq!(weeks[4][6], NaiveDate::from_ymd_opt(2025, 8, 3).unwrap());
+
+        let mut calendar = CalendarView::new(NaiveDate::from_ymd_opt(2025, 7, 28).unwrap());
+        calendar.set_entries(vec![
+            timed_entry("Work", "2025-07-28 09:00", Some("2025-07-28 10:30"), &[]),
+            timed_entry("Work", "2025-07-28 11:00", Some("2025-07-28 11:45"), &[]),
+            timed_entry("Work", "2025-07-29 09:00", Some("2025-07-29 09:20"), &[]),
+        ]);
+
+        let now = Local::now();
+        assert_eq!(
+            calendar.tracked(calendar.selected, now),
+            Duration::minutes(135)
+        );
+        assert!(!calendar.handle_key(KeyCode::Right));
+        assert_eq!(
+            calendar.tracked(calendar.selected, now),
+            Duration::minutes(20)
+        );
+        // Three days on is August
+        for _ in 0..2 {
+            assert!(!calendar.handle_key(KeyCode::Right));
+        }
+        assert!(calendar.handle_key(KeyCode::Right));
+    }
+}
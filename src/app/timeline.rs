@@ -0,0 +1,340 @@
+use super::format_elapsed;
+use crate::display::format_duration;
+use crate::models::Entry;
+use crate::report::period;
+use chrono::{DateTime, Days, Duration, Local, NaiveDate, Timelike};
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Paragraph},
+};
+
+/// Colors of the entries, by lane
+const LANE_COLORS: [Color; 4] = [Color::Blue, Color::Magenta, Color::Cyan, Color::Green];
+
+/// Width of the hour labels on the left
+const AXIS_WIDTH: u16 = 6;
+
+/// One day drawn top to bottom, with an entry as a block as tall as it
+/// lasted. Entries going on at the same time are drawn side by side.
+#[derive(Debug)]
+pub(super) struct TimelineView {
+    pub(super) day: NaiveDate,
+    /// Entries started on the day or the day before, so that entries
+    /// running past midnight show too
+    entries: Vec<Entry>,
+}
+
+/// Where an entry is drawn
+#[derive(Debug, Clone, PartialEq)]
+struct Slot<'a> {
+    entry: &'a Entry,
+    /// Start and end, cut to the day
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+    lane: usize,
+    /// Whether it was going on at the same time as another entry
+    overlaps: bool,
+}
+
+impl TimelineView {
+    pub(super) fn new(day: NaiveDate) -> Self {
+        Self {
+            day,
+            entries: Vec::new(),
+        }
+    }
+
+    /// The period to load entries from
+    pub(super) fn range(&self) -> (DateTime<Local>, DateTime<Local>) {
+        let (_, end) = self.bounds();
+        let (start, _) = period(self.day - Days::new(1), self.day);
+        (start, end)
+    }
+
+    pub(super) fn set_entries(&mut self, entries: Vec<Entry>) {
+        self.entries = entries;
+    }
+
+    /// Midnight to midnight
+    fn bounds(&self) -> (DateTime<Local>, DateTime<Local>) {
+        let (start, end) = period(self.day, self.day + Days::new(1));
+        (start, end + Duration::seconds(1))
+    }
+
+    /// Change the day shown; returns true when it changed, so the entries
+    /// have to be loaded again
+    pub(super) fn handle_key(&mut self, code: KeyCode) -> bool {
+        let day = match code {
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::PageUp => self.day - Days::new(1),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::PageDown => self.day + Days::new(1),
+            KeyCode::Char('t') | KeyCode::Home => Local::now().date_naive(),
+            _ => return false,
+        };
+        let changed = day != self.day;
+        self.day = day;
+        changed
+    }
+
+    pub(super) fn render(&self, frame: &mut Frame, area: Rect, now: DateTime<Local>) {
+        let (day_start, day_end) = self.bounds();
+        let slots = slots(&self.entries, day_start, day_end, now);
+        let gaps = gaps(&slots);
+
+        let tracked: Duration = slots.iter().map(|slot| slot.end - slot.start).sum();
+        let idle: Duration = gaps.iter().map(|(start, end)| *end - *start).sum();
+        let overlaps = slots.iter().filter(|slot| slot.overlaps).count();
+        let mut title = format!(
+            "{} - {} tracked",
+            self.day.format("%A, %B %-d, %Y"),
+            format_elapsed(tracked)
+        );
+        if !gaps.is_empty() {
+            title.push_str(&format!(", {} in gaps", format_duration(&idle)));
+        }
+        if overlaps > 0 {
+            title.push_str(&format!(", {overlaps} overlapping"));
+        }
+        let block = Block::default().borders(Borders::ALL).title(title);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let (Some(first), Some(last)) = (
+            slots.iter().map(|slot| slot.start).min(),
+            slots.iter().map(|slot| slot.end).max(),
+        ) else {
+            frame.render_widget(
+                Paragraph::new("No entries on this day").style(Style::default().fg(Color::Gray)),
+                inner,
+            );
+            return;
+        };
+        if inner.height == 0 || inner.width <= AXIS_WIDTH {
+            return;
+        }
+
+        // Whole hours around the entries
+        let from = first
+            .with_minute(0)
+            .and_then(|t| t.with_second(0))
+            .and_then(|t| t.with_nanosecond(0))
+            .unwrap_or(first);
+        let to = (from + Duration::hours((last - from).num_minutes() / 60 + 1)).min(day_end);
+        let rows = f64::from(inner.height);
+        let span = (to - from).num_seconds().max(1) as f64;
+        let row = |time: DateTime<Local>| -> u16 {
+            let offset = (time - from).num_seconds() as f64 / span * rows;
+            (offset.round() as u16).min(inner.height)
+        };
+
+        // Hour labels, skipping those that would share a row
+        let mut last_row = None;
+        let mut hour = from;
+        while hour < to {
+            let y = row(hour);
+            if y < inner.height && last_row != Some(y) {
+                frame.render_widget(
+                    Paragraph::new(hour.format("%H:%M").to_string())
+                        .style(Style::default().fg(Color::Yellow)),
+                    Rect::new(inner.x, inner.y + y, AXIS_WIDTH, 1),
+                );
+                last_row = Some(y);
+            }
+            hour += Duration::hours(1);
+        }
+
+        let lanes = slots.iter().map(|slot| slot.lane + 1).max().unwrap_or(1) as u16;
+        let lane_width = ((inner.width - AXIS_WIDTH) / lanes).max(1);
+        let content_x = inner.x + AXIS_WIDTH;
+
+        for (start, end) in &gaps {
+            let (top, bottom) = (row(*start), row(*end));
+            if bottom > top {
+                let label = format!("┄ {} gap ┄", format_duration(&(*end - *start)));
+                frame.render_widget(
+                    Paragraph::new(label).style(Style::default().fg(Color::DarkGray)),
+                    Rect::new(content_x, inner.y + top, inner.width - AXIS_WIDTH, 1),
+                );
+            }
+        }
+
+        for slot in &slots {
+            let top = row(slot.start).min(inner.height - 1);
+            let height = row(slot.end).saturating_sub(top).max(1);
+            let rect = Rect::new(
+                content_x + slot.lane as u16 * lane_width,
+                inner.y + top,
+                lane_width,
+                height,
+            );
+            let color = LANE_COLORS[slot.lane % LANE_COLORS.len()];
+            let border = if slot.overlaps {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(color)
+            };
+
+            let mut lines = vec![Line::from(vec![
+                Span::styled(
+                    format!(
+                        "{}-{} ",
+                        slot.start.format("%H:%M"),
+                        slot.end.format("%H:%M")
+                    ),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(slot.entry.description.as_str()),
+                Span::raw(format!(" ({})", format_duration(&(slot.end - slot.start)))),
+            ])];
+            let tags: Vec<String> = slot
+                .entry
+                .tags
+                .keys()
+                .filter(|tag| *tag != "done")
+                .map(|tag| format!("@{tag}"))
+                .collect();
+            if !tags.is_empty() {
+                lines.push(Line::from(tags.join(" ")));
+            }
+            if slot.overlaps {
+                lines.push(Line::styled("overlaps", border));
+            }
+
+            frame.render_widget(
+                Paragraph::new(lines).block(
+                    Block::default()
+                        .borders(Borders::LEFT)
+                        .border_type(BorderType::Thick)
+                        .border_style(border),
+                ),
+                rect,
+            );
+        }
+    }
+}
+
+/// The entries that took time on the day, cut to the day and given lanes so
+/// that entries going on at the same time sit side by side. Running entries
+/// last until `now`; entries done without a time are left out.
+fn slots(
+    entries: &[Entry],
+    day_start: DateTime<Local>,
+    day_end: DateTime<Local>,
+    now: DateTime<Local>,
+) -> Vec<Slot<'_>> {
+    let mut slots: Vec<Slot> = entries
+        .iter()
+        .filter_map(|entry| {
+            let end = match entry.finished_at {
+                Some(end) => end,
+                None if entry.is_running() => now,
+                None => return None,
+            };
+            let (start, end) = (entry.started_at.max(day_start), end.min(day_end));
+            (start < end).then_some(Slot {
+                entry,
+                start,
+                end,
+                lane: 0,
+                overlaps: false,
+            })
+        })
+        .collect();
+    slots.sort_by_key(|slot| (slot.start, slot.end));
+
+    // Each entry goes in the first lane free by its start
+    let mut lane_ends: Vec<DateTime<Local>> = Vec::new();
+    for slot in &mut slots {
+        match lane_ends.iter().position(|end| *end <= slot.start) {
+            Some(lane) => {
+                slot.lane = lane;
+                lane_ends[lane] = slot.end;
+            }
+            None => {
+                slot.lane = lane_ends.len();
+                lane_ends.push(slot.end);
+            }
+        }
+    }
+
+    for i in 0..slots.len() {
+        slots[i].overlaps = slots
+            .iter()
+            .enumerate()
+            .any(|(j, other)| i != j && slots[i].start < other.end && other.start < slots[i].end);
+    }
+    slots
+}
+
+/// The stretches between the first and the last entry where nothing was tracked
+fn gaps(slots: &[Slot]) -> Vec<(DateTime<Local>, DateTime<Local>)> {
+    let mut gaps = Vec::new();
+    let mut covered: Option<DateTime<Local>> = None;
+    for slot in slots {
+        if let Some(end) = covered
+            && slot.start > end
+        {
+            gaps.push((end, slot.start));
+        }
+        covered = Some(covered.map_or(slot.end, |end| end.max(slot.end)));
+    }
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::entry::parse_time;
+    use crate::test_utils::utils::timed_entry;
+
+    #[test]
+    fn test_lanes_overlaps_and_gaps() {
+        let entries = vec![
+            timed_entry("Work", "2025-07-27 23:00", Some("2025-07-28 01:00"), &[]),
+            timed_entry("Work", "2025-07-28 09:00", Some("2025-07-28 10:00"), &[]),
+            timed_entry("Work", "2025-07-28 09:30", Some("2025-07-28 10:30"), &[]),
+            timed_entry("Work", "2025-07-28 10:00", Some("2025-07-28 11:00"), &[]),
+            timed_entry("Work", "2025-07-28 13:00", None, &[]),
+            timed_entry("Work", "2025-07-28 15:00", None, &["done"]),
+        ];
+        let day_start = parse_time("2025-07-28 00:00").unwrap();
+        let day_end = parse_time("2025-07-29 00:00").unwrap();
+        let now = parse_time("2025-07-28 14:00").unwrap();
+
+        let slots = slots(&entries, day_start, day_end, now);
+        assert_eq!(slots.len(), 5);
+        assert_eq!(
+            slots
+                .iter()
+                .map(|slot| (
+                    slot.start.format("%H:%M").to_string(),
+                    slot.lane,
+                    slot.overlaps
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("00:00".to_string(), 0, false),
+                ("09:00".to_string(), 0, true),
+                ("09:30".to_string(), 1, true),
+                ("10:00".to_string(), 0, true),
+                ("13:00".to_string(), 0, false),
+            ]
+        );
+        // The running entry lasts until now
+        assert_eq!(slots[4].end, now);
+
+        let gaps = gaps(&slots);
+        assert_eq!(
+            gaps.iter()
+                .map(|(start, end)| (start.format("%H:%M").to_string(), *end - *start))
+                .collect::<Vec<_>>(),
+            vec![
+                ("01:00".to_string(), Duration::hours(8)),
+                ("11:00".to_string(), Duration::hours(2)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use crate::models::Entry;
+use crate::storage::{Config, parse_title};
+use color_eyre::Result;
+use color_eyre::eyre::{WrapErr, eyre};
+use indexmap::IndexMap;
+use regex::Regex;
+
+/// Tags entries from the `autotag` and `default_tags` settings
+#[derive(Debug, Default)]
+pub struct Autotagger {
+    default_tags: IndexMap<String, Option<String>>,
+    /// Tag name and the whole-word pattern that adds it
+    words: Vec<(String, Regex)>,
+    transforms: Vec<Transform>,
+}
+
+#[derive(Debug)]
+struct Transform {
+    pattern: Regex,
+    replacement: String,
+    replace: bool,
+}
+
+impl Autotagger {
+    /// Compile the rules in `config`, failing on a malformed transform
+    pub fn new(config: &Config) -> Result<Self> {
+        let (_, default_tags) = parse_title(&tag_list(&config.default_tags));
+
+        let whitelist = config
+            .autotag
+            .whitelist
+            .iter()
+            .map(|word| (word.as_str(), word.as_str()));
+        let synonyms =
+            config.autotag.synonyms.iter().flat_map(|(tag, words)| {
+                words.iter().map(move |word| (tag.as_str(), word.as_str()))
+            });
+
+        let mut words = Vec::new();
+        for (tag, word) in whitelist.chain(synonyms) {
+            let tag = tag.trim().trim_start_matches('@');
+            let word = word.trim().trim_start_matches('@');
+            if tag.is_empty() || word.is_empty() {
+                continue;
+            }
+            let pattern = regex::escape(word)
+                .replace(r"\*", r"\w*")
+                .replace(r"\?", r"\w");
+            words.push((tag.to_string(), Regex::new(&format!(r"(?i)\b{pattern}\b"))?));
+        }
+
+        let transforms = config
+            .autotag
+            .transform
+            .iter()
+            .map(|rule| Transform::parse(rule))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            default_tags,
+            words,
+            transforms,
+        })
+    }
+
+    /// Add the default tags, then the autotags, to an entry that is being created.
+    /// Returns the names of the tags that were added.
+    pub fn tag_new_entry(&self, entry: &mut Entry) -> Vec<String> {
+        let mut added = Vec::new();
+        for (tag, value) in &self.default_tags {
+            if !entry.tags.contains_key(tag) {
+                entry.tags.insert(tag.clone(), value.clone());
+                added.push(tag.clone());
+            }
+        }
+        added.extend(self.tag_entry(entry));
+        added
+    }
+
+    /// Add tags for the whitelist and synonym words in the entry's description,
+    /// then run the transforms over its tags. Returns the names of the tags that were added.
+    pub fn tag_entry(&self, entry: &mut Entry) -> Vec<String> {
+        let mut added = Vec::new();
+
+        for (tag, pattern) in &self.words {
+            if !entry.tags.contains_key(tag) && pattern.is_match(&entry.description) {
+                entry.tags.insert(tag.clone(), None);
+                added.push(tag.clone());
+            }
+        }
+
+        for transform in &self.transforms {
+            let matching: Vec<String> = entry
+                .tags
+                .keys()
+                .filter(|tag| *tag != "done" && transform.pattern.is_match(tag))
+                .cloned()
+                .collect();
+
+            for tag in matching {
+                let replacement = transform
+                    .pattern
+                    .replace(&tag, transform.replacement.as_str());
+                let (_, new_tags) = parse_title(&tag_list(replacement.split([' ', ','])));
+
+                if transform.replace {
+                    entry.tags.shift_remove(&tag);
+                }
+                for (new_tag, value) in new_tags {
+                    if !entry.tags.contains_key(&new_tag) {
+                        entry.tags.insert(new_tag.clone(), value);
+                        added.push(new_tag);
+                    }
+                }
+            }
+        }
+
+        added
+    }
+}
+
+impl Transform {
+    /// Parse a doing-style `pattern:replacement[/r]` rule. The pattern must match
+    /// a whole tag and `$1`-style groups may be used in the replacement.
+    fn parse(rule: &str) -> Result<Self> {
+        let (pattern, replacement) = rule.rsplit_once(':').ok_or_else(|| {
+            eyre!("invalid autotag transform '{rule}': expected pattern:replacement")
+        })?;
+        let (replacement, replace) = match replacement.strip_suffix("/r") {
+            Some(replacement) => (replacement, true),
+            None => (replacement, false),
+        };
+
+        let pattern = Regex::new(&format!("(?i)^(?:{})$", pattern.trim_start_matches('@')))
+            .wrap_err_with(|| format!("invalid autotag transform '{rule}'"))?;
+
+        Ok(Self {
+            pattern,
+            replacement: replacement.trim().to_string(),
+            replace,
+        })
+    }
+}
+
+/// Turn tag names, with or without a leading `@`, into a title `parse_title` understands
+fn tag_list<S: AsRef<str>>(tags: impl IntoIterator<Item = S>) -> String {
+    tags.into_iter()
+        .filter_map(|tag| {
+            let tag = tag.as_ref().trim().trim_start_matches('@');
+            (!tag.is_empty()).then(|| format!(" @{tag}"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::config::AutotagConfig;
+
+    fn tagger(autotag: AutotagConfig, default_tags: &[&str]) -> Autotagger {
+        let config = Config {
+            autotag,
+            default_tags: default_tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Config::default()
+        };
+        Autotagger::new(&config).unwrap()
+    }
+
+    fn entry(description: &str) -> Entry {
+        Entry::new(description.to_string(), "Currently".to_string())
+    }
+
+    #[test]
+    fn test_whitelist_and_synonyms_match_whole_words() {
+        let tagger = tagger(
+            AutotagConfig {
+                whitelist: vec!["rust".to_string()],
+                synonyms: IndexMap::from([(
+                    "meeting".to_string(),
+                    vec!["standup".to_string(), "sync*".to_string()],
+                )]),
+                transform: Vec::new(),
+            },
+            &[],
+        );
+
+        let mut standup = entry("Rust standup");
+        assert_eq!(tagger.tag_entry(&mut standup), vec!["rust", "meeting"]);
+
+        let mut syncing = entry("Syncing with the team");
+        assert_eq!(tagger.tag_entry(&mut syncing), vec!["meeting"]);
+
+        let mut untouched = entry("Trusted builds");
+        assert!(tagger.tag_entry(&mut untouched).is_empty());
+    }
+
+    #[test]
+    fn test_transforms_add_or_replace_tags() {
+        let tagger = tagger(
+            AutotagConfig {
+                transform: vec![r"(\w+)-\d+:$1".to_string(), "todo:task/r".to_string()],
+                ..AutotagConfig::default()
+            },
+            &[],
+        );
+
+        let mut entry = entry("Fix login")
+            .with_tag("api-42".to_string(), None)
+            .with_tag("todo".to_string(), None);
+        assert_eq!(tagger.tag_entry(&mut entry), vec!["api", "task"]);
+        assert_eq!(
+            entry.tags.keys().collect::<Vec<_>>(),
+            vec!["api-42", "api", "task"]
+        );
+    }
+
+    #[test]
+    fn test_default_tags_only_for_new_entries() {
+        let tagger = tagger(AutotagConfig::default(), &["@work", "client(acme)"]);
+
+        let mut entry = entry("Write report");
+        assert!(tagger.tag_entry(&mut entry).is_empty());
+
+        assert_eq!(tagger.tag_new_entry(&mut entry), vec!["work", "client"]);
+        assert_eq!(entry.tags["client"], Some("acme".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_transform_is_an_error() {
+        for rule in ["no separator", "(unclosed:tag"] {
+            let config = Config {
+                autotag: AutotagConfig {
+                    transform: vec![rule.to_string()],
+                    ..AutotagConfig::default()
+                },
+                ..Config::default()
+            };
+            assert!(Autotagger::new(&config).is_err(), "{rule}");
+        }
+    }
+}
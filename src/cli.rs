@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "daily-log")]
@@ -34,6 +35,58 @@ pub enum SectionsAction {
     },
 }
 
+#[derive(Debug, Clone, Subcommand)]
+pub enum ConfigAction {
+    /// Print the effective value of a setting
+    Get {
+        /// Setting name, with dots for nested settings (e.g. autotag.whitelist)
+        key: String,
+    },
+    /// Change a setting in the config file
+    Set {
+        /// Setting name, with dots for nested settings
+        key: String,
+        /// New value; numbers, booleans and JSON lists are stored as such
+        value: String,
+        /// Write to the config file in the current directory
+        #[arg(short = 'l', long)]
+        local: bool,
+    },
+    /// List every effective setting
+    List,
+    /// Open the config file in your editor
+    Edit {
+        /// Edit the config file in the current directory
+        #[arg(short = 'l', long)]
+        local: bool,
+    },
+    /// Print the path of the config file
+    Path {
+        /// Print the config file path for the current directory
+        #[arg(short = 'l', long)]
+        local: bool,
+        /// Print every config file in effect, in the order they are applied
+        #[arg(short = 'a', long, conflicts_with = "local")]
+        all: bool,
+    },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ViewsAction {
+    /// List saved views (default)
+    List,
+    /// Print the settings of a view
+    Show {
+        /// Name of the view
+        name: String,
+    },
+    /// Delete a saved view
+    Delete {
+        /// Name of the view
+        name: String,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Add an entry
@@ -472,7 +525,7 @@ pub enum Commands {
     )]
     Tag {
         /// Tags to add/remove
-        #[arg(value_name = "TAG", required = true)]
+        #[arg(value_name = "TAG", required_unless_present = "autotag")]
         tags: Vec<String>,
 
         /// Autotag entries based on autotag configuration
@@ -984,13 +1037,13 @@ pub enum Commands {
         #[arg(long = "tag")]
         tag: Option<String>,
 
-        /// Tag sort direction (asc|desc)
-        #[arg(long = "tag_order", default_value = "asc")]
-        tag_order: String,
+        /// Tag sort direction (asc|desc); entries' tags keep their order unless given
+        #[arg(long = "tag_order")]
+        tag_order: Option<String>,
 
-        /// Sort tags by (name|time)
-        #[arg(long = "tag_sort", default_value = "name")]
-        tag_sort: String,
+        /// Sort tags by (name|time); entries' tags keep their order unless given
+        #[arg(long = "tag_sort")]
+        tag_sort: Option<String>,
 
         /// Override output format with template
         #[arg(long = "template")]
@@ -1000,7 +1053,7 @@ pub enum Commands {
         #[arg(long = "title")]
         title: Option<String>,
 
-        /// Show time totals at end
+        /// Show time totals by tag, section and day
         #[arg(long = "totals")]
         totals: bool,
 
@@ -1095,13 +1148,13 @@ pub enum Commands {
         #[arg(long = "tag")]
         tag: Option<String>,
 
-        /// Tag sort direction (asc|desc)
-        #[arg(long = "tag_order", default_value = "asc")]
-        tag_order: String,
+        /// Tag sort direction (asc|desc); entries' tags keep their order unless given
+        #[arg(long = "tag_order")]
+        tag_order: Option<String>,
 
-        /// Sort tags by (name|time)
-        #[arg(long = "tag_sort", default_value = "name")]
-        tag_sort: String,
+        /// Sort tags by (name|time); entries' tags keep their order unless given
+        #[arg(long = "tag_sort")]
+        tag_sort: Option<String>,
 
         /// Override output format with template
         #[arg(long = "template")]
@@ -1111,7 +1164,7 @@ pub enum Commands {
         #[arg(long = "title")]
         title: Option<String>,
 
-        /// Show time totals at end
+        /// Show time totals by tag, section and day
         #[arg(long = "totals")]
         totals: bool,
 
@@ -1202,13 +1255,13 @@ pub enum Commands {
         #[arg(long = "tag")]
         tag: Option<String>,
 
-        /// Tag sort direction (asc|desc)
-        #[arg(long = "tag_order", default_value = "asc")]
-        tag_order: String,
+        /// Tag sort direction (asc|desc); entries' tags keep their order unless given
+        #[arg(long = "tag_order")]
+        tag_order: Option<String>,
 
-        /// Sort tags by (name|time)
-        #[arg(long = "tag_sort", default_value = "name")]
-        tag_sort: String,
+        /// Sort tags by (name|time); entries' tags keep their order unless given
+        #[arg(long = "tag_sort")]
+        tag_sort: Option<String>,
 
         /// Override output format with template
         #[arg(long = "template")]
@@ -1218,7 +1271,7 @@ pub enum Commands {
         #[arg(long = "title")]
         title: Option<String>,
 
-        /// Show time totals at end
+        /// Show time totals by tag, section and day
         #[arg(long = "totals")]
         totals: bool,
 
@@ -1301,13 +1354,13 @@ pub enum Commands {
         #[arg(long = "tag")]
         tag: Option<String>,
 
-        /// Tag sort direction (asc|desc)
-        #[arg(long = "tag_order", default_value = "asc")]
-        tag_order: String,
+        /// Tag sort direction (asc|desc); entries' tags keep their order unless given
+        #[arg(long = "tag_order")]
+        tag_order: Option<String>,
 
-        /// Sort tags by (name|time)
-        #[arg(long = "tag_sort", default_value = "name")]
-        tag_sort: String,
+        /// Sort tags by (name|time); entries' tags keep their order unless given
+        #[arg(long = "tag_sort")]
+        tag_sort: Option<String>,
 
         /// Override output format with template
         #[arg(long = "template")]
@@ -1317,7 +1370,7 @@ pub enum Commands {
         #[arg(long = "title")]
         title: Option<String>,
 
-        /// Show time totals at end
+        /// Show time totals by tag, section and day
         #[arg(long = "totals")]
         totals: bool,
 
@@ -1388,13 +1441,13 @@ pub enum Commands {
         #[arg(long = "tag")]
         tag: Option<String>,
 
-        /// Tag sort direction (asc|desc)
-        #[arg(long = "tag_order", default_value = "asc")]
-        tag_order: String,
+        /// Tag sort direction (asc|desc); entries' tags keep their order unless given
+        #[arg(long = "tag_order")]
+        tag_order: Option<String>,
 
-        /// Sort tags by (name|time)
-        #[arg(long = "tag_sort", default_value = "name")]
-        tag_sort: String,
+        /// Sort tags by (name|time); entries' tags keep their order unless given
+        #[arg(long = "tag_sort")]
+        tag_sort: Option<String>,
 
         /// Override output format with template
         #[arg(long = "template")]
@@ -1404,7 +1457,7 @@ pub enum Commands {
         #[arg(long = "title")]
         title: Option<String>,
 
-        /// Show time totals at end
+        /// Show time totals by tag, section and day
         #[arg(long = "totals")]
         totals: bool,
 
@@ -1463,13 +1516,13 @@ pub enum Commands {
         #[arg(short = 't', long = "times", default_value = "true")]
         times: bool,
 
-        /// Tag sort direction (asc|desc)
-        #[arg(long = "tag_order", default_value = "asc")]
-        tag_order: String,
+        /// Tag sort direction (asc|desc); entries' tags keep their order unless given
+        #[arg(long = "tag_order")]
+        tag_order: Option<String>,
 
-        /// Sort tags by (name|time)
-        #[arg(long = "tag_sort", default_value = "name")]
-        tag_sort: String,
+        /// Sort tags by (name|time); entries' tags keep their order unless given
+        #[arg(long = "tag_sort")]
+        tag_sort: Option<String>,
 
         /// Override output format with template
         #[arg(long = "template")]
@@ -1479,11 +1532,106 @@ pub enum Commands {
         #[arg(long = "title")]
         title: Option<String>,
 
-        /// Show time totals at end
+        /// Show time totals by tag, section and day
         #[arg(long = "totals")]
         totals: bool,
     },
 
+    /// Display a saved view
+    #[command(
+        about = "Display a saved view",
+        long_about = "Replay the filter, display and template options saved with `--save` on show, grep, on, since or yesterday. Views can also be written by hand in the `views` setting. An unambiguous prefix of the view name is enough."
+    )]
+    View {
+        /// Name of the view
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+
+    /// List, show or delete saved views
+    Views {
+        #[command(subcommand)]
+        action: Option<ViewsAction>,
+    },
+
+    /// Summarize a week, a month or a date range
+    #[command(
+        about = "Summarize a week, a month or a date range",
+        long_about = "Write a status report for the current week (the default), the current month or a date range: finished entries day by day with the hours tracked each day, the top tags and sections by time, and the entries that are still open."
+    )]
+    Report {
+        /// Report on the current week, Monday to Sunday
+        #[arg(long, conflicts_with_all = ["month", "from"])]
+        week: bool,
+
+        /// Report on the current month
+        #[arg(long, conflicts_with = "from")]
+        month: bool,
+
+        /// Report on a date range, e.g. "last monday to yesterday"
+        #[arg(long, value_name = "DATE_OR_RANGE")]
+        from: Option<String>,
+
+        /// Output format (markdown|html|json)
+        #[arg(short = 'o', long = "output")]
+        output: Option<String>,
+
+        /// Only include entries from these sections
+        #[arg(short = 's', long = "section")]
+        sections: Vec<String>,
+
+        /// Only include entries with these tags (comma separated)
+        #[arg(long = "tag")]
+        tag: Option<String>,
+
+        /// Title of the report
+        #[arg(long = "title")]
+        title: Option<String>,
+    },
+
+    #[command(
+        alias = "timesheet",
+        about = "Write a timesheet of billable time with rates",
+        long_about = "Write a timesheet of the finished entries in the current month (the default), the current week or a date range, grouped by client and priced at each client's hourly rate. The client is the value of the `@client(name)` tag, or the section for entries without one; `--by section` groups by section instead. Rates, rounding to the nearest 6 or 15 minutes and the non-billable tags come from the `invoice` settings in the config."
+    )]
+    Invoice {
+        /// Bill the current week, Monday to Sunday
+        #[arg(long, conflicts_with_all = ["month", "from"])]
+        week: bool,
+
+        /// Bill the current month
+        #[arg(long, conflicts_with = "from")]
+        month: bool,
+
+        /// Bill a date range, e.g. "last monday to yesterday"
+        #[arg(long, value_name = "DATE_OR_RANGE")]
+        from: Option<String>,
+
+        /// Output format (csv|html)
+        #[arg(short = 'o', long = "output")]
+        output: Option<String>,
+
+        /// Group entries by client tag or by section (client|section)
+        #[arg(long = "by", default_value = "client")]
+        by: String,
+
+        /// Only bill this client
+        #[arg(long = "client")]
+        client: Option<String>,
+
+        /// Only include entries from these sections
+        #[arg(short = 's', long = "section")]
+        sections: Vec<String>,
+
+        /// Only include entries with these tags (comma separated)
+        #[arg(long = "tag")]
+        tag: Option<String>,
+
+        /// Title of the timesheet
+        #[arg(long = "title")]
+        title: Option<String>,
+    },
+
     /// List, add, or remove sections in the Doing file
     Sections {
         #[command(subcommand)]
@@ -1529,6 +1677,37 @@ pub enum Commands {
         exact: bool,
     },
 
+    /// Import entries from another time tracker
+    #[command(
+        about = "Import entries from another time tracker",
+        long_about = "Add the intervals from a Timewarrior export (`timew export`), Watson (`watson log --json` or its frames file) or a Toggl Track detailed report in CSV to the doing file, or merge in another daily-log or doing file (`taskpaper`) or a `--output json` export (`json`). Finished intervals get a @done tag. Entries already in the file are skipped; an imported entry whose id is taken by a different entry gets a new id. Sections are matched by name."
+    )]
+    Import {
+        /// The file to import
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Format of the file (timewarrior|watson|toggl-csv|json|taskpaper)
+        #[arg(long = "type", value_name = "TYPE")]
+        kind: String,
+
+        /// Section to add the entries to
+        #[arg(short = 's', long = "section")]
+        section: Option<String>,
+
+        /// Show what would be imported without changing the file
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+
+        /// Put this in front of every imported title
+        #[arg(long = "prefix")]
+        prefix: Option<String>,
+
+        /// Add these tags to every imported entry (comma separated)
+        #[arg(long = "tag")]
+        tag: Option<String>,
+    },
+
     /// Move entries to archive file
     Rotate {
         #[arg(long, value_name = "DATE_STRING")]
@@ -1553,6 +1732,41 @@ pub enum Commands {
         exact: bool,
     },
 
+    /// Undo the last change(s) to the Doing file
+    Undo {
+        /// Number of changes to undo
+        #[arg(value_name = "COUNT", default_value = "1")]
+        count: usize,
+
+        /// Undo even if the doing file was changed since
+        #[arg(long = "force")]
+        force: bool,
+    },
+
+    /// Redo changes reverted by undo
+    Redo {
+        /// Number of changes to redo
+        #[arg(value_name = "COUNT", default_value = "1")]
+        count: usize,
+
+        /// Redo even if the doing file was changed since the undo
+        #[arg(long = "force")]
+        force: bool,
+    },
+
+    /// List recorded changes that can be undone
+    History {
+        /// Maximum number of changes to show
+        #[arg(short = 'c', long = "count", value_name = "COUNT")]
+        count: Option<usize>,
+    },
+
+    /// Show or change daily-log settings
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
     /// List all tags in the current Doing file
     Tags {
         /// Maximum number of tags to show
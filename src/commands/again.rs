@@ -1,5 +1,10 @@
+use crate::autotag::Autotagger;
+use crate::editor;
+use crate::filtering::{BoolOp, matches_value_queries, parse_value_queries};
 use crate::models::Entry;
-use crate::storage::{Config, parse_taskpaper, save_taskpaper};
+use crate::picker;
+use crate::services::EntryService;
+use crate::storage::{Config, parse_taskpaper};
 use chrono::Local;
 use chrono_english::{Dialect, parse_date_string};
 use regex::Regex;
@@ -10,7 +15,7 @@ pub struct AgainOptions {
     pub noauto: bool,
     pub ask: bool,
     pub back: Option<String>,
-    pub _bool_op: String,
+    pub bool_op: String,
     pub case: String,
     pub editor: bool,
     pub interactive: bool,
@@ -20,36 +25,39 @@ pub struct AgainOptions {
     pub sections: Vec<String>,
     pub search: Option<String>,
     pub tag: Option<String>,
-    pub _val: Vec<String>,
+    pub val: Vec<String>,
     pub exact: bool,
 }
 
 pub fn handle_again(opts: AgainOptions) -> color_eyre::Result<()> {
-    if opts.interactive {
+    let config = Config::try_load()?;
+    let doing_file_path = config.doing_file_path();
+
+    // Only read for now; the file is locked once the new entry is ready
+    let doing_file = parse_taskpaper(&doing_file_path)?;
+
+    // Find the entry to duplicate: the most recent match, or one the user picks
+    let matches = find_entries_to_duplicate(&doing_file, &opts)?;
+    if matches.is_empty() {
         return Err(color_eyre::eyre::eyre!(
-            "Interactive mode not yet implemented"
+            "No matching entry found to duplicate"
         ));
     }
 
-    if opts.editor {
-        return Err(color_eyre::eyre::eyre!("Editor mode not yet implemented"));
-    }
-
-    let config = Config::load();
-    let doing_file_path = config.doing_file_path();
-
-    let mut doing_file = parse_taskpaper(&doing_file_path)?;
-
-    // Find the entry to duplicate
-    let entry_to_duplicate = find_entry_to_duplicate(
-        &doing_file,
-        &opts.sections,
-        opts.search.as_deref(),
-        opts.tag.as_deref(),
-        opts.exact,
-        opts.not,
-        &opts.case,
-    )?;
+    let entry_to_duplicate = if opts.interactive {
+        let candidates: Vec<&Entry> = matches.iter().collect();
+        let chosen = picker::pick_entries(&candidates, "Select an entry to resume", false)?;
+        let Some(entry) = matches
+            .into_iter()
+            .find(|entry| chosen.contains(&entry.uuid))
+        else {
+            println!("Cancelled");
+            return Ok(());
+        };
+        entry
+    } else {
+        matches[0].clone()
+    };
 
     // Create new entry based on the found one
     let new_start_time = if let Some(back_str) = &opts.back {
@@ -59,25 +67,11 @@ pub fn handle_again(opts: AgainOptions) -> color_eyre::Result<()> {
         Local::now()
     };
 
-    // Create new entry with same description and tags (minus @done)
-    let mut new_entry = Entry::new(
-        entry_to_duplicate.description.clone(),
-        opts.in_section
-            .clone()
-            .unwrap_or_else(|| entry_to_duplicate.section.clone()),
-    );
-
-    // Set the new timestamp
-    new_entry.timestamp = new_start_time;
+    // Same description, tags (minus @done) and note
+    let mut new_entry =
+        EntryService::resumed_entry(&entry_to_duplicate, opts.in_section.clone(), new_start_time);
 
-    // Copy tags except @done
-    for (tag_name, tag_value) in &entry_to_duplicate.tags {
-        if tag_name != "done" {
-            new_entry.tags.insert(tag_name.clone(), tag_value.clone());
-        }
-    }
-
-    // Add or update note
+    // Replace the note
     if let Some(new_note) = opts.note {
         new_entry.note = Some(new_note);
     } else if opts.ask {
@@ -108,26 +102,30 @@ pub fn handle_again(opts: AgainOptions) -> color_eyre::Result<()> {
             lines.pop();
         }
 
-        if !lines.is_empty() {
-            new_entry.note = Some(lines.join("\n"));
-        }
-    } else if let Some(existing_note) = &entry_to_duplicate.note {
-        new_entry.note = Some(existing_note.clone());
+        new_entry.note = (!lines.is_empty()).then(|| lines.join("\n"));
     }
 
     // Add auto tags unless disabled
     if !opts.noauto {
-        // Add any default tags from config if implemented
+        Autotagger::new(&config)?.tag_new_entry(&mut new_entry);
+    }
+
+    if opts.editor {
+        match editor::edit_entry(&new_entry)? {
+            Some(entry) => new_entry = entry,
+            None => {
+                println!("Empty entry, nothing added");
+                return Ok(());
+            }
+        }
     }
 
-    // Add the new entry
-    doing_file.add_entry(new_entry.clone());
-    save_taskpaper(&doing_file)?;
+    EntryService::add_entry(new_entry.clone(), false)?;
 
     // Show confirmation
     println!(
         "{}: {}",
-        new_entry.timestamp.format("%Y-%m-%d %H:%M"),
+        new_entry.started_at.format("%Y-%m-%d %H:%M"),
         new_entry.description
     );
 
@@ -140,15 +138,22 @@ pub fn handle_again(opts: AgainOptions) -> color_eyre::Result<()> {
     Ok(())
 }
 
-fn find_entry_to_duplicate(
+fn find_entries_to_duplicate(
     doing_file: &crate::models::DoingFile,
-    sections: &[String],
-    search: Option<&str>,
-    tag: Option<&str>,
-    exact: bool,
-    not: bool,
-    case: &str,
-) -> Result<Entry, color_eyre::eyre::Error> {
+    opts: &AgainOptions,
+) -> Result<Vec<Entry>, color_eyre::eyre::Error> {
+    let AgainOptions {
+        sections,
+        search,
+        tag,
+        val,
+        bool_op,
+        exact,
+        not,
+        case,
+        ..
+    } = opts;
+
     // Determine which sections to search
     let target_sections: Vec<String> = if sections.is_empty() {
         // Get all section names
@@ -168,14 +173,14 @@ fn find_entry_to_duplicate(
     }
 
     // Sort by timestamp (newest first)
-    all_entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    all_entries.sort_by_key(|e| std::cmp::Reverse(e.started_at));
 
     // Apply filters
     let mut filtered_entries = all_entries;
 
     // Apply search filter
     if let Some(search_query) = search {
-        filtered_entries = filter_by_search(filtered_entries, search_query, exact, case)?;
+        filtered_entries = filter_by_search(filtered_entries, search_query, *exact, case)?;
     }
 
     // Apply tag filter
@@ -183,8 +188,15 @@ fn find_entry_to_duplicate(
         filtered_entries = filter_by_tag(filtered_entries, tag_query)?;
     }
 
+    // Apply tag value queries
+    if !val.is_empty() {
+        let queries = parse_value_queries(val)?;
+        let bool_op = BoolOp::from(bool_op.as_str());
+        filtered_entries.retain(|entry| matches_value_queries(entry, &queries, &bool_op));
+    }
+
     // Apply NOT filter if specified
-    if not {
+    if *not {
         // Get all entries again
         let mut all_entries_again: Vec<Entry> = Vec::new();
         for section in &target_sections {
@@ -194,7 +206,7 @@ fn find_entry_to_duplicate(
                 }
             }
         }
-        all_entries_again.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        all_entries_again.sort_by_key(|e| std::cmp::Reverse(e.started_at));
 
         filtered_entries = all_entries_again
             .into_iter()
@@ -202,11 +214,7 @@ fn find_entry_to_duplicate(
             .collect();
     }
 
-    // Get the most recent entry after filtering
-    filtered_entries
-        .into_iter()
-        .next()
-        .ok_or_else(|| color_eyre::eyre::eyre!("No matching entry found to duplicate"))
+    Ok(filtered_entries)
 }
 
 fn filter_by_search(
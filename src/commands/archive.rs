@@ -1,15 +1,15 @@
-use crate::storage::{Config, parse_taskpaper, save_taskpaper};
+use crate::filtering::{BoolOp, matches_value_queries, parse_value_queries};
+use crate::storage::{Config, FileLock, parse_taskpaper, save_taskpaper};
 use chrono::Local;
 use color_eyre::Result;
 use regex::Regex;
-use std::collections::HashMap;
 
 #[derive(Debug)]
 pub struct ArchiveOptions {
     pub target: Option<String>,
     pub after: Option<String>,
     pub before: Option<String>,
-    pub _bool_op: String,
+    pub bool_op: String,
     pub case: String,
     pub from: Option<String>,
     pub keep: Option<usize>,
@@ -23,8 +23,9 @@ pub struct ArchiveOptions {
 }
 
 pub fn handle_archive(opts: ArchiveOptions) -> Result<()> {
-    let config = Config::load();
+    let config = Config::try_load()?;
     let doing_file_path = config.doing_file_path();
+    let _lock = FileLock::acquire(&doing_file_path)?;
     let mut doing_file = parse_taskpaper(&doing_file_path)?;
 
     // Ensure destination section exists
@@ -104,7 +105,8 @@ pub fn handle_archive(opts: ArchiveOptions) -> Result<()> {
         None
     };
 
-    let tag_value_queries = compile_tag_value_queries(&opts.val)?;
+    let value_queries = parse_value_queries(&opts.val)?;
+    let bool_op = BoolOp::from(opts.bool_op.as_str());
 
     // Collect entries to move from each section
     for section_name in sections_to_process {
@@ -120,17 +122,17 @@ pub fn handle_archive(opts: ArchiveOptions) -> Result<()> {
 
                 // Apply date filters
                 if let Some(after) = &after_date
-                    && entry.timestamp <= *after
+                    && entry.started_at <= *after
                 {
                     matches = false;
                 }
                 if let Some(before) = &before_date
-                    && entry.timestamp >= *before
+                    && entry.started_at >= *before
                 {
                     matches = false;
                 }
                 if let Some((start, end)) = &date_range
-                    && (entry.timestamp < *start || entry.timestamp > *end)
+                    && (entry.started_at < *start || entry.started_at > *end)
                 {
                     matches = false;
                 }
@@ -162,15 +164,10 @@ pub fn handle_archive(opts: ArchiveOptions) -> Result<()> {
                 }
 
                 // Apply tag value queries
-                for (tag_name, value_pattern) in &tag_value_queries {
-                    match entry.tags.get(tag_name) {
-                        Some(Some(value)) => {
-                            if !value_pattern.is_match(value) {
-                                matches = false;
-                            }
-                        }
-                        _ => matches = false,
-                    }
+                if !value_queries.is_empty()
+                    && !matches_value_queries(entry, &value_queries, &bool_op)
+                {
+                    matches = false;
                 }
 
                 // Apply not filter
@@ -230,7 +227,7 @@ pub fn handle_archive(opts: ArchiveOptions) -> Result<()> {
     }
 
     if moved_count > 0 {
-        save_taskpaper(&doing_file)?;
+        save_taskpaper(&doing_file, &config)?;
         println!(
             "Moved {} {} from {} to {}",
             moved_count,
@@ -282,19 +279,3 @@ fn parse_smart_case(case: &str, pattern: &str) -> bool {
         }
     }
 }
-
-fn compile_tag_value_queries(val: &[String]) -> Result<HashMap<String, Regex>> {
-    let mut queries = HashMap::new();
-
-    for query in val {
-        // Parse tag value queries in format "tag_name=pattern"
-        if let Some(eq_pos) = query.find('=') {
-            let tag_name = query[..eq_pos].trim().to_string();
-            let pattern = query[eq_pos + 1..].trim();
-            let regex = Regex::new(pattern)?;
-            queries.insert(tag_name, regex);
-        }
-    }
-
-    Ok(queries)
-}
@@ -0,0 +1,396 @@
+use crate::cli::ConfigAction;
+use crate::editor;
+use crate::storage::config::{
+    ConfigFile, ConfigScope, LOCAL_CONFIG_NAMES, get_value, merge_values, parse_setting,
+    remove_value, set_value,
+};
+use crate::storage::{Config, write_atomic};
+use color_eyre::Result;
+use color_eyre::eyre::{WrapErr, eyre};
+use regex::Regex;
+use serde_json::Value;
+use std::sync::LazyLock;
+
+const NEW_CONFIG_HEADER: &str = "\
+# daily-log configuration
+# Run `daily-log config list` to see every setting and its current value.
+";
+
+pub fn handle_config(action: ConfigAction) -> Result<()> {
+    match action {
+        ConfigAction::Get { key } => {
+            let settings = Config::effective_value()?;
+            match get_value(&settings, &key) {
+                Some(value) if !value.is_null() => println!("{}", format_value(value)),
+                Some(_) => return Err(eyre!("{key} is not set")),
+                None => return Err(eyre!("unknown setting: {key}")),
+            }
+        }
+        ConfigAction::Set { key, value, local } => {
+            let file = target_file(local)?;
+            let value = parse_setting(&value);
+            set_setting(&file, &key, value.clone())?;
+            println!(
+                "Set {key} = {} in {}",
+                format_value(&value),
+                file.path.display()
+            );
+        }
+        ConfigAction::List => {
+            let settings = Config::effective_value()?;
+            let mut lines = Vec::new();
+            flatten_settings(&settings, "", &mut lines);
+            for (key, value) in lines {
+                println!("{key} = {value}");
+            }
+        }
+        ConfigAction::Edit { local } => {
+            let file = target_file(local)?;
+            if !file.path.exists() {
+                if let Some(parent) = file.path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let header = if file.is_toml() {
+                    NEW_CONFIG_HEADER
+                } else {
+                    ""
+                };
+                write_atomic(&file.path, header.as_bytes())?;
+            }
+            editor::edit_file(&file.path)?;
+            Config::from_path(&file.path)
+                .wrap_err_with(|| format!("{} has errors", file.path.display()))?;
+        }
+        ConfigAction::Path { local, all } => {
+            if all {
+                for file in Config::files() {
+                    let scope = match file.scope {
+                        ConfigScope::Global => "global",
+                        ConfigScope::Local => "local",
+                    };
+                    println!("{scope}\t{}", file.path.display());
+                }
+            } else {
+                println!("{}", target_file(local)?.path.display());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The file `set` and `edit` write to: the global config, or a config in the
+/// current directory (an existing one, else a new `.daily-log.toml`).
+pub(crate) fn target_file(local: bool) -> Result<ConfigFile> {
+    if !local {
+        return Ok(ConfigFile {
+            path: Config::global_path(),
+            scope: ConfigScope::Global,
+        });
+    }
+
+    let cwd = std::env::current_dir()?;
+    let path = LOCAL_CONFIG_NAMES
+        .iter()
+        .map(|name| cwd.join(name))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| cwd.join(LOCAL_CONFIG_NAMES[0]));
+    Ok(ConfigFile {
+        path,
+        scope: ConfigScope::Local,
+    })
+}
+
+/// Write one setting to `file`, refusing values the config can't load.
+///
+/// The file is edited in place so comments and layout survive.
+pub(crate) fn set_setting(file: &ConfigFile, key: &str, value: Value) -> Result<()> {
+    let mut settings = match file.read_raw()? {
+        Value::Null => Value::Object(Default::default()),
+        settings => settings,
+    };
+    set_value(&mut settings, key, value.clone())?;
+
+    let mut effective = serde_json::to_value(Config::default())?;
+    merge_values(&mut effective, settings.clone());
+    serde_json::from_value::<Config>(effective)
+        .wrap_err_with(|| format!("invalid value for {key}"))?;
+
+    let content = if file.is_toml() {
+        match std::fs::read_to_string(&file.path) {
+            Ok(existing) => set_in_toml(&existing, key, &value)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                format!("{NEW_CONFIG_HEADER}{}", set_in_toml("", key, &value)?)
+            }
+            Err(e) => return Err(e.into()),
+        }
+    } else {
+        match std::fs::read_to_string(&file.path) {
+            Ok(existing) => edit_yaml(&existing, key, Some(&value))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => edit_yaml("", key, Some(&value))?,
+            Err(e) => return Err(e.into()),
+        }
+    };
+
+    if let Some(parent) = file.path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    write_atomic(&file.path, content.as_bytes())
+}
+
+/// Remove one setting from `file`. Returns false if the file doesn't set it.
+pub(crate) fn remove_setting(file: &ConfigFile, key: &str) -> Result<bool> {
+    let mut settings = file.read_raw()?;
+    if remove_value(&mut settings, key).is_none() {
+        return Ok(false);
+    }
+
+    let content = if file.is_toml() {
+        let mut document: toml_edit::DocumentMut = std::fs::read_to_string(&file.path)?.parse()?;
+        let mut table: &mut dyn toml_edit::TableLike = document.as_table_mut();
+        let (parents, last) = key.rsplit_once('.').unwrap_or(("", key));
+        for part in parents.split('.').filter(|part| !part.is_empty()) {
+            table = table
+                .get_mut(part)
+                .and_then(|item| item.as_table_like_mut())
+                .ok_or_else(|| eyre!("{key}: a parent of this setting is not a table"))?;
+        }
+        table.remove(last);
+        document.to_string()
+    } else {
+        edit_yaml(&std::fs::read_to_string(&file.path)?, key, None)?
+    };
+
+    write_atomic(&file.path, content.as_bytes())?;
+    Ok(true)
+}
+
+/// Set a dotted key in a TOML document, keeping everything else as written
+pub(crate) fn set_in_toml(content: &str, key: &str, value: &Value) -> Result<String> {
+    // Tables become one `[section]` entry per key rather than an inline table
+    if let Value::Object(map) = value {
+        let mut content = content.to_string();
+        for (sub_key, sub_value) in map {
+            content = set_in_toml(&content, &format!("{key}.{sub_key}"), sub_value)?;
+        }
+        return Ok(content);
+    }
+
+    let mut document: toml_edit::DocumentMut = content.parse()?;
+    let parts: Vec<&str> = key.split('.').collect();
+    let (last, parents) = parts
+        .split_last()
+        .ok_or_else(|| eyre!("invalid setting name: {key}"))?;
+
+    let mut table = document.as_table_mut();
+    for part in parents {
+        table = table
+            .entry(part)
+            .or_insert_with(toml_edit::table)
+            .as_table_mut()
+            .ok_or_else(|| eyre!("{key}: a parent of this setting is not a table"))?;
+    }
+
+    let toml_value = toml::Value::try_from(value)
+        .map_err(|_| eyre!("{key}: null can't be stored in a TOML file"))?;
+    let mut toml_value: toml_edit::Value = toml_value.to_string().parse()?;
+    // Keep a trailing comment on the line being replaced
+    if let Some(existing) = table.get(last).and_then(|item| item.as_value()) {
+        *toml_value.decor_mut() = existing.decor().clone();
+    }
+    table[last] = toml_edit::value(toml_value);
+
+    Ok(document.to_string())
+}
+
+/// Set a dotted key in a YAML document, or remove it when `value` is `None`,
+/// keeping comments and the order of everything else. Block mappings, as
+/// doing writes `.doingrc`, are edited line by line. A document in flow style,
+/// such as JSON, has no comments to keep and is written out again as JSON.
+pub(crate) fn edit_yaml(content: &str, key: &str, value: Option<&Value>) -> Result<String> {
+    let first_line = content.lines().find(|line| is_yaml_content(line));
+    if first_line.is_some_and(|line| line.trim_start().starts_with('{')) {
+        let mut settings: Value = serde_yaml::from_str(content)?;
+        match value {
+            Some(value) => set_value(&mut settings, key, value.clone())?,
+            None => {
+                remove_value(&mut settings, key);
+            }
+        }
+        return Ok(format!("{}\n", serde_json::to_string_pretty(&settings)?));
+    }
+
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let parts: Vec<&str> = key.split('.').collect();
+    let mut range = 0..lines.len();
+    let mut indent = 0;
+
+    for (depth, part) in parts.iter().enumerate() {
+        if let Some(child) = lines[range.clone()]
+            .iter()
+            .find(|line| is_yaml_content(line))
+        {
+            indent = indentation(child);
+        }
+        let found = range
+            .clone()
+            .find(|&i| yaml_key(&lines[i]).is_some_and(|(at, name)| at == indent && name == *part));
+
+        let Some(line) = found else {
+            // Nothing to remove; anything to set goes at the end of the parent
+            let Some(value) = value else {
+                return Ok(content.to_string());
+            };
+            let nested = parts[depth..].iter().rev().fold(
+                value.clone(),
+                |value, part| serde_json::json!({ *part: value }),
+            );
+            let at = range
+                .clone()
+                .rev()
+                .find(|&i| is_yaml_content(&lines[i]))
+                .map_or(range.start, |i| i + 1);
+            lines.splice(at..at, render_yaml(&nested, indent)?);
+            return Ok(join_lines(&lines));
+        };
+
+        let end = yaml_block_end(&lines, line, indent);
+        if depth + 1 < parts.len() {
+            // The parent becomes a mapping if it held anything else on its line
+            if yaml_inline_value(&lines[line]).is_some() {
+                lines[line] = format!("{}{part}:", " ".repeat(indent));
+            }
+            range = line + 1..end;
+            indent += 2;
+            continue;
+        }
+
+        let replacement = match value {
+            None => Vec::new(),
+            Some(value) => {
+                let mut rendered = render_yaml(&serde_json::json!({ *part: value }), indent)?;
+                if let ([single], Some(comment)) =
+                    (rendered.as_mut_slice(), yaml_comment(&lines[line]))
+                {
+                    single.push_str(comment);
+                }
+                rendered
+            }
+        };
+        lines.splice(line..end, replacement);
+        return Ok(join_lines(&lines));
+    }
+
+    Ok(join_lines(&lines))
+}
+
+/// A mapping key at the start of a line: its indentation and name
+fn yaml_key(line: &str) -> Option<(usize, &str)> {
+    static KEY: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r#"^( *)(?:"([^"]+)"|'([^']+)'|([^\s#'"?:\-][^:#]*?)) *:(?:\s|$)"#).unwrap()
+    });
+    let captures = KEY.captures(line)?;
+    let name = captures
+        .get(2)
+        .or_else(|| captures.get(3))
+        .or_else(|| captures.get(4))?;
+    Some((captures[1].len(), name.as_str()))
+}
+
+/// What follows a key's colon on its line, without a comment; `None` when
+/// the value is on the lines below
+fn yaml_inline_value(line: &str) -> Option<&str> {
+    let (_, rest) = line.split_once(':')?;
+    let rest = match yaml_comment(line) {
+        Some(comment) => &rest[..rest.len() - comment.len()],
+        None => rest,
+    };
+    Some(rest.trim()).filter(|rest| !rest.is_empty())
+}
+
+/// A trailing ` # comment`, with the space before it
+fn yaml_comment(line: &str) -> Option<&str> {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '#') if previous.is_whitespace() => {
+                let start = line[..index].trim_end().len();
+                return Some(&line[start..]);
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    None
+}
+
+/// The line after the block of the key on `line`: its value's lines, which
+/// are indented further or are list items at the key's own indentation.
+/// Comments and blank lines after the block are left to what follows.
+fn yaml_block_end(lines: &[String], line: usize, indent: usize) -> usize {
+    let mut end = line + 1;
+    for (i, next) in lines.iter().enumerate().skip(line + 1) {
+        if !is_yaml_content(next) {
+            continue;
+        }
+        let at = indentation(next);
+        if at > indent || (at == indent && next.trim_start().starts_with("- ")) {
+            end = i + 1;
+        } else {
+            break;
+        }
+    }
+    end
+}
+
+fn render_yaml(value: &Value, indent: usize) -> Result<Vec<String>> {
+    Ok(serde_yaml::to_string(value)?
+        .lines()
+        .map(|line| format!("{}{line}", " ".repeat(indent)))
+        .collect())
+}
+
+fn is_yaml_content(line: &str) -> bool {
+    let trimmed = line.trim();
+    !(trimmed.is_empty() || trimmed.starts_with('#') || trimmed == "---" || trimmed == "...")
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn join_lines(lines: &[String]) -> String {
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Dotted `key = value` pairs for every set leaf, in key order
+fn flatten_settings(value: &Value, prefix: &str, lines: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            for key in keys {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten_settings(&map[key], &path, lines);
+            }
+        }
+        Value::Null => {}
+        other => lines.push((prefix.to_string(), other.to_string())),
+    }
+}
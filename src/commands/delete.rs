@@ -1,4 +1,6 @@
-use crate::storage::{Config, parse_taskpaper, save_taskpaper};
+use crate::models::Entry;
+use crate::picker;
+use crate::storage::{Config, FileLock, parse_taskpaper, save_taskpaper};
 use chrono::{DateTime, Local};
 use regex::Regex;
 use std::io::{self, Write};
@@ -16,15 +18,11 @@ pub struct DeleteOptions {
 }
 
 pub fn handle_delete(opts: DeleteOptions) -> color_eyre::Result<()> {
-    if opts.interactive {
-        return Err(color_eyre::eyre::eyre!(
-            "Interactive mode not yet implemented"
-        ));
-    }
-
-    let config = Config::load();
+    let config = Config::try_load()?;
     let doing_file_path = config.doing_file_path();
 
+    let mut lock = FileLock::acquire(&doing_file_path)?;
+
     let mut doing_file = parse_taskpaper(&doing_file_path)?;
 
     // Determine which sections to work with
@@ -39,13 +37,13 @@ pub fn handle_delete(opts: DeleteOptions) -> color_eyre::Result<()> {
     for section in &target_sections {
         if let Some(entries) = doing_file.sections.get(section) {
             for entry in entries {
-                all_entries.push((section.clone(), entry.timestamp, entry.description.clone()));
+                all_entries.push((section.clone(), entry.started_at, entry.description.clone()));
             }
         }
     }
 
     // Sort by timestamp (newest first)
-    all_entries.sort_by(|a, b| b.1.cmp(&a.1));
+    all_entries.sort_by_key(|e| std::cmp::Reverse(e.1));
 
     // Filter entries based on search criteria
     let mut filtered_entries = all_entries;
@@ -69,13 +67,13 @@ pub fn handle_delete(opts: DeleteOptions) -> color_eyre::Result<()> {
                 for entry in entries {
                     all_entries_again.push((
                         section.clone(),
-                        entry.timestamp,
+                        entry.started_at,
                         entry.description.clone(),
                     ));
                 }
             }
         }
-        all_entries_again.sort_by(|a, b| b.1.cmp(&a.1));
+        all_entries_again.sort_by_key(|e| std::cmp::Reverse(e.1));
 
         filtered_entries = all_entries_again
             .into_iter()
@@ -83,8 +81,25 @@ pub fn handle_delete(opts: DeleteOptions) -> color_eyre::Result<()> {
             .collect();
     }
 
-    // Take only the requested count
-    let entries_to_delete: Vec<_> = filtered_entries.into_iter().take(opts.count).collect();
+    // Let the user choose, or take only the requested count
+    let entries_to_delete: Vec<_> = if opts.interactive && !filtered_entries.is_empty() {
+        let candidates: Vec<&Entry> = filtered_entries
+            .iter()
+            .filter_map(|key| find_entry(&doing_file, key))
+            .collect();
+        let chosen =
+            lock.suspend(|| picker::pick_entries(&candidates, "Select entries to delete", true))?;
+        if chosen.is_empty() {
+            println!("Cancelled");
+            return Ok(());
+        }
+        filtered_entries
+            .into_iter()
+            .filter(|key| find_entry(&doing_file, key).is_some_and(|e| chosen.contains(&e.uuid)))
+            .collect()
+    } else {
+        filtered_entries.into_iter().take(opts.count).collect()
+    };
 
     if entries_to_delete.is_empty() {
         return Err(color_eyre::eyre::eyre!(
@@ -129,7 +144,7 @@ pub fn handle_delete(opts: DeleteOptions) -> color_eyre::Result<()> {
         if let Some(entries) = doing_file.sections.get_mut(&section) {
             let initial_len = entries.len();
             entries.retain(|entry| {
-                !(entry.timestamp == timestamp && entry.description == description)
+                !(entry.started_at == timestamp && entry.description == description)
             });
 
             if entries.len() < initial_len {
@@ -143,7 +158,7 @@ pub fn handle_delete(opts: DeleteOptions) -> color_eyre::Result<()> {
         }
     }
 
-    save_taskpaper(&doing_file)?;
+    save_taskpaper(&doing_file, &config)?;
 
     if deleted_count == 0 {
         return Err(color_eyre::eyre::eyre!("No entries were deleted"));
@@ -162,6 +177,18 @@ pub fn handle_delete(opts: DeleteOptions) -> color_eyre::Result<()> {
     Ok(())
 }
 
+/// The entry a `(section, timestamp, description)` key refers to
+fn find_entry<'a>(
+    doing_file: &'a crate::models::DoingFile,
+    (section, timestamp, description): &(String, DateTime<Local>, String),
+) -> Option<&'a Entry> {
+    doing_file
+        .sections
+        .get(section)?
+        .iter()
+        .find(|entry| entry.started_at == *timestamp && entry.description == *description)
+}
+
 fn filter_by_search(
     entries: Vec<(String, DateTime<Local>, String)>,
     search_query: &str,
@@ -212,7 +239,7 @@ fn filter_by_tag(
             // Find the actual entry to check tags
             if let Some(section_entries) = doing_file.sections.get(section) {
                 for entry in section_entries {
-                    if entry.timestamp == *timestamp && entry.description == *description {
+                    if entry.started_at == *timestamp && entry.description == *description {
                         // Check if entry has any of the requested tags
                         for tag in &tags {
                             if tag.contains('*') || tag.contains('?') {
@@ -1,5 +1,7 @@
+use crate::autotag::Autotagger;
+use crate::editor;
 use crate::models::Entry;
-use crate::storage::{Config, parse_taskpaper, save_taskpaper};
+use crate::storage::{Config, FileLock, parse_taskpaper, save_taskpaper};
 use chrono::{DateTime, Duration, Local};
 use chrono_english::{Dialect, parse_date_string};
 use regex::Regex;
@@ -20,13 +22,31 @@ pub struct DoneOptions {
     pub remove: bool,
     pub unfinished: bool,
     pub _date: bool,
-    pub _noauto: bool,
+    pub noauto: bool,
 }
 
 pub fn handle_done(opts: DoneOptions) -> color_eyre::Result<()> {
-    let config = Config::load();
+    let config = Config::try_load()?;
     let doing_file_path = config.doing_file_path();
 
+    // The editor runs before the doing file is locked, so it doesn't keep
+    // other commands waiting
+    let edited = if opts.editor && !opts.remove {
+        let mut draft = Entry::new(opts.entry.join(" "), new_entry_section(&opts));
+        draft.note = opts.note.clone();
+        match editor::edit_entry(&draft)? {
+            Some(entry) => Some(entry),
+            None => {
+                println!("Empty entry, nothing added");
+                return Ok(());
+            }
+        }
+    } else {
+        None
+    };
+
+    let _lock = FileLock::acquire(&doing_file_path)?;
+
     let mut doing_file = parse_taskpaper(&doing_file_path)?;
 
     // Handle remove flag - remove @done tag from last entry
@@ -38,8 +58,8 @@ pub fn handle_done(opts: DoneOptions) -> color_eyre::Result<()> {
             .get_all_entries()
             .into_iter()
             .filter(|e| e.section == target_section && e.is_done())
-            .max_by_key(|e| e.timestamp)
-            .map(|e| (e.timestamp, e.description.clone()));
+            .max_by_key(|e| e.started_at)
+            .map(|e| (e.started_at, e.description.clone()));
 
         if let Some((timestamp, description)) = last_entry_info {
             // Remove the done tag
@@ -48,8 +68,8 @@ pub fn handle_done(opts: DoneOptions) -> color_eyre::Result<()> {
 
             if let Some(entries) = doing_file.sections.get_mut(target_section) {
                 for entry in entries.iter_mut() {
-                    if entry.timestamp == timestamp && entry.description == description {
-                        entry.tags.remove("done");
+                    if entry.started_at == timestamp && entry.description == description {
+                        entry.unfinish();
                         entry_desc = entry.description.clone();
                         found = true;
                         break;
@@ -58,7 +78,7 @@ pub fn handle_done(opts: DoneOptions) -> color_eyre::Result<()> {
             }
 
             if found {
-                save_taskpaper(&doing_file)?;
+                save_taskpaper(&doing_file, &config)?;
                 println!("Removed @done tag from: {entry_desc}");
                 return Ok(());
             }
@@ -70,7 +90,7 @@ pub fn handle_done(opts: DoneOptions) -> color_eyre::Result<()> {
     }
 
     // If no entry text provided, mark last entry as done
-    if opts.entry.is_empty() {
+    if opts.entry.is_empty() && edited.is_none() {
         let target_section = opts.section.as_deref().unwrap_or("Currently");
 
         // Find the last entry (unfinished if --unfinished flag is set)
@@ -78,8 +98,8 @@ pub fn handle_done(opts: DoneOptions) -> color_eyre::Result<()> {
             .get_all_entries()
             .into_iter()
             .filter(|e| e.section == target_section && (!opts.unfinished || !e.is_done()))
-            .max_by_key(|e| e.timestamp)
-            .map(|e| (e.timestamp, e.description.clone()));
+            .max_by_key(|e| e.started_at)
+            .map(|e| (e.started_at, e.description.clone()));
 
         if let Some((timestamp, description)) = last_entry_info {
             // Mark it as done
@@ -88,7 +108,7 @@ pub fn handle_done(opts: DoneOptions) -> color_eyre::Result<()> {
 
             if let Some(entries) = doing_file.sections.get_mut(target_section) {
                 for entry in entries.iter_mut() {
-                    if entry.timestamp == timestamp && entry.description == description {
+                    if entry.started_at == timestamp && entry.description == description {
                         if entry.is_done() && !opts.unfinished {
                             return Err(color_eyre::eyre::eyre!(
                                 "Last entry is already marked @done"
@@ -97,14 +117,11 @@ pub fn handle_done(opts: DoneOptions) -> color_eyre::Result<()> {
 
                         // Calculate done time based on flags
                         let done_time =
-                            calculate_done_time(&opts.at, &opts.took, &entry.timestamp)?;
-                        entry.tags.insert(
-                            "done".to_string(),
-                            Some(done_time.format("%Y-%m-%d %H:%M").to_string()),
-                        );
+                            calculate_done_time(&opts.at, &opts.took, &entry.started_at)?;
+                        entry.finish(done_time);
 
                         entry_info = Some((
-                            entry.timestamp.format("%Y-%m-%d %H:%M").to_string(),
+                            entry.started_at.format("%Y-%m-%d %H:%M").to_string(),
                             entry.description.clone(),
                             done_time.format("%Y-%m-%d %H:%M").to_string(),
                         ));
@@ -122,7 +139,7 @@ pub fn handle_done(opts: DoneOptions) -> color_eyre::Result<()> {
                     // Find and remove from current section
                     if let Some(entries) = doing_file.sections.get_mut(target_section)
                         && let Some(pos) = entries.iter().position(|e| {
-                            e.timestamp.format("%Y-%m-%d %H:%M").to_string() == *time_str
+                            e.started_at.format("%Y-%m-%d %H:%M").to_string() == *time_str
                                 && e.description == *desc
                         })
                     {
@@ -138,7 +155,7 @@ pub fn handle_done(opts: DoneOptions) -> color_eyre::Result<()> {
                     }
                 }
 
-                save_taskpaper(&doing_file)?;
+                save_taskpaper(&doing_file, &config)?;
                 if let Some((time_str, desc, done_time_str)) = entry_info {
                     println!("{time_str}: {desc} @done({done_time_str})");
                 }
@@ -150,12 +167,82 @@ pub fn handle_done(opts: DoneOptions) -> color_eyre::Result<()> {
     }
 
     // Create a new entry and mark it as done
-    let entry_text = if opts.editor {
-        // TODO: Implement editor support
-        return Err(color_eyre::eyre::eyre!(
-            "Editor support not yet implemented"
-        ));
-    } else if opts.entry.is_empty() {
+    let mut new_entry = match edited {
+        Some(entry) => entry,
+        None => entry_from_args(&opts)?,
+    };
+
+    if !opts.noauto {
+        Autotagger::new(&config)?.tag_new_entry(&mut new_entry);
+    }
+
+    // Handle time calculations
+    let (start_time, done_time) = if let Some(from_str) = opts.from {
+        // Parse "from X to Y" format
+        parse_from_range(&from_str)?
+    } else {
+        // Calculate times based on other flags
+        let (start, done) = calculate_times(opts.back, opts.at, opts.took)?;
+        (start, done)
+    };
+
+    new_entry = new_entry.with_started_at(start_time);
+
+    // Add @done tag with timestamp
+    new_entry.finish(done_time);
+
+    doing_file.add_entry(new_entry.clone());
+    save_taskpaper(&doing_file, &config)?;
+
+    println!(
+        "{}: {} @done({})",
+        new_entry.started_at.format("%Y-%m-%d %H:%M"),
+        new_entry.description,
+        done_time.format("%Y-%m-%d %H:%M")
+    );
+
+    if !new_entry.tags.is_empty() {
+        let tags_str: Vec<String> = new_entry
+            .tags
+            .iter()
+            .filter(|(k, _)| k != &"done") // Don't show done tag again
+            .map(|(k, v)| {
+                if let Some(val) = v {
+                    format!("@{k}({val})")
+                } else {
+                    format!("@{k}")
+                }
+            })
+            .collect();
+        if !tags_str.is_empty() {
+            println!("  {}", tags_str.join(" "));
+        }
+    }
+
+    if let Some(note) = &new_entry.note {
+        println!("  Note: {}", note.lines().next().unwrap_or(""));
+        for line in note.lines().skip(1) {
+            println!("        {line}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Section a new entry goes to
+fn new_entry_section(opts: &DoneOptions) -> String {
+    if opts.archive {
+        "Archive".to_string()
+    } else {
+        opts.section
+            .clone()
+            .unwrap_or_else(|| "Currently".to_string())
+    }
+}
+
+/// Build a new entry from the command line, prompting for whatever is missing
+fn entry_from_args(opts: &DoneOptions) -> color_eyre::Result<Entry> {
+    let entry_text = if opts.entry.is_empty() {
         // Interactive prompt
         print!("What did you finish? ");
         io::stdout().flush()?;
@@ -180,7 +267,7 @@ pub fn handle_done(opts: DoneOptions) -> color_eyre::Result<()> {
     };
 
     // Get final note
-    let final_note = if let Some(n) = opts.note {
+    let final_note = if let Some(n) = opts.note.clone() {
         Some(n)
     } else if let Some(n) = parsed_note {
         Some(n)
@@ -222,13 +309,6 @@ pub fn handle_done(opts: DoneOptions) -> color_eyre::Result<()> {
         None
     };
 
-    // Determine section
-    let target_section = if opts.archive {
-        "Archive".to_string()
-    } else {
-        opts.section.unwrap_or_else(|| "Currently".to_string())
-    };
-
     // Extract tags from entry text
     let tag_regex = Regex::new(r"@(\w+)(?:\(([^)]+)\))?")?;
     let mut tags = Vec::new();
@@ -245,72 +325,19 @@ pub fn handle_done(opts: DoneOptions) -> color_eyre::Result<()> {
         .to_string();
 
     // Create entry with clean description
-    let mut new_entry = Entry::new(clean_description, target_section);
+    let mut new_entry = Entry::new(clean_description, new_entry_section(opts));
 
     // Add tags
     for (tag_name, tag_value) in tags {
         new_entry = new_entry.with_tag(tag_name, tag_value);
     }
 
-    // Handle time calculations
-    let (start_time, done_time) = if let Some(from_str) = opts.from {
-        // Parse "from X to Y" format
-        parse_from_range(&from_str)?
-    } else {
-        // Calculate times based on other flags
-        let (start, done) = calculate_times(opts.back, opts.at, opts.took)?;
-        (start, done)
-    };
-
-    new_entry = new_entry.with_timestamp(start_time);
-
-    // Add @done tag with timestamp
-    new_entry.tags.insert(
-        "done".to_string(),
-        Some(done_time.format("%Y-%m-%d %H:%M").to_string()),
-    );
-
     // Add note if present
     if let Some(note_text) = final_note {
         new_entry = new_entry.with_note(note_text);
     }
 
-    doing_file.add_entry(new_entry.clone());
-    save_taskpaper(&doing_file)?;
-
-    println!(
-        "{}: {} @done({})",
-        new_entry.timestamp.format("%Y-%m-%d %H:%M"),
-        new_entry.description,
-        done_time.format("%Y-%m-%d %H:%M")
-    );
-
-    if !new_entry.tags.is_empty() {
-        let tags_str: Vec<String> = new_entry
-            .tags
-            .iter()
-            .filter(|(k, _)| k != &"done") // Don't show done tag again
-            .map(|(k, v)| {
-                if let Some(val) = v {
-                    format!("@{k}({val})")
-                } else {
-                    format!("@{k}")
-                }
-            })
-            .collect();
-        if !tags_str.is_empty() {
-            println!("  {}", tags_str.join(" "));
-        }
-    }
-
-    if let Some(note) = &new_entry.note {
-        println!("  Note: {}", note.lines().next().unwrap_or(""));
-        for line in note.lines().skip(1) {
-            println!("        {line}");
-        }
-    }
-
-    Ok(())
+    Ok(new_entry)
 }
 
 fn calculate_done_time(
@@ -1,5 +1,6 @@
 use crate::models::Entry;
-use crate::storage::{Config, parse_taskpaper, save_taskpaper};
+use crate::picker;
+use crate::storage::{Config, FileLock, parse_taskpaper, save_taskpaper};
 use chrono::{DateTime, Duration, Local};
 use chrono_english::{Dialect, parse_date_string};
 use regex::Regex;
@@ -26,15 +27,11 @@ pub struct FinishOptions {
 }
 
 pub fn handle_finish(opts: FinishOptions) -> color_eyre::Result<()> {
-    if opts.interactive {
-        return Err(color_eyre::eyre::eyre!(
-            "Interactive mode not yet implemented"
-        ));
-    }
-
-    let config = Config::load();
+    let config = Config::try_load()?;
     let doing_file_path = config.doing_file_path();
 
+    let mut lock = FileLock::acquire(&doing_file_path)?;
+
     let mut doing_file = parse_taskpaper(&doing_file_path)?;
 
     // Determine which sections to work with
@@ -49,13 +46,13 @@ pub fn handle_finish(opts: FinishOptions) -> color_eyre::Result<()> {
     for section in &target_sections {
         if let Some(entries) = doing_file.sections.get(section) {
             for entry in entries {
-                all_entries.push((section.clone(), entry.timestamp, entry.description.clone()));
+                all_entries.push((section.clone(), entry.started_at, entry.description.clone()));
             }
         }
     }
 
     // Sort by timestamp (newest first)
-    all_entries.sort_by(|a, b| b.1.cmp(&a.1));
+    all_entries.sort_by_key(|e| std::cmp::Reverse(e.1));
 
     // Filter entries based on search criteria
     let mut filtered_entries = all_entries;
@@ -84,13 +81,13 @@ pub fn handle_finish(opts: FinishOptions) -> color_eyre::Result<()> {
                 for entry in entries {
                     all_entries_again.push((
                         section.clone(),
-                        entry.timestamp,
+                        entry.started_at,
                         entry.description.clone(),
                     ));
                 }
             }
         }
-        all_entries_again.sort_by(|a, b| b.1.cmp(&a.1));
+        all_entries_again.sort_by_key(|e| std::cmp::Reverse(e.1));
 
         filtered_entries = all_entries_again
             .into_iter()
@@ -98,8 +95,25 @@ pub fn handle_finish(opts: FinishOptions) -> color_eyre::Result<()> {
             .collect();
     }
 
-    // Take only the requested count
-    let entries_to_finish: Vec<_> = filtered_entries.into_iter().take(opts.count).collect();
+    // Let the user choose, or take only the requested count
+    let entries_to_finish: Vec<_> = if opts.interactive && !filtered_entries.is_empty() {
+        let candidates: Vec<&Entry> = filtered_entries
+            .iter()
+            .filter_map(|key| find_entry(&doing_file, key))
+            .collect();
+        let chosen =
+            lock.suspend(|| picker::pick_entries(&candidates, "Select entries to finish", true))?;
+        if chosen.is_empty() {
+            println!("Cancelled");
+            return Ok(());
+        }
+        filtered_entries
+            .into_iter()
+            .filter(|key| find_entry(&doing_file, key).is_some_and(|e| chosen.contains(&e.uuid)))
+            .collect()
+    } else {
+        filtered_entries.into_iter().take(opts.count).collect()
+    };
 
     if entries_to_finish.is_empty() {
         return Err(color_eyre::eyre::eyre!(
@@ -116,7 +130,7 @@ pub fn handle_finish(opts: FinishOptions) -> color_eyre::Result<()> {
     for (section, timestamp, description) in entries_to_finish {
         if let Some(entries) = doing_file.sections.get(&section) {
             for entry in entries {
-                if entry.timestamp == timestamp && entry.description == description {
+                if entry.started_at == timestamp && entry.description == description {
                     // Skip if already done and not updating
                     if entry.is_done() && !opts.update && !opts.remove {
                         continue;
@@ -127,12 +141,17 @@ pub fn handle_finish(opts: FinishOptions) -> color_eyre::Result<()> {
                     } else {
                         // Calculate done time
                         let done_time = if opts.auto {
-                            calculate_auto_done_time(&doing_file, &entry.timestamp)?
+                            calculate_auto_done_time(&doing_file, &entry.started_at)?
                         } else if let Some(from_str) = &opts.from {
                             let (_, end_time) = parse_from_range(from_str)?;
                             end_time
                         } else {
-                            calculate_done_time(&opts.at, &opts.back, &opts.took, &entry.timestamp)?
+                            calculate_done_time(
+                                &opts.at,
+                                &opts.back,
+                                &opts.took,
+                                &entry.started_at,
+                            )?
                         };
 
                         if opts.date {
@@ -162,32 +181,30 @@ pub fn handle_finish(opts: FinishOptions) -> color_eyre::Result<()> {
     for (section, timestamp, description, done_time) in updates {
         if let Some(entries) = doing_file.sections.get_mut(&section) {
             for entry in entries.iter_mut() {
-                if entry.timestamp == timestamp && entry.description == description {
+                if entry.started_at == timestamp && entry.description == description {
                     if done_time.is_none() {
                         // Remove the done tag
-                        entry.tags.remove("done");
+                        entry.unfinish();
                         println!("Removed @done tag from: {}", entry.description);
                     } else if let Some(dt) = done_time {
                         // Add or update done tag
                         if opts.date {
-                            entry.tags.insert(
-                                "done".to_string(),
-                                Some(dt.format("%Y-%m-%d %H:%M").to_string()),
-                            );
+                            entry.finish(dt);
 
                             println!(
                                 "{}: {} @done({})",
-                                entry.timestamp.format("%Y-%m-%d %H:%M"),
+                                entry.started_at.format("%Y-%m-%d %H:%M"),
                                 entry.description,
                                 dt.format("%Y-%m-%d %H:%M")
                             );
                         } else {
                             // No date - just add @done without timestamp
+                            entry.unfinish();
                             entry.tags.insert("done".to_string(), None);
 
                             println!(
                                 "{}: {} @done",
-                                entry.timestamp.format("%Y-%m-%d %H:%M"),
+                                entry.started_at.format("%Y-%m-%d %H:%M"),
                                 entry.description
                             );
                         }
@@ -226,7 +243,7 @@ pub fn handle_finish(opts: FinishOptions) -> color_eyre::Result<()> {
         }
     }
 
-    save_taskpaper(&doing_file)?;
+    save_taskpaper(&doing_file, &config)?;
 
     if finished_count == 0 {
         return Err(color_eyre::eyre::eyre!("No entries were finished"));
@@ -235,6 +252,18 @@ pub fn handle_finish(opts: FinishOptions) -> color_eyre::Result<()> {
     Ok(())
 }
 
+/// The entry a `(section, timestamp, description)` key refers to
+fn find_entry<'a>(
+    doing_file: &'a crate::models::DoingFile,
+    (section, timestamp, description): &(String, DateTime<Local>, String),
+) -> Option<&'a Entry> {
+    doing_file
+        .sections
+        .get(section)?
+        .iter()
+        .find(|entry| entry.started_at == *timestamp && entry.description == *description)
+}
+
 fn filter_by_search(
     entries: Vec<(String, DateTime<Local>, String)>,
     search_query: &str,
@@ -285,7 +314,7 @@ fn filter_by_tag(
             // Find the actual entry to check tags
             if let Some(section_entries) = doing_file.sections.get(section) {
                 for entry in section_entries {
-                    if entry.timestamp == *timestamp && entry.description == *description {
+                    if entry.started_at == *timestamp && entry.description == *description {
                         // Check if entry has any of the requested tags
                         for tag in &tags {
                             if tag.contains('*') || tag.contains('?') {
@@ -322,7 +351,7 @@ fn filter_unfinished(
             // Find the actual entry to check if done
             if let Some(section_entries) = doing_file.sections.get(section) {
                 for entry in section_entries {
-                    if entry.timestamp == *timestamp && entry.description == *description {
+                    if entry.started_at == *timestamp && entry.description == *description {
                         return !entry.is_done();
                     }
                 }
@@ -348,14 +377,14 @@ fn calculate_auto_done_time(
     }
 
     // Sort by timestamp
-    all_entries.sort_by_key(|e| e.timestamp);
+    all_entries.sort_by_key(|e| e.started_at);
 
     // Find the entry after our target entry
     for i in 0..all_entries.len() {
-        if all_entries[i].timestamp == *entry_time {
+        if all_entries[i].started_at == *entry_time {
             if i + 1 < all_entries.len() {
                 // Return 1 minute before the next entry
-                return Ok(all_entries[i + 1].timestamp - Duration::minutes(1));
+                return Ok(all_entries[i + 1].started_at - Duration::minutes(1));
             }
             break;
         }
@@ -1,8 +1,15 @@
+use crate::commands::show::report_bulk_edit;
+use crate::commands::view;
 use crate::display::{DisplayOptions, OutputFormat, SortOrder, TagSort, display_entries};
+use crate::editor;
 use crate::filtering::{
     BoolOp, CaseSensitivity, FilterOptions, filter_entries, parse_date_filter, parse_date_range,
 };
-use crate::storage::{Config, parse_taskpaper};
+use crate::models::Entry;
+use crate::picker;
+use crate::storage::config::{View, ViewCommand};
+use crate::storage::{Config, FileLock, parse_taskpaper};
+use crate::template;
 use std::io::{self, Write};
 
 #[derive(Debug)]
@@ -27,8 +34,8 @@ pub struct GrepDisplayOptions {
     pub hilite: bool,
     pub output: Option<String>,
     pub times: bool,
-    pub tag_order: String,
-    pub tag_sort: String,
+    pub tag_order: Option<String>,
+    pub tag_sort: Option<String>,
     pub totals: bool,
 }
 
@@ -36,31 +43,35 @@ pub struct GrepDisplayOptions {
 pub struct GrepActionOptions {
     pub delete: bool,
     pub interactive: bool,
-    pub _editor: bool,
+    pub editor: bool,
 }
 
 #[derive(Debug)]
 pub struct GrepConfigOptions {
-    pub _config_template: Option<String>,
-    pub _save: Option<String>,
-    pub _template: Option<String>,
-    pub _title: Option<String>,
+    pub config_template: Option<String>,
+    pub save: Option<String>,
+    pub template: Option<String>,
+    pub title: Option<String>,
 }
 
 pub fn handle_grep(
     filter_opts: GrepFilterOptions,
     display_opts: GrepDisplayOptions,
     action_opts: GrepActionOptions,
-    _config_opts: GrepConfigOptions,
+    config_opts: GrepConfigOptions,
 ) -> color_eyre::Result<()> {
-    if action_opts.interactive {
-        return Err(color_eyre::eyre::eyre!(
-            "Interactive mode not yet implemented"
-        ));
+    if let Some(name) = &config_opts.save {
+        view::save_view(name, &saved_view(&filter_opts, &display_opts, &config_opts))?;
     }
 
-    let config = Config::load();
+    let config = Config::try_load()?;
+    let template = template::resolve(
+        config_opts.template.as_deref(),
+        config_opts.config_template.as_deref(),
+        &config,
+    )?;
     let doing_file_path = config.doing_file_path();
+    let mut lock = FileLock::acquire(&doing_file_path)?;
     let mut doing_file = parse_taskpaper(&doing_file_path)?;
 
     // Parse tags from --tag option
@@ -82,12 +93,7 @@ pub fn handle_grep(
         },
         exact: filter_opts.exact,
         not: filter_opts.not,
-        bool_op: match filter_opts.bool_op.as_str() {
-            "and" | "AND" => BoolOp::And,
-            "or" | "OR" => BoolOp::Or,
-            "not" | "NOT" => BoolOp::Not,
-            _ => BoolOp::Pattern,
-        },
+        bool_op: BoolOp::from(filter_opts.bool_op.as_str()),
         only_timed: filter_opts.only_timed,
         val: filter_opts.val,
         ..Default::default()
@@ -108,13 +114,31 @@ pub fn handle_grep(
     }
 
     // Filter entries
-    let entries = filter_entries(&doing_file, &filter_options)?;
+    let mut entries = filter_entries(&doing_file, &filter_options)?;
 
     if entries.is_empty() {
         println!("No entries found matching '{}'", filter_opts.pattern);
         return Ok(());
     }
 
+    // Narrow the results down to the entries picked from a menu
+    if action_opts.interactive {
+        let candidates: Vec<&Entry> = entries.iter().map(|(_, entry)| entry).collect();
+        let chosen = lock.suspend(|| picker::pick_entries(&candidates, "Select entries", true))?;
+        if chosen.is_empty() {
+            println!("Cancelled");
+            return Ok(());
+        }
+        entries.retain(|(_, entry)| chosen.contains(&entry.uuid));
+    }
+
+    if action_opts.editor {
+        // Don't hold the lock while the editor is open; edits are applied to a fresh read
+        drop(lock);
+        let entries: Vec<_> = entries.into_iter().map(|(_, entry)| entry).collect();
+        return report_bulk_edit(editor::edit_entries(&config, &entries)?);
+    }
+
     // Handle delete mode
     if action_opts.delete {
         print!(
@@ -144,7 +168,7 @@ pub fn handle_grep(
             section.retain(|entry| !uuids_to_delete.contains(&entry.uuid));
         }
 
-        crate::storage::save_taskpaper(&doing_file)?;
+        crate::storage::save_taskpaper(&doing_file, &config)?;
         println!(
             "Deleted {} {}.",
             entries.len(),
@@ -171,17 +195,20 @@ pub fn handle_grep(
             Some("html") => OutputFormat::Html,
             Some("taskpaper") => OutputFormat::TaskPaper,
             Some("timeline") => OutputFormat::Timeline,
+            Some("ics") => OutputFormat::Ics,
             _ => OutputFormat::Default,
         },
-        tag_sort: match display_opts.tag_sort.as_str() {
+        tag_sort: display_opts.tag_sort.as_deref().map(|sort| match sort {
             "time" => TagSort::Time,
             _ => TagSort::Name,
-        },
-        tag_order: match display_opts.tag_order.as_str() {
+        }),
+        tag_order: display_opts.tag_order.as_deref().map(|order| match order {
             "desc" => SortOrder::Desc,
             _ => SortOrder::Asc,
-        },
+        }),
         section_filter: filter_options.sections.clone(),
+        template,
+        title: config_opts.title,
     };
 
     // Display entries
@@ -189,3 +216,42 @@ pub fn handle_grep(
 
     Ok(())
 }
+
+/// The options of this search as a view for `--save`
+fn saved_view(
+    filter_opts: &GrepFilterOptions,
+    display_opts: &GrepDisplayOptions,
+    config_opts: &GrepConfigOptions,
+) -> View {
+    View {
+        command: ViewCommand::Grep,
+        sections: filter_opts.sections.clone(),
+        tags: filter_opts
+            .tag
+            .iter()
+            .flat_map(|tags| tags.split(','))
+            .map(|tag| tag.trim().to_string())
+            .collect(),
+        search: Some(filter_opts.pattern.clone()),
+        val: filter_opts.val.clone(),
+        bool_op: Some(filter_opts.bool_op.clone()),
+        case: Some(filter_opts.case.clone()),
+        exact: filter_opts.exact,
+        not: filter_opts.not,
+        only_timed: filter_opts.only_timed,
+        after: filter_opts.after.clone(),
+        before: filter_opts.before.clone(),
+        from: filter_opts.from.clone(),
+        output: display_opts.output.clone(),
+        tag_order: display_opts.tag_order.clone(),
+        tag_sort: display_opts.tag_sort.clone(),
+        duration: display_opts.duration,
+        hilite: display_opts.hilite,
+        times: display_opts.times,
+        totals: display_opts.totals,
+        template: config_opts.template.clone(),
+        config_template: config_opts.config_template.clone(),
+        title: config_opts.title.clone(),
+        ..View::default()
+    }
+}
@@ -0,0 +1,57 @@
+use crate::storage::history::HistoryRecord;
+use crate::storage::{Config, History};
+use color_eyre::Result;
+
+pub fn handle_history(count: Option<usize>) -> Result<()> {
+    let config = Config::try_load()?;
+    let history = History::open(&config)?;
+
+    let undo = history.undo_records();
+    let redo = history.redo_records();
+
+    if undo.is_empty() && redo.is_empty() {
+        println!("No history recorded");
+        return Ok(());
+    }
+
+    if !redo.is_empty() {
+        println!("Redo:");
+        for record in redo.iter().rev().take(count.unwrap_or(redo.len())) {
+            println!("       {}", format_record(record));
+        }
+        println!();
+    }
+
+    if !undo.is_empty() {
+        println!("Undo:");
+        for (index, record) in undo
+            .iter()
+            .rev()
+            .take(count.unwrap_or(undo.len()))
+            .enumerate()
+        {
+            println!("  {:>3}  {}", index + 1, format_record(record));
+        }
+    }
+
+    println!();
+    println!("Backups: {}", history.dir().display());
+
+    Ok(())
+}
+
+fn format_record(record: &HistoryRecord) -> String {
+    let files: Vec<String> = record
+        .files
+        .iter()
+        .filter_map(|file| file.path.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .collect();
+
+    format!(
+        "{}  {}  [{}]",
+        record.timestamp.format("%Y-%m-%d %H:%M:%S"),
+        record.command,
+        files.join(", ")
+    )
+}
@@ -0,0 +1,146 @@
+use crate::import::{ImportType, read_entries};
+use crate::models::Entry;
+use crate::storage::{Config, FileLock, parse_taskpaper, parse_title, save_taskpaper};
+use color_eyre::Result;
+use color_eyre::eyre::WrapErr;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct ImportOptions {
+    pub file: PathBuf,
+    pub kind: String,
+    pub section: Option<String>,
+    pub dry_run: bool,
+    /// Put in front of every imported title
+    pub prefix: Option<String>,
+    /// Comma separated tags added to every imported entry
+    pub tag: Option<String>,
+}
+
+pub fn handle_import(opts: ImportOptions) -> Result<()> {
+    let kind = ImportType::parse(&opts.kind)?;
+    let content = fs::read_to_string(&opts.file)
+        .wrap_err_with(|| format!("could not read {}", opts.file.display()))?;
+    let entries = read_entries(kind, &content)
+        .wrap_err_with(|| format!("could not import {}", opts.file.display()))?;
+
+    let config = Config::try_load()?;
+    let doing_file_path = config.doing_file_path();
+    let _lock = FileLock::acquire(&doing_file_path)?;
+    let mut doing_file = parse_taskpaper(&doing_file_path)?;
+
+    let (_, extra_tags) = parse_title(
+        &opts
+            .tag
+            .iter()
+            .flat_map(|tags| tags.split(','))
+            .map(|tag| format!(" @{}", tag.trim().trim_start_matches('@')))
+            .collect::<String>(),
+    );
+
+    let mut seen = Seen::default();
+    for entry in doing_file.get_all_entries() {
+        seen.insert(entry);
+    }
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    for mut entry in entries {
+        // Titled as it will be stored, so importing the same file again with
+        // the same prefix finds the entries added the first time
+        if let Some(prefix) = &opts.prefix {
+            entry.description = format!("{prefix}{}", entry.description);
+        }
+        for (tag, value) in &extra_tags {
+            entry.tags.insert(tag.clone(), value.clone());
+        }
+
+        // Entries already in the file, or repeated in the export, are only added once
+        if seen.contains(&entry) {
+            skipped += 1;
+            continue;
+        }
+        if seen.ids.contains_key(&entry.uuid) {
+            // Another entry has this id, so this one gets a new one
+            entry.uuid = Uuid::new_v4();
+        }
+        seen.insert(&entry);
+        let section = opts
+            .section
+            .clone()
+            .filter(|section| !section.is_empty())
+            .or_else(|| Some(entry.section.clone()).filter(|section| !section.is_empty()))
+            .unwrap_or_else(|| "Currently".to_string());
+        // Sections are matched by name, whatever their case in the other file
+        entry.section = doing_file
+            .sections
+            .keys()
+            .find(|existing| existing.eq_ignore_ascii_case(&section))
+            .cloned()
+            .unwrap_or(section);
+
+        println!(
+            "{}{}: {} [{}]",
+            if opts.dry_run { "Would import " } else { "" },
+            entry.started_at.format("%Y-%m-%d %H:%M"),
+            entry.title(),
+            entry.section
+        );
+        doing_file.add_entry(entry);
+        imported += 1;
+    }
+
+    if !opts.dry_run && imported > 0 {
+        save_taskpaper(&doing_file, &config)?;
+    }
+
+    println!(
+        "\n{} {imported} {}, skipped {skipped} {}.",
+        if opts.dry_run {
+            "Would import"
+        } else {
+            "Imported"
+        },
+        if imported == 1 { "entry" } else { "entries" },
+        if skipped == 1 {
+            "duplicate"
+        } else {
+            "duplicates"
+        }
+    );
+
+    Ok(())
+}
+
+/// Entries are the same if they start in the same minute and either share an
+/// id or have the same title. The same id on a different entry is a collision.
+#[derive(Default)]
+struct Seen {
+    /// Entry ids and the minute the entry starts
+    ids: HashMap<Uuid, String>,
+    starts: HashSet<(String, String)>,
+}
+
+impl Seen {
+    fn minute(entry: &Entry) -> String {
+        entry.started_at.format("%Y-%m-%d %H:%M").to_string()
+    }
+
+    fn contains(&self, entry: &Entry) -> bool {
+        let minute = Self::minute(entry);
+        self.ids.get(&entry.uuid) == Some(&minute)
+            || self
+                .starts
+                .contains(&(minute, entry.description.to_lowercase()))
+    }
+
+    fn insert(&mut self, entry: &Entry) {
+        let minute = Self::minute(entry);
+        self.ids.insert(entry.uuid, minute.clone());
+        self.starts
+            .insert((minute, entry.description.to_lowercase()));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::display::{OutputFormat, display_timesheet};
+use crate::filtering::{FilterOptions, filter_entries, parse_date_range};
+use crate::report::{month_of, week_of, whole_days};
+use crate::storage::{Config, parse_taskpaper};
+use crate::timesheet::{GroupBy, Timesheet};
+use chrono::Local;
+use color_eyre::eyre::eyre;
+
+#[derive(Debug)]
+pub struct InvoiceOptions {
+    pub week: bool,
+    pub month: bool,
+    pub from: Option<String>,
+    pub output: Option<String>,
+    pub by: String,
+    pub client: Option<String>,
+    pub sections: Vec<String>,
+    pub tag: Option<String>,
+    pub title: Option<String>,
+}
+
+pub fn handle_invoice(opts: InvoiceOptions) -> color_eyre::Result<()> {
+    let format = match opts.output.as_deref() {
+        None | Some("csv") => OutputFormat::Csv,
+        Some("html") => OutputFormat::Html,
+        Some(other) => {
+            return Err(eyre!("unknown timesheet format '{other}': use csv or html"));
+        }
+    };
+
+    display_timesheet(&timesheet(opts)?, &format)
+}
+
+/// The timesheet for the period and entries the options pick
+pub fn timesheet(opts: InvoiceOptions) -> color_eyre::Result<Timesheet> {
+    let config = Config::try_load()?;
+    let doing_file = parse_taskpaper(&config.doing_file_path())?;
+
+    let group_by = match opts.by.as_str() {
+        "client" => GroupBy::Client,
+        "section" => GroupBy::Section,
+        other => return Err(eyre!("unknown grouping '{other}': use client or section")),
+    };
+
+    let now = Local::now();
+    let (start, end, title) = match &opts.from {
+        Some(range) => {
+            let (start, end) = parse_date_range(range)?;
+            let (start, end) = whole_days(start, end, now);
+            let title = format!(
+                "Timesheet for {} to {}",
+                start.format("%Y-%m-%d"),
+                end.format("%Y-%m-%d")
+            );
+            (start, end, title)
+        }
+        None if opts.week => {
+            let (start, end, title) = week_of(now);
+            (start, end, format!("Timesheet, {title}"))
+        }
+        // Billing is monthly unless asked otherwise
+        None => {
+            let (start, end, title) = month_of(now);
+            (start, end, format!("Timesheet for {title}"))
+        }
+    };
+
+    let filter_options = FilterOptions {
+        tags: opts
+            .tag
+            .iter()
+            .flat_map(|tags| tags.split(','))
+            .map(|tag| tag.trim().to_string())
+            .collect(),
+        sections: opts.sections,
+        from: Some((start, Some(end))),
+        ..Default::default()
+    };
+    let entries = filter_entries(&doing_file, &filter_options)?;
+
+    let mut sheet = Timesheet::new(
+        opts.title.unwrap_or(title),
+        start,
+        end,
+        &entries,
+        &config.invoice,
+        group_by,
+    );
+    if let Some(client) = &opts.client {
+        sheet
+            .clients
+            .retain(|sheet| sheet.name.eq_ignore_ascii_case(client));
+    }
+
+    Ok(sheet)
+}
@@ -2,18 +2,18 @@ use crate::storage::{Config, parse_taskpaper};
 use chrono::Local;
 
 pub fn handle_last() -> color_eyre::Result<()> {
-    let config = Config::load();
+    let config = Config::try_load()?;
     let doing_file_path = config.doing_file_path();
 
     let doing_file = parse_taskpaper(&doing_file_path)?;
 
     if let Some(entry) = doing_file.get_last_entry() {
-        let time_ago = Local::now().signed_duration_since(entry.timestamp);
+        let time_ago = Local::now().signed_duration_since(entry.started_at);
         let time_str = format_duration(time_ago);
 
         println!(
             "{} - {} ({})",
-            entry.timestamp.format("%Y-%m-%d %H:%M"),
+            entry.started_at.format("%Y-%m-%d %H:%M"),
             entry.description,
             time_str
         );
@@ -1,12 +1,14 @@
+use crate::filtering::{BoolOp, matches_value_queries, parse_value_queries};
 use crate::models::Entry;
-use crate::storage::{Config, parse_taskpaper, save_taskpaper};
+use crate::picker;
+use crate::storage::{Config, FileLock, parse_taskpaper, save_taskpaper};
 use chrono::Local;
 use regex::Regex;
 use std::io::{self, Write};
 
 #[derive(Debug)]
 pub struct MarkOptions {
-    pub _bool_op: String,
+    pub bool_op: String,
     pub count: usize,
     pub case: String,
     pub date: bool,
@@ -18,7 +20,7 @@ pub struct MarkOptions {
     pub search: Option<String>,
     pub tag: Option<String>,
     pub unfinished: bool,
-    pub _val: Vec<String>,
+    pub val: Vec<String>,
     pub exact: bool,
 }
 
@@ -27,6 +29,8 @@ struct EntryFilter {
     sections: Vec<String>,
     search: Option<String>,
     tag: Option<String>,
+    val: Vec<String>,
+    bool_op: String,
     count: usize,
     unfinished: bool,
     exact: bool,
@@ -35,14 +39,8 @@ struct EntryFilter {
 }
 
 pub fn handle_mark(opts: MarkOptions) -> color_eyre::Result<()> {
-    if opts.interactive {
-        return Err(color_eyre::eyre::eyre!(
-            "Interactive mode not yet implemented"
-        ));
-    }
-
     // Validate count and force
-    if opts.count == 0 && !opts.force {
+    if opts.count == 0 && !opts.force && !opts.interactive {
         print!("Are you sure you want to flag all entries? [y/N] ");
         io::stdout().flush()?;
 
@@ -55,9 +53,11 @@ pub fn handle_mark(opts: MarkOptions) -> color_eyre::Result<()> {
         }
     }
 
-    let config = Config::load();
+    let config = Config::try_load()?;
     let doing_file_path = config.doing_file_path();
 
+    let mut lock = FileLock::acquire(&doing_file_path)?;
+
     let mut doing_file = parse_taskpaper(&doing_file_path)?;
 
     // Find entries to modify
@@ -65,18 +65,35 @@ pub fn handle_mark(opts: MarkOptions) -> color_eyre::Result<()> {
         sections: opts.sections.clone(),
         search: opts.search.clone(),
         tag: opts.tag.clone(),
-        count: opts.count,
+        val: opts.val.clone(),
+        bool_op: opts.bool_op.clone(),
+        // Offer every match in the menu
+        count: if opts.interactive { 0 } else { opts.count },
         unfinished: opts.unfinished,
         exact: opts.exact,
         not: opts.not,
         case: opts.case.clone(),
     };
-    let entries_to_modify = find_entries_to_modify(&doing_file, filter)?;
+    let mut entries_to_modify = find_entries_to_modify(&doing_file, filter)?;
 
     if entries_to_modify.is_empty() {
         return Err(color_eyre::eyre::eyre!("No matching entries found"));
     }
 
+    if opts.interactive {
+        let candidates: Vec<&Entry> = entries_to_modify
+            .iter()
+            .filter_map(|(_, uuid)| doing_file.get_entry(*uuid))
+            .collect();
+        let chosen =
+            lock.suspend(|| picker::pick_entries(&candidates, "Select entries to flag", true))?;
+        if chosen.is_empty() {
+            println!("Cancelled");
+            return Ok(());
+        }
+        entries_to_modify.retain(|(_, uuid)| chosen.contains(uuid));
+    }
+
     // Modify entries
     let mut modified_count = 0;
     for (target_section, target_uuid) in entries_to_modify {
@@ -85,7 +102,7 @@ pub fn handle_mark(opts: MarkOptions) -> color_eyre::Result<()> {
                 if entry.uuid == target_uuid {
                     if opts.remove {
                         // Remove flagged tag
-                        entry.tags.remove("flagged");
+                        entry.tags.shift_remove("flagged");
                     } else {
                         // Add flagged tag
                         if opts.date {
@@ -101,7 +118,7 @@ pub fn handle_mark(opts: MarkOptions) -> color_eyre::Result<()> {
                     // Print updated entry
                     println!(
                         "{}: {} {}",
-                        entry.timestamp.format("%Y-%m-%d %H:%M"),
+                        entry.started_at.format("%Y-%m-%d %H:%M"),
                         entry.description,
                         format_tags(&entry.tags)
                     );
@@ -113,7 +130,7 @@ pub fn handle_mark(opts: MarkOptions) -> color_eyre::Result<()> {
         }
     }
 
-    save_taskpaper(&doing_file)?;
+    save_taskpaper(&doing_file, &config)?;
 
     let action = if opts.remove { "Unflagged" } else { "Flagged" };
     println!(
@@ -130,8 +147,8 @@ pub fn handle_mark(opts: MarkOptions) -> color_eyre::Result<()> {
     Ok(())
 }
 
-fn format_tags(tags: &std::collections::HashMap<String, Option<String>>) -> String {
-    let mut tag_strs: Vec<String> = tags
+fn format_tags(tags: &indexmap::IndexMap<String, Option<String>>) -> String {
+    let tag_strs: Vec<String> = tags
         .iter()
         .map(|(tag, value)| {
             if let Some(val) = value {
@@ -141,7 +158,6 @@ fn format_tags(tags: &std::collections::HashMap<String, Option<String>>) -> Stri
             }
         })
         .collect();
-    tag_strs.sort();
     tag_strs.join(" ")
 }
 
@@ -167,7 +183,7 @@ fn find_entries_to_modify(
     }
 
     // Sort by timestamp (newest first)
-    all_entries.sort_by(|a, b| b.1.timestamp.cmp(&a.1.timestamp));
+    all_entries.sort_by_key(|e| std::cmp::Reverse(e.1.started_at));
 
     // Apply filters
     let mut filtered_entries = all_entries;
@@ -183,6 +199,13 @@ fn find_entries_to_modify(
         filtered_entries = filter_by_tag(filtered_entries, tag_query)?;
     }
 
+    // Apply tag value queries
+    if !filter.val.is_empty() {
+        let queries = parse_value_queries(&filter.val)?;
+        let bool_op = BoolOp::from(filter.bool_op.as_str());
+        filtered_entries.retain(|(_, entry)| matches_value_queries(entry, &queries, &bool_op));
+    }
+
     // Apply unfinished filter
     if filter.unfinished {
         filtered_entries.retain(|(_, entry)| !entry.is_done());
@@ -199,7 +222,7 @@ fn find_entries_to_modify(
                 }
             }
         }
-        all_entries_again.sort_by(|a, b| b.1.timestamp.cmp(&a.1.timestamp));
+        all_entries_again.sort_by_key(|e| std::cmp::Reverse(e.1.started_at));
 
         let filtered_uuids: std::collections::HashSet<_> =
             filtered_entries.iter().map(|(_, e)| e.uuid).collect();
@@ -1,16 +1,21 @@
 pub mod again;
 pub mod archive;
 pub mod cancel;
+pub mod config;
 pub mod delete;
 pub mod done;
 pub mod finish;
 pub mod grep;
+pub mod history;
+pub mod import;
+pub mod invoice;
 pub mod last;
 pub mod mark;
 pub mod note;
 pub mod now;
 pub mod on;
 pub mod recent;
+pub mod report;
 pub mod reset;
 pub mod rotate;
 pub mod sections;
@@ -19,6 +24,8 @@ pub mod since;
 pub mod tag;
 pub mod tags;
 pub mod today;
+pub mod undo;
+pub mod view;
 pub mod yesterday;
 
 #[cfg(test)]
@@ -27,18 +34,23 @@ mod tests;
 pub use again::{AgainOptions, handle_again};
 pub use archive::{ArchiveOptions, handle_archive};
 pub use cancel::{CancelOptions, handle_cancel};
+pub use config::handle_config;
 pub use delete::{DeleteOptions, handle_delete};
 pub use done::{DoneOptions, handle_done};
 pub use finish::{FinishOptions, handle_finish};
 pub use grep::{
     GrepActionOptions, GrepConfigOptions, GrepDisplayOptions, GrepFilterOptions, handle_grep,
 };
+pub use history::handle_history;
+pub use import::{ImportOptions, handle_import};
+pub use invoice::{InvoiceOptions, handle_invoice};
 pub use last::handle_last;
 pub use mark::{MarkOptions, handle_mark};
 pub use note::{NoteFilterOptions, NoteOptions, handle_note};
 pub use now::{NowOptions, handle_now};
 pub use on::{OnConfigOptions, OnDisplayOptions, OnFilterOptions, handle_on};
 pub use recent::handle_recent;
+pub use report::{ReportOptions, handle_report};
 pub use reset::{ResetOptions, handle_reset};
 pub use rotate::{RotateOptions, handle_rotate};
 pub use sections::handle_sections;
@@ -49,4 +61,6 @@ pub use since::{SinceConfigOptions, SinceDisplayOptions, SinceFilterOptions, han
 pub use tag::{TagOptions, handle_tag};
 pub use tags::{TagsDisplayOptions, TagsFilterOptions, handle_tags};
 pub use today::handle_today;
+pub use undo::{handle_redo, handle_undo};
+pub use view::{handle_view, handle_views};
 pub use yesterday::{YesterdayOptions, handle_yesterday};
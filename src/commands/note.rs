@@ -1,5 +1,8 @@
+use crate::editor;
+use crate::filtering::{BoolOp, matches_value_queries, parse_value_queries};
 use crate::models::Entry;
-use crate::storage::{Config, parse_taskpaper, save_taskpaper};
+use crate::picker;
+use crate::storage::{Config, FileLock, parse_taskpaper, save_taskpaper};
 use regex::Regex;
 use std::io;
 
@@ -8,6 +11,8 @@ pub struct NoteFilterOptions {
     pub sections: Vec<String>,
     pub search: Option<String>,
     pub tag: Option<String>,
+    pub val: Vec<String>,
+    pub bool_op: String,
     pub case: String,
     pub exact: bool,
     pub not: bool,
@@ -26,31 +31,21 @@ pub fn handle_note(
     note_opts: NoteOptions,
     interactive: bool,
 ) -> color_eyre::Result<()> {
-    if interactive {
-        return Err(color_eyre::eyre::eyre!(
-            "Interactive mode not yet implemented"
-        ));
-    }
-
-    if note_opts.editor {
-        return Err(color_eyre::eyre::eyre!("Editor mode not yet implemented"));
-    }
-
-    let config = Config::load();
+    let config = Config::try_load()?;
     let doing_file_path = config.doing_file_path();
 
-    let mut doing_file = parse_taskpaper(&doing_file_path)?;
+    // Choose the entry before locking, so menus and prompts don't hold up
+    // other commands
+    let doing_file = parse_taskpaper(&doing_file_path)?;
+    let Some(entry_to_modify) = find_entry_to_modify(&doing_file, &filter_opts, interactive)?
+    else {
+        println!("Cancelled");
+        return Ok(());
+    };
 
-    // Find the entry to modify
-    let entry_to_modify = find_entry_to_modify(
-        &doing_file,
-        &filter_opts.sections,
-        filter_opts.search.as_deref(),
-        filter_opts.tag.as_deref(),
-        filter_opts.exact,
-        filter_opts.not,
-        &filter_opts.case,
-    )?;
+    if note_opts.editor {
+        return edit_note_in_editor(&config, &entry_to_modify);
+    }
 
     // Get the note text
     let note_text = if !note_opts.note.is_empty() {
@@ -92,6 +87,9 @@ pub fn handle_note(
         None
     };
 
+    let _lock = FileLock::acquire(&doing_file_path)?;
+    let mut doing_file = parse_taskpaper(&doing_file_path)?;
+
     // Find and modify the entry
     let mut modified = false;
     let target_uuid = entry_to_modify.uuid;
@@ -137,19 +135,59 @@ pub fn handle_note(
         return Err(color_eyre::eyre::eyre!("Entry not found"));
     }
 
-    save_taskpaper(&doing_file)?;
+    save_taskpaper(&doing_file, &config)?;
     Ok(())
 }
 
+/// Replace the note of `target` with one written in the editor.
+/// The doing file is only locked once the editor has closed.
+fn edit_note_in_editor(config: &Config, target: &Entry) -> color_eyre::Result<()> {
+    let Some(note) = editor::edit_note(target.note.as_deref())? else {
+        println!("Empty note, nothing changed");
+        return Ok(());
+    };
+
+    let doing_file_path = config.doing_file_path();
+    let _lock = FileLock::acquire(&doing_file_path)?;
+    let mut doing_file = parse_taskpaper(&doing_file_path)?;
+    let mut entry = doing_file
+        .get_all_entries()
+        .into_iter()
+        .find(|entry| entry.uuid == target.uuid)
+        .cloned()
+        .ok_or_else(|| color_eyre::eyre::eyre!("Entry not found"))?;
+
+    entry.note = Some(note);
+    doing_file.update_entry(entry.clone());
+    save_taskpaper(&doing_file, config)?;
+
+    println!("Note replaced for: {}", entry.description);
+    if let Some(note) = &entry.note {
+        for line in note.lines() {
+            println!("  {line}");
+        }
+    }
+    Ok(())
+}
+
+/// The most recent matching entry, or the one picked from a menu when
+/// `interactive`. `None` means the user cancelled the menu.
 fn find_entry_to_modify(
     doing_file: &crate::models::DoingFile,
-    sections: &[String],
-    search: Option<&str>,
-    tag: Option<&str>,
-    exact: bool,
-    not: bool,
-    case: &str,
-) -> Result<Entry, color_eyre::eyre::Error> {
+    filter_opts: &NoteFilterOptions,
+    interactive: bool,
+) -> Result<Option<Entry>, color_eyre::eyre::Error> {
+    let NoteFilterOptions {
+        sections,
+        search,
+        tag,
+        val,
+        bool_op,
+        case,
+        exact,
+        not,
+    } = filter_opts;
+
     // Determine which sections to search
     let target_sections: Vec<String> = if sections.is_empty() {
         // Get all section names
@@ -169,14 +207,14 @@ fn find_entry_to_modify(
     }
 
     // Sort by timestamp (newest first)
-    all_entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    all_entries.sort_by_key(|e| std::cmp::Reverse(e.started_at));
 
     // Apply filters
     let mut filtered_entries = all_entries;
 
     // Apply search filter
     if let Some(search_query) = search {
-        filtered_entries = filter_by_search(filtered_entries, search_query, exact, case)?;
+        filtered_entries = filter_by_search(filtered_entries, search_query, *exact, case)?;
     }
 
     // Apply tag filter
@@ -184,8 +222,15 @@ fn find_entry_to_modify(
         filtered_entries = filter_by_tag(filtered_entries, tag_query)?;
     }
 
+    // Apply tag value queries
+    if !val.is_empty() {
+        let queries = parse_value_queries(val)?;
+        let bool_op = BoolOp::from(bool_op.as_str());
+        filtered_entries.retain(|entry| matches_value_queries(entry, &queries, &bool_op));
+    }
+
     // Apply NOT filter if specified
-    if not {
+    if *not {
         // Get all entries again
         let mut all_entries_again: Vec<Entry> = Vec::new();
         for section in &target_sections {
@@ -195,7 +240,7 @@ fn find_entry_to_modify(
                 }
             }
         }
-        all_entries_again.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        all_entries_again.sort_by_key(|e| std::cmp::Reverse(e.started_at));
 
         filtered_entries = all_entries_again
             .into_iter()
@@ -203,11 +248,20 @@ fn find_entry_to_modify(
             .collect();
     }
 
+    if filtered_entries.is_empty() {
+        return Err(color_eyre::eyre::eyre!("No matching entry found"));
+    }
+
+    if interactive {
+        let candidates: Vec<&Entry> = filtered_entries.iter().collect();
+        let chosen = picker::pick_entries(&candidates, "Select an entry to add a note to", false)?;
+        return Ok(filtered_entries
+            .into_iter()
+            .find(|entry| chosen.contains(&entry.uuid)));
+    }
+
     // Get the most recent entry after filtering
-    filtered_entries
-        .into_iter()
-        .next()
-        .ok_or_else(|| color_eyre::eyre::eyre!("No matching entry found"))
+    Ok(filtered_entries.into_iter().next())
 }
 
 fn filter_by_search(
@@ -1,5 +1,8 @@
+use crate::autotag::Autotagger;
+use crate::editor;
 use crate::models::Entry;
-use crate::storage::{Config, parse_taskpaper, save_taskpaper};
+use crate::services::EntryService;
+use crate::storage::Config;
 use chrono::{DateTime, Local};
 use chrono_english::{Dialect, parse_date_string};
 use regex::Regex;
@@ -15,55 +18,94 @@ pub struct NowOptions {
     pub from: Option<String>,
     pub editor: bool,
     pub ask: bool,
-    pub _noauto: bool,
+    pub noauto: bool,
 }
 
 pub fn handle_now(opts: NowOptions) -> color_eyre::Result<()> {
-    let config = Config::load();
-    let doing_file_path = config.doing_file_path();
-
-    let mut doing_file = parse_taskpaper(&doing_file_path)?;
-
-    // Handle finish_last option - mark last entry as done
-    if opts.finish_last {
-        let target_section = opts.section.as_deref().unwrap_or("Currently");
-
-        // Find the last undone entry in the section
-        let last_entry_info = doing_file
-            .get_all_entries()
-            .into_iter()
-            .filter(|e| e.section == target_section && !e.is_done())
-            .max_by_key(|e| e.timestamp)
-            .map(|e| (e.timestamp, e.description.clone()));
-
-        if let Some((timestamp, description)) = last_entry_info {
-            // Now update the actual entry in the sections
-            if let Some(entries) = doing_file.sections.get_mut(target_section) {
-                for entry in entries.iter_mut() {
-                    if entry.timestamp == timestamp && entry.description == description {
-                        entry.mark_done();
-                        break;
-                    }
-                }
+    let config = Config::try_load()?;
+
+    // Determine section
+    let target_section = opts
+        .section
+        .clone()
+        .unwrap_or_else(|| "Currently".to_string());
+
+    // The entry is built before the doing file is locked, so prompts and the
+    // editor don't keep other commands waiting
+    let mut new_entry = if opts.editor {
+        let mut draft = Entry::new(opts.entry.join(" "), target_section.clone());
+        draft.note = opts.note.clone();
+        match editor::edit_entry(&draft)? {
+            Some(entry) => entry,
+            None => {
+                println!("Empty entry, nothing added");
+                return Ok(());
             }
         }
+    } else {
+        entry_from_args(&opts, target_section.clone())?
+    };
+
+    if !opts.noauto {
+        Autotagger::new(&config)?.tag_new_entry(&mut new_entry);
     }
 
+    // Handle backdating
+    let entry_time = if let Some(back_str) = opts.back {
+        parse_date_string(&back_str, Local::now(), Dialect::Us)
+            .map_err(|_| color_eyre::eyre::eyre!("Invalid date string: {}", back_str))?
+    } else if let Some(from_str) = opts.from {
+        // Parse "from X to Y" format
+        parse_from_range(&from_str, &mut new_entry)?
+    } else {
+        Local::now()
+    };
+
+    new_entry = new_entry.with_started_at(entry_time);
+
+    EntryService::add_entry(new_entry.clone(), opts.finish_last)?;
+
+    println!(
+        "{}: {}",
+        new_entry.started_at.format("%Y-%m-%d %H:%M"),
+        new_entry.description
+    );
+
+    if !new_entry.tags.is_empty() {
+        let tags_str: Vec<String> = new_entry
+            .tags
+            .iter()
+            .map(|(k, v)| {
+                if let Some(val) = v {
+                    format!("@{k}({val})")
+                } else {
+                    format!("@{k}")
+                }
+            })
+            .collect();
+        println!("  {}", tags_str.join(" "));
+    }
+
+    if let Some(note) = &new_entry.note {
+        println!("  Note: {}", note.lines().next().unwrap_or(""));
+        for line in note.lines().skip(1) {
+            println!("        {line}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Build the entry from the command line, prompting for whatever is missing
+fn entry_from_args(opts: &NowOptions, target_section: String) -> color_eyre::Result<Entry> {
     // Get entry text
     let entry_text = if opts.entry.is_empty() {
-        if opts.editor {
-            // TODO: Implement editor support
-            return Err(color_eyre::eyre::eyre!(
-                "Editor support not yet implemented"
-            ));
-        } else {
-            // Interactive prompt
-            print!("What are you doing now? ");
-            io::stdout().flush()?;
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
-            input.trim().to_string()
-        }
+        // Interactive prompt
+        print!("What are you doing now? ");
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        input.trim().to_string()
     } else {
         opts.entry.join(" ")
     };
@@ -82,7 +124,7 @@ pub fn handle_now(opts: NowOptions) -> color_eyre::Result<()> {
     };
 
     // Get final note (command line flag takes precedence)
-    let final_note = if let Some(n) = opts.note {
+    let final_note = if let Some(n) = opts.note.clone() {
         Some(n)
     } else if let Some(n) = parsed_note {
         Some(n)
@@ -124,9 +166,6 @@ pub fn handle_now(opts: NowOptions) -> color_eyre::Result<()> {
         None
     };
 
-    // Determine section
-    let target_section = opts.section.unwrap_or_else(|| "Currently".to_string());
-
     // Extract tags from entry text first
     let tag_regex = Regex::new(r"@(\w+)(?:\(([^)]+)\))?")?;
     let mut tags = Vec::new();
@@ -150,56 +189,12 @@ pub fn handle_now(opts: NowOptions) -> color_eyre::Result<()> {
         new_entry = new_entry.with_tag(tag_name, tag_value);
     }
 
-    // Handle backdating
-    let entry_time = if let Some(back_str) = opts.back {
-        parse_date_string(&back_str, Local::now(), Dialect::Us)
-            .map_err(|_| color_eyre::eyre::eyre!("Invalid date string: {}", back_str))?
-    } else if let Some(from_str) = opts.from {
-        // Parse "from X to Y" format
-        parse_from_range(&from_str, &mut new_entry)?
-    } else {
-        Local::now()
-    };
-
-    new_entry = new_entry.with_timestamp(entry_time);
-
     // Add note if present
     if let Some(note_text) = final_note {
         new_entry = new_entry.with_note(note_text);
     }
 
-    doing_file.add_entry(new_entry.clone());
-    save_taskpaper(&doing_file)?;
-
-    println!(
-        "{}: {}",
-        new_entry.timestamp.format("%Y-%m-%d %H:%M"),
-        new_entry.description
-    );
-
-    if !new_entry.tags.is_empty() {
-        let tags_str: Vec<String> = new_entry
-            .tags
-            .iter()
-            .map(|(k, v)| {
-                if let Some(val) = v {
-                    format!("@{k}({val})")
-                } else {
-                    format!("@{k}")
-                }
-            })
-            .collect();
-        println!("  {}", tags_str.join(" "));
-    }
-
-    if let Some(note) = &new_entry.note {
-        println!("  Note: {}", note.lines().next().unwrap_or(""));
-        for line in note.lines().skip(1) {
-            println!("        {line}");
-        }
-    }
-
-    Ok(())
+    Ok(new_entry)
 }
 
 fn parse_from_range(
@@ -220,10 +215,7 @@ fn parse_from_range(
                 .map_err(|_| color_eyre::eyre::eyre!("Invalid end time: {}", end_str))?;
 
             // Add @done tag with end time
-            entry.tags.insert(
-                "done".to_string(),
-                Some(end_time.format("%Y-%m-%d %H:%M").to_string()),
-            );
+            entry.finish(end_time);
         }
 
         Ok(start_time)
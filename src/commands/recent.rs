@@ -1,8 +1,8 @@
 use crate::storage::{Config, parse_taskpaper};
-use chrono::{Duration, Local, TimeZone};
+use chrono::{Duration, Local};
 
 pub fn handle_recent(count: usize, section: Option<String>) -> color_eyre::Result<()> {
-    let config = Config::load();
+    let config = Config::try_load()?;
     let doing_file_path = config.doing_file_path();
 
     let doing_file = parse_taskpaper(&doing_file_path)?;
@@ -27,33 +27,18 @@ pub fn handle_recent(count: usize, section: Option<String>) -> color_eyre::Resul
 
     for entry in entries {
         // Format date/time
-        let date_str = format_date(&entry.timestamp, &now);
-        let time_str = entry.timestamp.format("%H:%M").to_string();
+        let date_str = format_date(&entry.started_at, &now);
+        let time_str = entry.started_at.format("%H:%M").to_string();
 
         // Format section
         let section_str = format!("[{:<width$}]", entry.section, width = section_width - 2);
 
         // Calculate duration if done with a timestamp
-        let duration_str = if let Some(Some(done_time_str)) = entry.tags.get("done") {
-            // Parse done timestamp
-            if let Ok(done_time) =
-                chrono::NaiveDateTime::parse_from_str(done_time_str, "%Y-%m-%d %H:%M")
-            {
-                let done_local = Local
-                    .from_local_datetime(&done_time)
-                    .single()
-                    .unwrap_or_else(Local::now);
-                let duration = done_local.timestamp() - entry.timestamp.timestamp();
-                if duration > 0 {
-                    format!(" {}", format_duration(Duration::seconds(duration)))
-                } else {
-                    String::new()
-                }
-            } else {
-                String::new()
+        let duration_str = match entry.duration() {
+            Some(duration) if duration.num_seconds() > 0 => {
+                format!(" {}", format_duration(duration))
             }
-        } else {
-            String::new()
+            _ => String::new(),
         };
 
         // Build description with tags
@@ -0,0 +1,74 @@
+use crate::display::{OutputFormat, display_report};
+use crate::filtering::{FilterOptions, filter_entries, parse_date_range};
+use crate::report::{Report, month_of, week_of, whole_days};
+use crate::storage::{Config, parse_taskpaper};
+use chrono::Local;
+
+#[derive(Debug)]
+pub struct ReportOptions {
+    pub week: bool,
+    pub month: bool,
+    pub from: Option<String>,
+    pub output: Option<String>,
+    pub sections: Vec<String>,
+    pub tag: Option<String>,
+    pub title: Option<String>,
+}
+
+pub fn handle_report(opts: ReportOptions) -> color_eyre::Result<()> {
+    let format = match opts.output.as_deref() {
+        None | Some("markdown") => OutputFormat::Markdown,
+        Some("html") => OutputFormat::Html,
+        Some("json") => OutputFormat::Json,
+        Some(other) => {
+            return Err(color_eyre::eyre::eyre!(
+                "unknown report format '{other}': use markdown, html or json"
+            ));
+        }
+    };
+
+    display_report(&report(opts)?, &format)
+}
+
+/// The report for the period and entries the options pick
+pub fn report(opts: ReportOptions) -> color_eyre::Result<Report> {
+    let config = Config::try_load()?;
+    let doing_file = parse_taskpaper(&config.doing_file_path())?;
+
+    let now = Local::now();
+    let (start, end, title) = match &opts.from {
+        Some(range) => {
+            let (start, end) = parse_date_range(range)?;
+            let (start, end) = whole_days(start, end, now);
+            let title = format!(
+                "Report for {} to {}",
+                start.format("%Y-%m-%d"),
+                end.format("%Y-%m-%d")
+            );
+            (start, end, title)
+        }
+        None if opts.month => month_of(now),
+        // A week is the default period
+        None => week_of(now),
+    };
+
+    let filter_options = FilterOptions {
+        tags: opts
+            .tag
+            .iter()
+            .flat_map(|tags| tags.split(','))
+            .map(|tag| tag.trim().to_string())
+            .collect(),
+        sections: opts.sections,
+        from: Some((start, Some(end))),
+        ..Default::default()
+    };
+    let entries = filter_entries(&doing_file, &filter_options)?;
+
+    Ok(Report::new(
+        opts.title.unwrap_or(title),
+        start,
+        end,
+        &entries,
+    ))
+}
@@ -1,5 +1,7 @@
+use crate::filtering::{BoolOp, matches_value_queries, parse_value_queries};
 use crate::models::Entry;
-use crate::storage::{Config, parse_taskpaper, save_taskpaper};
+use crate::picker;
+use crate::storage::{Config, FileLock, parse_taskpaper, save_taskpaper};
 use chrono::{DateTime, Local};
 use chrono_english::{Dialect, parse_date_string};
 use regex::Regex;
@@ -7,7 +9,7 @@ use regex::Regex;
 #[derive(Debug)]
 pub struct ResetOptions {
     pub date_string: Option<String>,
-    pub _bool_op: String,
+    pub bool_op: String,
     pub case: String,
     pub from: Option<String>,
     pub interactive: bool,
@@ -18,38 +20,43 @@ pub struct ResetOptions {
     pub search: Option<String>,
     pub took: Option<String>,
     pub tag: Option<String>,
-    pub _val: Vec<String>,
+    pub val: Vec<String>,
     pub exact: bool,
 }
 
 pub fn handle_reset(opts: ResetOptions) -> color_eyre::Result<()> {
-    if opts.interactive {
-        return Err(color_eyre::eyre::eyre!(
-            "Interactive mode not yet implemented"
-        ));
-    }
-
-    let config = Config::load();
+    let config = Config::try_load()?;
     let doing_file_path = config.doing_file_path();
 
+    let mut lock = FileLock::acquire(&doing_file_path)?;
+
     let mut doing_file = parse_taskpaper(&doing_file_path)?;
 
-    // Find the entry to modify
-    let entry_to_modify = find_entry_to_modify(
-        &doing_file,
-        &opts.sections,
-        opts.search.as_deref(),
-        opts.tag.as_deref(),
-        opts.exact,
-        opts.not,
-        &opts.case,
-    )?;
-
-    if entry_to_modify.is_none() {
+    // Find the entry to modify: the most recent match, or one the user picks
+    let matches = find_entries_to_modify(&doing_file, &opts)?;
+
+    if matches.is_empty() {
         return Err(color_eyre::eyre::eyre!("No matching entry found"));
     }
 
-    let (target_section, target_uuid) = entry_to_modify.unwrap();
+    let (target_section, target_uuid) = if opts.interactive {
+        let candidates: Vec<&Entry> = matches
+            .iter()
+            .filter_map(|(_, uuid)| doing_file.get_entry(*uuid))
+            .collect();
+        let chosen =
+            lock.suspend(|| picker::pick_entries(&candidates, "Select an entry to reset", false))?;
+        let Some(uuid) = chosen.first() else {
+            println!("Cancelled");
+            return Ok(());
+        };
+        matches
+            .into_iter()
+            .find(|(_, candidate)| candidate == uuid)
+            .ok_or_else(|| color_eyre::eyre::eyre!("No matching entry found"))?
+    } else {
+        matches[0].clone()
+    };
 
     // Parse the new start time
     let new_start_time = if let Some(from_range) = &opts.from {
@@ -67,23 +74,20 @@ pub fn handle_reset(opts: ResetOptions) -> color_eyre::Result<()> {
     if let Some(entries) = doing_file.sections.get_mut(&target_section) {
         for entry in entries.iter_mut() {
             if entry.uuid == target_uuid {
-                let old_timestamp = entry.timestamp;
-                entry.timestamp = new_start_time;
+                let old_timestamp = entry.started_at;
+                entry.started_at = new_start_time;
 
                 // Handle resume (remove @done) unless explicitly disabled
                 let should_resume = opts.resume && !opts.no_resume && opts.took.is_none();
                 if should_resume {
-                    entry.tags.remove("done");
+                    entry.unfinish();
                 }
 
                 // Handle --took option
                 if let Some(took_str) = &opts.took {
                     let duration = parse_duration(took_str)?;
                     let done_time = new_start_time + duration;
-                    entry.tags.insert(
-                        "done".to_string(),
-                        Some(done_time.format("%Y-%m-%d %H:%M").to_string()),
-                    );
+                    entry.finish(done_time);
                 }
 
                 // Print result
@@ -95,12 +99,12 @@ pub fn handle_reset(opts: ResetOptions) -> color_eyre::Result<()> {
                 );
                 println!(
                     "New: {}: {} {}",
-                    entry.timestamp.format("%Y-%m-%d %H:%M"),
+                    entry.started_at.format("%Y-%m-%d %H:%M"),
                     entry.description,
                     format_tags(&entry.tags)
                 );
 
-                if should_resume && old_timestamp != entry.timestamp {
+                if should_resume && old_timestamp != entry.started_at {
                     println!("Entry resumed.");
                 }
 
@@ -109,7 +113,7 @@ pub fn handle_reset(opts: ResetOptions) -> color_eyre::Result<()> {
         }
     }
 
-    save_taskpaper(&doing_file)?;
+    save_taskpaper(&doing_file, &config)?;
 
     Ok(())
 }
@@ -158,8 +162,8 @@ fn parse_duration(duration_str: &str) -> Result<chrono::Duration, color_eyre::ey
     Err(color_eyre::eyre::eyre!("Invalid duration format"))
 }
 
-fn format_tags(tags: &std::collections::HashMap<String, Option<String>>) -> String {
-    let mut tag_strs: Vec<String> = tags
+fn format_tags(tags: &indexmap::IndexMap<String, Option<String>>) -> String {
+    let tag_strs: Vec<String> = tags
         .iter()
         .map(|(tag, value)| {
             if let Some(val) = value {
@@ -169,19 +173,25 @@ fn format_tags(tags: &std::collections::HashMap<String, Option<String>>) -> Stri
             }
         })
         .collect();
-    tag_strs.sort();
     tag_strs.join(" ")
 }
 
-fn find_entry_to_modify(
+fn find_entries_to_modify(
     doing_file: &crate::models::DoingFile,
-    sections: &[String],
-    search: Option<&str>,
-    tag: Option<&str>,
-    exact: bool,
-    not: bool,
-    case: &str,
-) -> Result<Option<(String, uuid::Uuid)>, color_eyre::eyre::Error> {
+    opts: &ResetOptions,
+) -> Result<Vec<(String, uuid::Uuid)>, color_eyre::eyre::Error> {
+    let ResetOptions {
+        sections,
+        search,
+        tag,
+        val,
+        bool_op,
+        exact,
+        not,
+        case,
+        ..
+    } = opts;
+
     // Determine which sections to search
     let target_sections: Vec<String> = if sections.is_empty() {
         doing_file.sections.keys().cloned().collect()
@@ -200,14 +210,14 @@ fn find_entry_to_modify(
     }
 
     // Sort by timestamp (newest first)
-    all_entries.sort_by(|a, b| b.1.timestamp.cmp(&a.1.timestamp));
+    all_entries.sort_by_key(|e| std::cmp::Reverse(e.1.started_at));
 
     // Apply filters
     let mut filtered_entries = all_entries;
 
     // Apply search filter
     if let Some(search_query) = search {
-        filtered_entries = filter_by_search(filtered_entries, search_query, exact, case)?;
+        filtered_entries = filter_by_search(filtered_entries, search_query, *exact, case)?;
     }
 
     // Apply tag filter
@@ -215,8 +225,15 @@ fn find_entry_to_modify(
         filtered_entries = filter_by_tag(filtered_entries, tag_query)?;
     }
 
+    // Apply tag value queries
+    if !val.is_empty() {
+        let queries = parse_value_queries(val)?;
+        let bool_op = BoolOp::from(bool_op.as_str());
+        filtered_entries.retain(|(_, entry)| matches_value_queries(entry, &queries, &bool_op));
+    }
+
     // Apply NOT filter if specified
-    if not {
+    if *not {
         // Get all entries again
         let mut all_entries_again: Vec<(String, Entry)> = Vec::new();
         for section in &target_sections {
@@ -226,7 +243,7 @@ fn find_entry_to_modify(
                 }
             }
         }
-        all_entries_again.sort_by(|a, b| b.1.timestamp.cmp(&a.1.timestamp));
+        all_entries_again.sort_by_key(|e| std::cmp::Reverse(e.1.started_at));
 
         let filtered_uuids: std::collections::HashSet<_> =
             filtered_entries.iter().map(|(_, e)| e.uuid).collect();
@@ -237,12 +254,10 @@ fn find_entry_to_modify(
             .collect();
     }
 
-    // Take the most recent entry
-    if let Some((section, entry)) = filtered_entries.into_iter().next() {
-        Ok(Some((section, entry.uuid)))
-    } else {
-        Ok(None)
-    }
+    Ok(filtered_entries
+        .into_iter()
+        .map(|(section, entry)| (section, entry.uuid))
+        .collect())
 }
 
 fn filter_by_search(
@@ -1,15 +1,14 @@
-use crate::storage::{Config, DoingFile, parse_taskpaper, save_taskpaper};
+use crate::filtering::{BoolOp, matches_value_queries, parse_value_queries};
+use crate::storage::{Config, FileLock, parse_taskpaper, save_taskpaper};
 use chrono::Local;
 use color_eyre::Result;
 use regex::Regex;
-use std::collections::HashMap;
-use std::fs;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug)]
 pub struct RotateOptions {
     pub before: Option<String>,
-    pub _bool_op: String,
+    pub bool_op: String,
     pub case: String,
     pub keep: Option<usize>,
     pub not: bool,
@@ -21,19 +20,20 @@ pub struct RotateOptions {
 }
 
 pub fn handle_rotate(opts: RotateOptions) -> Result<()> {
-    let config = Config::load();
+    let config = Config::try_load()?;
     let doing_file_path = config.doing_file_path();
+    let _lock = FileLock::acquire(&doing_file_path)?;
     let mut doing_file = parse_taskpaper(&doing_file_path)?;
 
     // Calculate archive file path
     let archive_path = get_archive_path(doing_file_path.as_path());
 
+    // Lock the archive too, always after the doing file, so that rotations
+    // running at once wait for each other instead of deadlocking
+    let _archive_lock = FileLock::acquire(&archive_path)?;
+
     // Load or create archive file
-    let mut archive_file = if archive_path.exists() {
-        parse_taskpaper(&archive_path)?
-    } else {
-        DoingFile::new(archive_path.clone())
-    };
+    let mut archive_file = parse_taskpaper(&archive_path)?;
 
     // Ensure Archive section exists in archive file
     if !archive_file.sections.contains_key("Archive") {
@@ -62,7 +62,8 @@ pub fn handle_rotate(opts: RotateOptions) -> Result<()> {
         None
     };
 
-    let tag_value_queries = compile_tag_value_queries(&opts.val)?;
+    let value_queries = parse_value_queries(&opts.val)?;
+    let bool_op = BoolOp::from(opts.bool_op.as_str());
 
     // Determine which sections to process
     let sections_to_process: Vec<String> = if let Some(ref section_name) = opts.section {
@@ -86,13 +87,13 @@ pub fn handle_rotate(opts: RotateOptions) -> Result<()> {
                 let mut matches = true;
 
                 // Only rotate entries marked as @done by default
-                if !entry.tags.contains_key("done") {
+                if !entry.is_done() {
                     matches = false;
                 }
 
                 // Apply before date filter
                 if let Some(before) = &before_date
-                    && entry.timestamp >= *before
+                    && entry.started_at >= *before
                 {
                     matches = false;
                 }
@@ -114,15 +115,10 @@ pub fn handle_rotate(opts: RotateOptions) -> Result<()> {
                 }
 
                 // Apply tag value queries
-                for (tag_name, value_pattern) in &tag_value_queries {
-                    match entry.tags.get(tag_name) {
-                        Some(Some(value)) => {
-                            if !value_pattern.is_match(value) {
-                                matches = false;
-                            }
-                        }
-                        _ => matches = false,
-                    }
+                if !value_queries.is_empty()
+                    && !matches_value_queries(entry, &value_queries, &bool_op)
+                {
+                    matches = false;
                 }
 
                 // Apply not filter
@@ -182,9 +178,10 @@ pub fn handle_rotate(opts: RotateOptions) -> Result<()> {
     }
 
     if rotated_count > 0 {
-        // Save both files
-        save_taskpaper(&doing_file)?;
-        save_taskpaper_to_path(&archive_file, &archive_path)?;
+        // The archive first: if saving it fails, the entries are still in
+        // the doing file
+        save_taskpaper(&archive_file, &config)?;
+        save_taskpaper(&doing_file, &config)?;
 
         println!(
             "Rotated {} {} to {}",
@@ -218,12 +215,6 @@ fn get_archive_path(doing_file_path: &Path) -> PathBuf {
     }
 }
 
-fn save_taskpaper_to_path(doing_file: &DoingFile, path: &PathBuf) -> Result<()> {
-    let content = crate::storage::format_taskpaper(doing_file);
-    fs::write(path, content)?;
-    Ok(())
-}
-
 fn compile_search_regex(pattern: &str, case: &str, exact: bool) -> Result<Regex> {
     let pattern = if exact {
         regex::escape(pattern)
@@ -261,19 +252,3 @@ fn parse_smart_case(case: &str, pattern: &str) -> bool {
         }
     }
 }
-
-fn compile_tag_value_queries(val: &[String]) -> Result<HashMap<String, Regex>> {
-    let mut queries = HashMap::new();
-
-    for query in val {
-        // Parse tag value queries in format "tag_name=pattern"
-        if let Some(eq_pos) = query.find('=') {
-            let tag_name = query[..eq_pos].trim().to_string();
-            let pattern = query[eq_pos + 1..].trim();
-            let regex = Regex::new(pattern)?;
-            queries.insert(tag_name, regex);
-        }
-    }
-
-    Ok(queries)
-}
@@ -1,11 +1,12 @@
 use crate::cli::SectionsAction;
-use crate::storage::{Config, DoingFile, parse_taskpaper, save_taskpaper};
+use crate::storage::{Config, DoingFile, FileLock, parse_taskpaper, save_taskpaper};
 use color_eyre::Result;
-use std::collections::BTreeMap;
+use indexmap::IndexMap;
 
 pub fn handle_sections(action: Option<SectionsAction>) -> Result<()> {
-    let config = Config::load();
+    let config = Config::try_load()?;
     let doing_file_path = config.doing_file_path();
+    let _lock = FileLock::acquire(&doing_file_path)?;
     let mut doing_file = parse_taskpaper(&doing_file_path)?;
 
     match action {
@@ -19,7 +20,7 @@ pub fn handle_sections(action: Option<SectionsAction>) -> Result<()> {
         }
         Some(SectionsAction::Add { section_name }) => {
             add_section(&mut doing_file, &section_name)?;
-            save_taskpaper(&doing_file)?;
+            save_taskpaper(&doing_file, &config)?;
             println!("Added section: {section_name}");
         }
         Some(SectionsAction::Remove {
@@ -27,7 +28,7 @@ pub fn handle_sections(action: Option<SectionsAction>) -> Result<()> {
             archive,
         }) => {
             remove_section(&mut doing_file, &section_name, archive)?;
-            save_taskpaper(&doing_file)?;
+            save_taskpaper(&doing_file, &config)?;
             println!("Removed section: {section_name}");
         }
     }
@@ -36,8 +37,8 @@ pub fn handle_sections(action: Option<SectionsAction>) -> Result<()> {
 }
 
 fn list_sections(doing_file: &DoingFile, column: bool) {
-    // Use BTreeMap to maintain alphabetical order
-    let sections: BTreeMap<&String, usize> = doing_file
+    // Sections are listed in file order
+    let sections: IndexMap<&String, usize> = doing_file
         .sections
         .iter()
         .map(|(name, entries)| (name, entries.len()))
@@ -95,7 +96,7 @@ fn remove_section(doing_file: &mut DoingFile, section_name: &str, archive: bool)
 
     if archive {
         // Move entries to Archive section if requested
-        if let Some(entries) = doing_file.sections.remove(section_name)
+        if let Some(entries) = doing_file.sections.shift_remove(section_name)
             && !entries.is_empty()
         {
             let archive_entries = doing_file
@@ -110,7 +111,7 @@ fn remove_section(doing_file: &mut DoingFile, section_name: &str, archive: bool)
         }
     } else {
         // Just remove the section
-        doing_file.sections.remove(section_name);
+        doing_file.sections.shift_remove(section_name);
     }
 
     Ok(())
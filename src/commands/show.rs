@@ -1,8 +1,15 @@
+use crate::commands::view;
 use crate::display::{DisplayOptions, OutputFormat, SortOrder, TagSort, display_entries};
+use crate::editor;
 use crate::filtering::{
     BoolOp, CaseSensitivity, FilterOptions, filter_entries, parse_date_filter, parse_date_range,
 };
+use crate::models::{DoingFile, Entry};
+use crate::picker;
+use crate::storage::config::{View, ViewCommand};
 use crate::storage::{Config, parse_taskpaper};
+use crate::template;
+use indexmap::IndexSet;
 
 #[derive(Debug)]
 pub struct ShowFilterOptions {
@@ -30,8 +37,8 @@ pub struct ShowDisplayOptions {
     pub output: Option<String>,
     pub sort: String,
     pub times: bool,
-    pub tag_order: String,
-    pub tag_sort: String,
+    pub tag_order: Option<String>,
+    pub tag_sort: Option<String>,
     pub totals: bool,
 }
 
@@ -39,34 +46,29 @@ pub struct ShowDisplayOptions {
 pub struct ShowUIOptions {
     pub interactive: bool,
     pub menu: bool,
-    pub _editor: bool,
+    pub editor: bool,
 }
 
 #[derive(Debug)]
 pub struct ShowConfigOptions {
-    pub _config_template: Option<String>,
-    pub _save: Option<String>,
-    pub _template: Option<String>,
-    pub _title: Option<String>,
+    pub config_template: Option<String>,
+    pub save: Option<String>,
+    pub template: Option<String>,
+    pub title: Option<String>,
 }
 
 pub fn handle_show(
     filter_opts: ShowFilterOptions,
     display_opts: ShowDisplayOptions,
     ui_opts: ShowUIOptions,
-    _config_opts: ShowConfigOptions,
+    config_opts: ShowConfigOptions,
 ) -> color_eyre::Result<()> {
-    if ui_opts.interactive {
-        return Err(color_eyre::eyre::eyre!(
-            "Interactive mode not yet implemented"
-        ));
-    }
-
-    if ui_opts.menu {
-        return Err(color_eyre::eyre::eyre!("Menu mode not yet implemented"));
-    }
-
-    let config = Config::load();
+    let config = Config::try_load()?;
+    let template = template::resolve(
+        config_opts.template.as_deref(),
+        config_opts.config_template.as_deref(),
+        &config,
+    )?;
     let doing_file_path = config.doing_file_path();
     let doing_file = parse_taskpaper(&doing_file_path)?;
 
@@ -78,7 +80,12 @@ pub fn handle_show(
         if arg.starts_with('@') {
             filter_tags.push(arg.clone());
         } else if arg == "pick" || arg == "choose" {
-            return Err(color_eyre::eyre::eyre!("Section menu not yet implemented"));
+            let sections: Vec<String> = doing_file.sections.keys().cloned().collect();
+            let Some(section) = picker::pick_one(&sections, "Select a section")? else {
+                println!("Cancelled");
+                return Ok(());
+            };
+            filter_sections.push(section);
         } else {
             // It's a section name
             filter_sections.push(arg.clone());
@@ -92,6 +99,51 @@ pub fn handle_show(
         }
     }
 
+    if ui_opts.menu {
+        let Some(choice) = picker::pick_one(&section_and_tag_choices(&doing_file), "Show")? else {
+            println!("Cancelled");
+            return Ok(());
+        };
+        if choice.starts_with('@') {
+            filter_tags.push(choice);
+        } else {
+            filter_sections.push(choice);
+        }
+    }
+
+    if let Some(name) = &config_opts.save {
+        let saved = View {
+            command: ViewCommand::Show,
+            sections: filter_sections.clone(),
+            tags: filter_tags.clone(),
+            search: filter_opts.search.clone(),
+            val: filter_opts.val.clone(),
+            bool_op: Some(filter_opts.bool_op.clone()),
+            case: Some(filter_opts.case.clone()),
+            exact: filter_opts.exact,
+            not: filter_opts.not,
+            only_timed: filter_opts.only_timed,
+            after: filter_opts.after.clone(),
+            before: filter_opts.before.clone(),
+            from: filter_opts.from.clone(),
+            age: Some(filter_opts.age.clone()),
+            count: display_opts.count,
+            sort: Some(display_opts.sort.clone()),
+            output: display_opts.output.clone(),
+            tag_order: display_opts.tag_order.clone(),
+            tag_sort: display_opts.tag_sort.clone(),
+            duration: display_opts.duration,
+            hilite: display_opts.hilite,
+            times: display_opts.times,
+            totals: display_opts.totals,
+            template: config_opts.template.clone(),
+            config_template: config_opts.config_template.clone(),
+            title: config_opts.title.clone(),
+            date: None,
+        };
+        view::save_view(name, &saved)?;
+    }
+
     // Build filter options
     let mut filter_options = FilterOptions {
         search: filter_opts.search,
@@ -104,12 +156,7 @@ pub fn handle_show(
         },
         exact: filter_opts.exact,
         not: filter_opts.not,
-        bool_op: match filter_opts.bool_op.as_str() {
-            "and" | "AND" => BoolOp::And,
-            "or" | "OR" => BoolOp::Or,
-            "not" | "NOT" => BoolOp::Not,
-            _ => BoolOp::Pattern,
-        },
+        bool_op: BoolOp::from(filter_opts.bool_op.as_str()),
         only_timed: filter_opts.only_timed,
         val: filter_opts.val,
         ..Default::default()
@@ -134,9 +181,9 @@ pub fn handle_show(
 
     // Sort entries
     match display_opts.sort.as_str() {
-        "asc" => entries.sort_by(|a, b| a.1.timestamp.cmp(&b.1.timestamp)),
-        "desc" => entries.sort_by(|a, b| b.1.timestamp.cmp(&a.1.timestamp)),
-        _ => entries.sort_by(|a, b| b.1.timestamp.cmp(&a.1.timestamp)),
+        "asc" => entries.sort_by_key(|a| a.1.started_at),
+        "desc" => entries.sort_by_key(|e| std::cmp::Reverse(e.1.started_at)),
+        _ => entries.sort_by_key(|e| std::cmp::Reverse(e.1.started_at)),
     }
 
     // Apply age filter (newest/oldest)
@@ -149,6 +196,22 @@ pub fn handle_show(
         entries.truncate(display_opts.count);
     }
 
+    // Narrow the results down to the entries picked from a menu
+    if ui_opts.interactive && !entries.is_empty() {
+        let candidates: Vec<&Entry> = entries.iter().map(|(_, entry)| entry).collect();
+        let chosen = picker::pick_entries(&candidates, "Select entries", true)?;
+        if chosen.is_empty() {
+            println!("Cancelled");
+            return Ok(());
+        }
+        entries.retain(|(_, entry)| chosen.contains(&entry.uuid));
+    }
+
+    if ui_opts.editor {
+        let entries: Vec<_> = entries.into_iter().map(|(_, entry)| entry).collect();
+        return report_bulk_edit(editor::edit_entries(&config, &entries)?);
+    }
+
     // Build display options
     let display_options = DisplayOptions {
         times: display_opts.times,
@@ -163,17 +226,20 @@ pub fn handle_show(
             Some("html") => OutputFormat::Html,
             Some("taskpaper") => OutputFormat::TaskPaper,
             Some("timeline") => OutputFormat::Timeline,
+            Some("ics") => OutputFormat::Ics,
             _ => OutputFormat::Default,
         },
-        tag_sort: match display_opts.tag_sort.as_str() {
+        tag_sort: display_opts.tag_sort.as_deref().map(|sort| match sort {
             "time" => TagSort::Time,
             _ => TagSort::Name,
-        },
-        tag_order: match display_opts.tag_order.as_str() {
+        }),
+        tag_order: display_opts.tag_order.as_deref().map(|order| match order {
             "desc" => SortOrder::Desc,
             _ => SortOrder::Asc,
-        },
+        }),
         section_filter: filter_options.sections.clone(),
+        template,
+        title: config_opts.title,
     };
 
     // Display entries
@@ -181,3 +247,24 @@ pub fn handle_show(
 
     Ok(())
 }
+
+/// Every section, then every tag in use as `@tag`, for the `--menu` picker
+fn section_and_tag_choices(doing_file: &DoingFile) -> Vec<String> {
+    let mut choices: Vec<String> = doing_file.sections.keys().cloned().collect();
+    let mut tags: IndexSet<String> = IndexSet::new();
+    for entry in doing_file.get_all_entries() {
+        tags.extend(entry.tags.keys().map(|tag| format!("@{tag}")));
+    }
+    choices.extend(tags);
+    choices
+}
+
+/// Summarize the result of `editor::edit_entries`
+pub(crate) fn report_bulk_edit(changed: Option<usize>) -> color_eyre::Result<()> {
+    match changed {
+        None => println!("Empty buffer, nothing changed"),
+        Some(1) => println!("Updated 1 entry"),
+        Some(count) => println!("Updated {count} entries"),
+    }
+    Ok(())
+}
@@ -1,6 +1,9 @@
+use crate::commands::view;
 use crate::display::{DisplayOptions, OutputFormat, SortOrder, TagSort, display_entries};
 use crate::filtering::{BoolOp, CaseSensitivity, FilterOptions, filter_entries, parse_date_filter};
+use crate::storage::config::{View, ViewCommand};
 use crate::storage::{Config, parse_taskpaper};
+use crate::template;
 
 #[derive(Debug)]
 pub struct SinceFilterOptions {
@@ -21,25 +24,34 @@ pub struct SinceDisplayOptions {
     pub duration: bool,
     pub output: Option<String>,
     pub times: bool,
-    pub tag_order: String,
-    pub tag_sort: String,
+    pub tag_order: Option<String>,
+    pub tag_sort: Option<String>,
     pub totals: bool,
 }
 
 #[derive(Debug)]
 pub struct SinceConfigOptions {
-    pub _config_template: Option<String>,
-    pub _save: Option<String>,
-    pub _template: Option<String>,
-    pub _title: Option<String>,
+    pub config_template: Option<String>,
+    pub save: Option<String>,
+    pub template: Option<String>,
+    pub title: Option<String>,
 }
 
 pub fn handle_since(
     filter_opts: SinceFilterOptions,
     display_opts: SinceDisplayOptions,
-    _config_opts: SinceConfigOptions,
+    config_opts: SinceConfigOptions,
 ) -> color_eyre::Result<()> {
-    let config = Config::load();
+    if let Some(name) = &config_opts.save {
+        view::save_view(name, &saved_view(&filter_opts, &display_opts, &config_opts))?;
+    }
+
+    let config = Config::try_load()?;
+    let template = template::resolve(
+        config_opts.template.as_deref(),
+        config_opts.config_template.as_deref(),
+        &config,
+    )?;
     let doing_file_path = config.doing_file_path();
     let doing_file = parse_taskpaper(&doing_file_path)?;
 
@@ -66,12 +78,7 @@ pub fn handle_since(
         },
         exact: filter_opts.exact,
         not: filter_opts.not,
-        bool_op: match filter_opts.bool_op.as_str() {
-            "and" | "AND" => BoolOp::And,
-            "or" | "OR" => BoolOp::Or,
-            "not" | "NOT" => BoolOp::Not,
-            _ => BoolOp::Pattern,
-        },
+        bool_op: BoolOp::from(filter_opts.bool_op.as_str()),
         only_timed: filter_opts.only_timed,
         val: filter_opts.val,
         ..Default::default()
@@ -99,17 +106,20 @@ pub fn handle_since(
             Some("html") => OutputFormat::Html,
             Some("taskpaper") => OutputFormat::TaskPaper,
             Some("timeline") => OutputFormat::Timeline,
+            Some("ics") => OutputFormat::Ics,
             _ => OutputFormat::Default,
         },
-        tag_sort: match display_opts.tag_sort.as_str() {
+        tag_sort: display_opts.tag_sort.as_deref().map(|sort| match sort {
             "time" => TagSort::Time,
             _ => TagSort::Name,
-        },
-        tag_order: match display_opts.tag_order.as_str() {
+        }),
+        tag_order: display_opts.tag_order.as_deref().map(|order| match order {
             "desc" => SortOrder::Desc,
             _ => SortOrder::Asc,
-        },
+        }),
         section_filter: filter_options.sections.clone(),
+        template,
+        title: config_opts.title,
     };
 
     // Display entries
@@ -117,3 +127,39 @@ pub fn handle_since(
 
     Ok(())
 }
+
+/// The options of this listing as a view for `--save`
+fn saved_view(
+    filter_opts: &SinceFilterOptions,
+    display_opts: &SinceDisplayOptions,
+    config_opts: &SinceConfigOptions,
+) -> View {
+    View {
+        command: ViewCommand::Since,
+        date: Some(filter_opts.date_string.clone()),
+        sections: filter_opts.sections.clone(),
+        tags: filter_opts
+            .tag
+            .iter()
+            .flat_map(|tags| tags.split(','))
+            .map(|tag| tag.trim().to_string())
+            .collect(),
+        search: filter_opts.search.clone(),
+        val: filter_opts.val.clone(),
+        bool_op: Some(filter_opts.bool_op.clone()),
+        case: Some(filter_opts.case.clone()),
+        exact: filter_opts.exact,
+        not: filter_opts.not,
+        only_timed: filter_opts.only_timed,
+        output: display_opts.output.clone(),
+        tag_order: display_opts.tag_order.clone(),
+        tag_sort: display_opts.tag_sort.clone(),
+        duration: display_opts.duration,
+        times: display_opts.times,
+        totals: display_opts.totals,
+        template: config_opts.template.clone(),
+        config_template: config_opts.config_template.clone(),
+        title: config_opts.title.clone(),
+        ..View::default()
+    }
+}
@@ -1,5 +1,8 @@
+use crate::autotag::Autotagger;
+use crate::filtering::{BoolOp, matches_value_queries, parse_value_queries};
 use crate::models::Entry;
-use crate::storage::{Config, parse_taskpaper, save_taskpaper};
+use crate::picker;
+use crate::storage::{Config, FileLock, parse_taskpaper, save_taskpaper};
 use chrono::Local;
 use regex::Regex;
 use std::io::{self, Write};
@@ -7,8 +10,8 @@ use std::io::{self, Write};
 #[derive(Debug)]
 pub struct TagOptions {
     pub tags: Vec<String>,
-    pub _autotag: bool,
-    pub _bool_op: String,
+    pub autotag: bool,
+    pub bool_op: String,
     pub count: usize,
     pub case: String,
     pub date: bool,
@@ -23,7 +26,7 @@ pub struct TagOptions {
     pub tag: Option<String>,
     pub unfinished: bool,
     pub value: Option<String>,
-    pub _val: Vec<String>,
+    pub val: Vec<String>,
     pub exact: bool,
 }
 
@@ -32,6 +35,8 @@ struct EntryFilter {
     sections: Vec<String>,
     search: Option<String>,
     tag: Option<String>,
+    val: Vec<String>,
+    bool_op: String,
     count: usize,
     unfinished: bool,
     exact: bool,
@@ -40,14 +45,8 @@ struct EntryFilter {
 }
 
 pub fn handle_tag(opts: TagOptions) -> color_eyre::Result<()> {
-    if opts.interactive {
-        return Err(color_eyre::eyre::eyre!(
-            "Interactive mode not yet implemented"
-        ));
-    }
-
     // Validate count and force
-    if opts.count == 0 && !opts.force {
+    if opts.count == 0 && !opts.force && !opts.interactive {
         print!("Are you sure you want to tag all entries? [y/N] ");
         io::stdout().flush()?;
 
@@ -60,8 +59,19 @@ pub fn handle_tag(opts: TagOptions) -> color_eyre::Result<()> {
         }
     }
 
-    let config = Config::load();
+    if opts.rename.is_some() && opts.tags.is_empty() {
+        return Err(color_eyre::eyre::eyre!("--rename needs the new tag name"));
+    }
+
+    let config = Config::try_load()?;
     let doing_file_path = config.doing_file_path();
+    let autotagger = if opts.autotag {
+        Some(Autotagger::new(&config)?)
+    } else {
+        None
+    };
+
+    let mut lock = FileLock::acquire(&doing_file_path)?;
 
     let mut doing_file = parse_taskpaper(&doing_file_path)?;
 
@@ -70,18 +80,35 @@ pub fn handle_tag(opts: TagOptions) -> color_eyre::Result<()> {
         sections: opts.sections.clone(),
         search: opts.search.clone(),
         tag: opts.tag.clone(),
-        count: opts.count,
+        val: opts.val.clone(),
+        bool_op: opts.bool_op.clone(),
+        // Offer every match in the menu
+        count: if opts.interactive { 0 } else { opts.count },
         unfinished: opts.unfinished,
         exact: opts.exact,
         not: opts.not,
         case: opts.case.clone(),
     };
-    let entries_to_modify = find_entries_to_modify(&doing_file, filter)?;
+    let mut entries_to_modify = find_entries_to_modify(&doing_file, filter)?;
 
     if entries_to_modify.is_empty() {
         return Err(color_eyre::eyre::eyre!("No matching entries found"));
     }
 
+    if opts.interactive {
+        let candidates: Vec<&Entry> = entries_to_modify
+            .iter()
+            .filter_map(|(_, uuid)| doing_file.get_entry(*uuid))
+            .collect();
+        let chosen =
+            lock.suspend(|| picker::pick_entries(&candidates, "Select entries to tag", true))?;
+        if chosen.is_empty() {
+            println!("Cancelled");
+            return Ok(());
+        }
+        entries_to_modify.retain(|(_, uuid)| chosen.contains(uuid));
+    }
+
     // Process tags
     let mut tags_to_process: Vec<(String, Option<String>)> = Vec::new();
     for tag_str in &opts.tags {
@@ -128,10 +155,16 @@ pub fn handle_tag(opts: TagOptions) -> color_eyre::Result<()> {
                         }
                     }
 
+                    if let Some(autotagger) = &autotagger {
+                        autotagger.tag_entry(entry);
+                    }
+                    // `tag done(...)` and `tag --remove done` finish and resume entries
+                    entry.sync_finished_at();
+
                     // Print updated entry
                     println!(
                         "{}: {} {}",
-                        entry.timestamp.format("%Y-%m-%d %H:%M"),
+                        entry.started_at.format("%Y-%m-%d %H:%M"),
                         entry.description,
                         format_tags(&entry.tags)
                     );
@@ -143,7 +176,7 @@ pub fn handle_tag(opts: TagOptions) -> color_eyre::Result<()> {
         }
     }
 
-    save_taskpaper(&doing_file)?;
+    save_taskpaper(&doing_file, &config)?;
 
     println!(
         "\nTagged {} {}.",
@@ -158,8 +191,8 @@ pub fn handle_tag(opts: TagOptions) -> color_eyre::Result<()> {
     Ok(())
 }
 
-fn format_tags(tags: &std::collections::HashMap<String, Option<String>>) -> String {
-    let mut tag_strs: Vec<String> = tags
+fn format_tags(tags: &indexmap::IndexMap<String, Option<String>>) -> String {
+    let tag_strs: Vec<String> = tags
         .iter()
         .map(|(tag, value)| {
             if let Some(val) = value {
@@ -169,7 +202,6 @@ fn format_tags(tags: &std::collections::HashMap<String, Option<String>>) -> Stri
             }
         })
         .collect();
-    tag_strs.sort();
     tag_strs.join(" ")
 }
 
@@ -228,7 +260,7 @@ fn rename_tags(
     }
 
     for (old_tag, value) in tags_to_rename {
-        entry.tags.remove(&old_tag);
+        entry.tags.shift_remove(&old_tag);
         entry.tags.insert(to_tag.to_string(), value);
     }
 
@@ -277,7 +309,7 @@ fn find_entries_to_modify(
     }
 
     // Sort by timestamp (newest first)
-    all_entries.sort_by(|a, b| b.1.timestamp.cmp(&a.1.timestamp));
+    all_entries.sort_by_key(|e| std::cmp::Reverse(e.1.started_at));
 
     // Apply filters
     let mut filtered_entries = all_entries;
@@ -293,6 +325,13 @@ fn find_entries_to_modify(
         filtered_entries = filter_by_tag(filtered_entries, tag_query)?;
     }
 
+    // Apply tag value queries
+    if !filter.val.is_empty() {
+        let queries = parse_value_queries(&filter.val)?;
+        let bool_op = BoolOp::from(filter.bool_op.as_str());
+        filtered_entries.retain(|(_, entry)| matches_value_queries(entry, &queries, &bool_op));
+    }
+
     // Apply unfinished filter
     if filter.unfinished {
         filtered_entries.retain(|(_, entry)| !entry.is_done());
@@ -309,7 +348,7 @@ fn find_entries_to_modify(
                 }
             }
         }
-        all_entries_again.sort_by(|a, b| b.1.timestamp.cmp(&a.1.timestamp));
+        all_entries_again.sort_by_key(|e| std::cmp::Reverse(e.1.started_at));
 
         let filtered_uuids: std::collections::HashSet<_> =
             filtered_entries.iter().map(|(_, e)| e.uuid).collect();
@@ -1,3 +1,4 @@
+use crate::filtering::{BoolOp, matches_value_queries, parse_value_queries};
 use crate::storage::{Config, parse_taskpaper};
 use color_eyre::Result;
 use regex::Regex;
@@ -9,6 +10,7 @@ pub struct TagsFilterOptions {
     pub search: Option<String>,
     pub tag: Option<String>,
     pub val: Vec<String>,
+    pub bool_op: String,
     pub case: String,
     pub exact: bool,
     pub not: bool,
@@ -28,7 +30,7 @@ pub fn handle_tags(
     display_opts: TagsDisplayOptions,
     interactive: bool,
 ) -> Result<()> {
-    let config = Config::load();
+    let config = Config::try_load()?;
     let doing_file_path = config.doing_file_path();
     let doing_file = parse_taskpaper(&doing_file_path)?;
 
@@ -49,7 +51,8 @@ pub fn handle_tags(
         None
     };
 
-    let tag_value_queries = compile_tag_value_queries(&filter_opts.val)?;
+    let value_queries = parse_value_queries(&filter_opts.val)?;
+    let bool_op = BoolOp::from(filter_opts.bool_op.as_str());
 
     // Determine which sections to process
     let sections_to_process: Vec<&String> = if filter_opts.section.is_empty() {
@@ -87,15 +90,10 @@ pub fn handle_tags(
                 }
 
                 // Apply tag value queries
-                for (tag_name, value_pattern) in &tag_value_queries {
-                    match entry.tags.get(tag_name) {
-                        Some(Some(value)) => {
-                            if !value_pattern.is_match(value) {
-                                matches = false;
-                            }
-                        }
-                        _ => matches = false,
-                    }
+                if !value_queries.is_empty()
+                    && !matches_value_queries(entry, &value_queries, &bool_op)
+                {
+                    matches = false;
                 }
 
                 // Apply not filter
@@ -126,7 +124,7 @@ pub fn handle_tags(
             sorted_tags.sort_by(|a, b| a.0.cmp(&b.0));
         }
         "count" | "time" => {
-            sorted_tags.sort_by(|a, b| b.1.cmp(&a.1));
+            sorted_tags.sort_by_key(|e| std::cmp::Reverse(e.1));
         }
         _ => {
             sorted_tags.sort_by(|a, b| a.0.cmp(&b.0));
@@ -221,19 +219,3 @@ fn parse_smart_case(case: &str, pattern: &str) -> bool {
         }
     }
 }
-
-fn compile_tag_value_queries(val: &[String]) -> Result<HashMap<String, Regex>> {
-    let mut queries = HashMap::new();
-
-    for query in val {
-        // Parse tag value queries in format "tag_name=pattern"
-        if let Some(eq_pos) = query.find('=') {
-            let tag_name = query[..eq_pos].trim().to_string();
-            let pattern = query[eq_pos + 1..].trim();
-            let regex = Regex::new(pattern)?;
-            queries.insert(tag_name, regex);
-        }
-    }
-
-    Ok(queries)
-}
@@ -16,7 +16,7 @@ mod tests {
             target: Some("Work".to_string()),
             after: None,
             before: None,
-            _bool_op: "pattern".to_string(),
+            bool_op: "pattern".to_string(),
             case: "smart".to_string(),
             from: None,
             keep: None,
@@ -43,7 +43,7 @@ mod tests {
             target: Some("@urgent".to_string()),
             after: None,
             before: None,
-            _bool_op: "pattern".to_string(),
+            bool_op: "pattern".to_string(),
             case: "smart".to_string(),
             from: None,
             keep: None,
@@ -70,7 +70,7 @@ mod tests {
             target: None,
             after: None,
             before: None,
-            _bool_op: "pattern".to_string(),
+            bool_op: "pattern".to_string(),
             case: "smart".to_string(),
             from: None,
             keep: None,
@@ -106,7 +106,7 @@ mod tests {
             target: None,
             after: None,
             before: Some(now.format("%Y-%m-%d").to_string()),
-            _bool_op: "pattern".to_string(),
+            bool_op: "pattern".to_string(),
             case: "smart".to_string(),
             from: None,
             keep: None,
@@ -133,7 +133,7 @@ mod tests {
             target: Some("Currently".to_string()),
             after: None,
             before: None,
-            _bool_op: "pattern".to_string(),
+            bool_op: "pattern".to_string(),
             case: "smart".to_string(),
             from: None,
             keep: Some(2),
@@ -161,7 +161,7 @@ mod tests {
             target: Some("Currently".to_string()),
             after: None,
             before: None,
-            _bool_op: "pattern".to_string(),
+            bool_op: "pattern".to_string(),
             case: "smart".to_string(),
             from: None,
             keep: None,
@@ -186,7 +186,7 @@ mod tests {
             target: Some("Work".to_string()),
             after: None,
             before: None,
-            _bool_op: "pattern".to_string(),
+            bool_op: "pattern".to_string(),
             case: "smart".to_string(),
             from: None,
             keep: None,
@@ -200,4 +200,35 @@ mod tests {
         });
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_archive_by_value_query() {
+        let ctx = TestContext::new().unwrap();
+        ctx.create_test_file(
+            "Currently:\n - 2025-07-28 10:00 | Nearly done @progress(80%) <11111111-1111-1111-1111-111111111111>\n - 2025-07-28 11:00 | Just started @progress(20%) <22222222-2222-2222-2222-222222222222>\n"
+        ).unwrap();
+
+        handle_archive(ArchiveOptions {
+            target: None,
+            after: None,
+            before: None,
+            bool_op: "pattern".to_string(),
+            case: "smart".to_string(),
+            from: None,
+            keep: None,
+            label: false,
+            not: false,
+            search: None,
+            to: "Archive".to_string(),
+            tag: None,
+            val: vec!["@progress >= 50".to_string()],
+            exact: false,
+        })
+        .unwrap();
+
+        let content = ctx.read_test_file().unwrap();
+        let archive = content.split("Archive:").nth(1).unwrap();
+        assert!(archive.contains("Nearly done"));
+        assert!(!archive.contains("Just started"));
+    }
 }
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use crate::commands::{NowOptions, TagOptions, handle_now, handle_tag};
+    use crate::storage::{Config, parse_taskpaper};
+    use crate::test_utils::utils::TestContext;
+    use std::fs;
+
+    fn use_autotag_config(ctx: &TestContext) {
+        let mut config = Config::load();
+        config.autotag.whitelist = vec!["rust".to_string()];
+        config
+            .autotag
+            .synonyms
+            .insert("meeting".to_string(), vec!["standup".to_string()]);
+        config.default_tags = vec!["work".to_string()];
+        fs::write(&ctx.config_path, serde_json::to_string(&config).unwrap()).unwrap();
+    }
+
+    fn now(entry: &str, noauto: bool) -> color_eyre::Result<()> {
+        handle_now(NowOptions {
+            entry: vec![entry.to_string()],
+            note: None,
+            back: None,
+            section: None,
+            finish_last: false,
+            from: None,
+            editor: false,
+            ask: false,
+            noauto,
+        })
+    }
+
+    fn tags_of(ctx: &TestContext, description: &str) -> Vec<String> {
+        let doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        let entry = doing_file
+            .get_all_entries()
+            .into_iter()
+            .find(|entry| entry.description == description)
+            .unwrap()
+            .clone();
+        entry.tags.into_keys().collect()
+    }
+
+    #[test]
+    fn test_now_applies_autotags_and_default_tags() {
+        let ctx = TestContext::new().unwrap();
+        ctx.create_test_file("Currently:\n").unwrap();
+        use_autotag_config(&ctx);
+
+        now("Rust standup @team", false).unwrap();
+
+        assert_eq!(
+            tags_of(&ctx, "Rust standup"),
+            vec!["team", "work", "rust", "meeting"]
+        );
+    }
+
+    #[test]
+    fn test_noauto_skips_autotags() {
+        let ctx = TestContext::new().unwrap();
+        ctx.create_test_file("Currently:\n").unwrap();
+        use_autotag_config(&ctx);
+
+        now("Rust standup", true).unwrap();
+
+        assert!(tags_of(&ctx, "Rust standup").is_empty());
+    }
+
+    #[test]
+    fn test_tag_autotag_retags_existing_entries() {
+        let ctx = TestContext::new().unwrap();
+        ctx.create_test_file(
+            "Currently:\n - 2025-07-28 10:00 | Daily standup <11111111-1111-1111-1111-111111111111>\n",
+        )
+        .unwrap();
+        use_autotag_config(&ctx);
+
+        handle_tag(TagOptions {
+            tags: vec![],
+            autotag: true,
+            bool_op: "pattern".to_string(),
+            count: 1,
+            case: "smart".to_string(),
+            date: false,
+            force: false,
+            interactive: false,
+            not: false,
+            remove: false,
+            regex: false,
+            rename: None,
+            sections: vec![],
+            search: None,
+            tag: None,
+            unfinished: false,
+            value: None,
+            val: vec![],
+            exact: false,
+        })
+        .unwrap();
+
+        // Default tags are only for new entries
+        assert_eq!(tags_of(&ctx, "Daily standup"), vec!["meeting"]);
+    }
+}
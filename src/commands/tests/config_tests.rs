@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests {
+    use crate::commands::config::{edit_yaml, set_in_toml, set_setting};
+    use crate::storage::Config;
+    use crate::storage::config::{ConfigFile, ConfigScope};
+    use crate::test_utils::utils::TestContext;
+    use serde_json::json;
+    use std::fs;
+
+    fn test_config_file(ctx: &TestContext) -> ConfigFile {
+        ConfigFile {
+            path: ctx.config_path.clone(),
+            scope: ConfigScope::Global,
+        }
+    }
+
+    #[test]
+    fn test_set_updates_effective_config() {
+        let ctx = TestContext::new().unwrap();
+        let file = test_config_file(&ctx);
+
+        set_setting(&file, "history_size", json!(7)).unwrap();
+        set_setting(&file, "dialect", json!("doing")).unwrap();
+
+        let config = Config::load();
+        assert_eq!(config.history_size, 7);
+        assert_eq!(config.dialect, Some(crate::models::Dialect::Doing));
+        // Settings that weren't touched are kept
+        assert_eq!(config.doing_file, ctx.doing_file_path);
+    }
+
+    #[test]
+    fn test_set_rejects_invalid_values() {
+        let ctx = TestContext::new().unwrap();
+        let file = test_config_file(&ctx);
+        let before = fs::read_to_string(&ctx.config_path).unwrap();
+
+        assert!(set_setting(&file, "history_size", json!("lots")).is_err());
+        assert!(set_setting(&file, "dialect", json!("emacs")).is_err());
+        assert_eq!(fs::read_to_string(&ctx.config_path).unwrap(), before);
+    }
+
+    #[test]
+    fn test_set_keeps_toml_comments() {
+        let content = "\
+# my settings
+history_size = 20 # plenty
+
+[autotag]
+whitelist = [\"rust\"]
+";
+        let updated = set_in_toml(content, "history_size", &json!(30)).unwrap();
+        let updated = set_in_toml(
+            &updated,
+            "autotag.synonyms",
+            &json!({"meeting": ["standup"]}),
+        )
+        .unwrap();
+        let updated = set_in_toml(&updated, "doing_file", &json!("~/work.md")).unwrap();
+
+        assert!(updated.starts_with("# my settings\nhistory_size = 30 # plenty\n"));
+        assert!(updated.contains("whitelist = [\"rust\"]"));
+        assert!(updated.contains("[autotag.synonyms]\nmeeting = [\"standup\"]"));
+        assert!(updated.contains("doing_file = \"~/work.md\""));
+
+        let parsed: toml::Value = toml::from_str(&updated).unwrap();
+        assert_eq!(parsed["history_size"].as_integer(), Some(30));
+    }
+
+    #[test]
+    fn test_set_keeps_doingrc_comments() {
+        let content = "\
+---
+# doing settings
+doing_file: ~/what_was_i_doing.md # main file
+current_section: Currently
+templates:
+  default:
+    date_format: '%Y-%m-%d %H:%M'
+    template: '%date | %title%note'
+  today:
+    date_format: '%_I:%M%P'
+
+# set by hand
+editor_app: vim
+";
+        let updated = edit_yaml(content, "doing_file", Some(&json!("~/work.md"))).unwrap();
+        let updated = edit_yaml(
+            &updated,
+            "templates.today.date_format",
+            Some(&json!("%H:%M")),
+        )
+        .unwrap();
+        let updated = edit_yaml(
+            &updated,
+            "templates.brief",
+            Some(&json!({"template": "%title"})),
+        )
+        .unwrap();
+        let updated = edit_yaml(&updated, "templates.default", None).unwrap();
+        let updated = edit_yaml(&updated, "history_size", Some(&json!(50))).unwrap();
+
+        assert_eq!(
+            updated,
+            "\
+---
+# doing settings
+doing_file: ~/work.md # main file
+current_section: Currently
+templates:
+  today:
+    date_format: '%H:%M'
+  brief:
+    template: '%title'
+
+# set by hand
+editor_app: vim
+history_size: 50
+"
+        );
+        // Removing what isn't there changes nothing
+        assert_eq!(edit_yaml(&updated, "views.standup", None).unwrap(), updated);
+    }
+}
@@ -32,7 +32,7 @@ mod tests {
             remove: false,
             unfinished: false,
             _date: false,
-            _noauto: false,
+            noauto: false,
         })?;
 
         // Re-read the file after done command
@@ -76,7 +76,7 @@ mod tests {
             remove: false,
             unfinished: false,
             _date: false,
-            _noauto: false,
+            noauto: false,
         })?;
 
         let doing_file = parse_taskpaper(&ctx.doing_file_path)?;
@@ -117,7 +117,7 @@ mod tests {
             remove: false,
             unfinished: false,
             _date: false,
-            _noauto: false,
+            noauto: false,
         })?;
 
         let doing_file = parse_taskpaper(&ctx.doing_file_path)?;
@@ -163,7 +163,7 @@ mod tests {
             remove: false,
             unfinished: false,
             _date: false,
-            _noauto: false,
+            noauto: false,
         })?;
 
         let doing_file = parse_taskpaper(&ctx.doing_file_path)?;
@@ -207,7 +207,7 @@ mod tests {
             remove: true,
             unfinished: false,
             _date: false,
-            _noauto: false,
+            noauto: false,
         })?;
 
         // Re-read the file after removing done tag
@@ -248,7 +248,7 @@ mod tests {
             remove: false,
             unfinished: false,
             _date: false,
-            _noauto: false,
+            noauto: false,
         })?;
 
         let doing_file = parse_taskpaper(&ctx.doing_file_path)?;
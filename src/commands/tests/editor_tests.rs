@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod tests {
+    use crate::commands::{
+        NowOptions, ShowConfigOptions, ShowDisplayOptions, ShowFilterOptions, ShowUIOptions,
+        handle_now, handle_show,
+    };
+    use crate::storage::{Config, parse_taskpaper};
+    use crate::test_utils::utils::{TestContext, TestEntry};
+    use chrono::{Duration, Local};
+    use std::fs;
+
+    /// Use a shell script as the editor; it gets the buffer's path as `$1`
+    fn use_editor_script(ctx: &TestContext, script: &str) {
+        let script_path = ctx.temp_dir.path().join("editor.sh");
+        fs::write(&script_path, script).unwrap();
+
+        let mut config = Config::load();
+        config.editor_app = Some(format!("sh {}", script_path.display()));
+        fs::write(&ctx.config_path, serde_json::to_string(&config).unwrap()).unwrap();
+    }
+
+    fn now_with_editor(entry: &str) -> color_eyre::Result<()> {
+        handle_now(NowOptions {
+            entry: vec![entry.to_string()],
+            note: None,
+            back: None,
+            section: None,
+            finish_last: false,
+            from: None,
+            editor: true,
+            ask: false,
+            noauto: false,
+        })
+    }
+
+    #[test]
+    fn test_now_with_editor() {
+        let ctx = TestContext::new().unwrap();
+        ctx.create_test_file("Currently:\n").unwrap();
+        use_editor_script(
+            &ctx,
+            r#"grep -q "^Draft title$" "$1" && printf 'Edited entry @review\nWith a note\n' > "$1""#,
+        );
+
+        now_with_editor("Draft title").unwrap();
+
+        let doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        let entries = doing_file.get_entries("Currently").unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].description, "Edited entry");
+        assert!(entries[0].tags.contains_key("review"));
+        assert_eq!(entries[0].note.as_deref(), Some("With a note"));
+    }
+
+    #[test]
+    fn test_empty_editor_buffer_adds_nothing() {
+        let ctx = TestContext::new().unwrap();
+        ctx.create_test_file("Currently:\n").unwrap();
+        use_editor_script(&ctx, r#": > "$1""#);
+
+        now_with_editor("Draft title").unwrap();
+
+        assert_eq!(ctx.read_test_file().unwrap(), "Currently:\n");
+    }
+
+    #[test]
+    fn test_failing_editor_is_an_error() {
+        let ctx = TestContext::new().unwrap();
+        ctx.create_test_file("Currently:\n").unwrap();
+        use_editor_script(&ctx, "exit 1");
+
+        assert!(now_with_editor("Draft title").is_err());
+        assert_eq!(ctx.read_test_file().unwrap(), "Currently:\n");
+    }
+
+    #[test]
+    fn test_show_bulk_edit() {
+        let ctx = TestContext::new().unwrap();
+        let now = Local::now();
+        ctx.create_doing_file_with_entries(vec![
+            TestEntry::new("Write report")
+                .with_timestamp(now - Duration::hours(2))
+                .with_tags(vec!["work"]),
+            TestEntry::new("Lunch").with_timestamp(now - Duration::hours(1)),
+            TestEntry::new("Old task")
+                .with_timestamp(now - Duration::days(3))
+                .with_section("Archive"),
+        ])
+        .unwrap();
+        use_editor_script(
+            &ctx,
+            r#"sed -e 's/Write report @work/Write quarterly report @work @done(2025-01-01 10:00)/' -e 's/^Currently:$/Later:/' "$1" > "$1.new" && mv "$1.new" "$1""#,
+        );
+
+        handle_show(
+            ShowFilterOptions {
+                args: vec!["Currently".to_string()],
+                age: "newest".to_string(),
+                after: None,
+                before: None,
+                bool_op: "pattern".to_string(),
+                case: "smart".to_string(),
+                from: None,
+                not: false,
+                only_timed: false,
+                sections: vec![],
+                search: None,
+                tag: None,
+                val: vec![],
+                exact: false,
+            },
+            ShowDisplayOptions {
+                count: 0,
+                duration: false,
+                hilite: false,
+                output: None,
+                sort: "asc".to_string(),
+                times: true,
+                tag_order: None,
+                tag_sort: None,
+                totals: false,
+            },
+            ShowUIOptions {
+                interactive: false,
+                menu: false,
+                editor: true,
+            },
+            ShowConfigOptions {
+                config_template: None,
+                save: None,
+                template: None,
+                title: None,
+            },
+        )
+        .unwrap();
+
+        let doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        assert!(doing_file.get_entries("Currently").unwrap().is_empty());
+        let later = doing_file.get_entries("Later").unwrap();
+        assert_eq!(later.len(), 2);
+        assert_eq!(later[0].description, "Write quarterly report");
+        assert!(later[0].is_done());
+        assert_eq!(later[1].description, "Lunch");
+        // Entries that weren't in the buffer are untouched
+        assert_eq!(doing_file.get_entries("Archive").unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,159 @@
+#[cfg(test)]
+mod tests {
+    use crate::commands::{ImportOptions, handle_import};
+    use crate::storage::parse_taskpaper;
+    use crate::test_utils::utils::TestContext;
+    use std::fs;
+    use std::path::PathBuf;
+
+    const TIMEWARRIOR: &str = r#"[
+        {"id":2,"start":"20250728T090000Z","end":"20250728T103000Z","tags":["rust"],"annotation":"Parser fixes"},
+        {"id":1,"start":"20250728T110000Z","end":"20250728T113000Z","tags":["meeting"],"annotation":"Standup"}
+    ]"#;
+
+    fn export(ctx: &TestContext, content: &str) -> PathBuf {
+        let path = ctx.temp_dir.path().join("export.json");
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn import(file: PathBuf, section: Option<&str>, dry_run: bool) -> color_eyre::Result<()> {
+        handle_import(ImportOptions {
+            file,
+            kind: "timewarrior".to_string(),
+            section: section.map(str::to_string),
+            dry_run,
+            prefix: None,
+            tag: None,
+        })
+    }
+
+    #[test]
+    fn test_import_adds_done_entries_once() {
+        let ctx = TestContext::new().unwrap();
+        ctx.create_test_file("Currently:\n").unwrap();
+        let file = export(&ctx, TIMEWARRIOR);
+
+        import(file.clone(), Some("Imported"), false).unwrap();
+        import(file, None, false).unwrap();
+
+        let doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        let entries = doing_file.get_entries("Imported").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|entry| entry.is_done()));
+        assert!(doing_file.get_entries("Currently").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_dry_run_leaves_the_file_alone() {
+        let ctx = TestContext::new().unwrap();
+        ctx.create_test_file("Currently:\n").unwrap();
+        let file = export(&ctx, TIMEWARRIOR);
+
+        import(file, None, true).unwrap();
+
+        assert_eq!(ctx.read_test_file().unwrap(), "Currently:\n");
+    }
+
+    #[test]
+    fn test_skips_entries_with_the_same_start_and_title() {
+        let ctx = TestContext::new().unwrap();
+        let file = export(&ctx, TIMEWARRIOR);
+        // The same standup, logged by hand in local time
+        let standup = local_minute("2025-07-28T11:00:00Z");
+        ctx.create_test_file(&format!(
+            "Currently:\n - {} | Standup @meeting <11111111-1111-1111-1111-111111111111>\n",
+            standup
+        ))
+        .unwrap();
+
+        import(file, None, false).unwrap();
+
+        let doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        let titles: Vec<&str> = doing_file
+            .get_all_entries()
+            .into_iter()
+            .map(|entry| entry.description.as_str())
+            .collect();
+        assert_eq!(titles, vec!["Standup", "Parser fixes"]);
+    }
+
+    #[test]
+    fn test_merge_another_doing_file() {
+        let ctx = TestContext::new().unwrap();
+        ctx.create_test_file(
+            "Currently:\n - 2025-07-28 09:00 | Review <11111111-1111-1111-1111-111111111111>\n\
+             Later:\n",
+        )
+        .unwrap();
+        let other = ctx.temp_dir.path().join("other.md");
+        fs::write(
+            &other,
+            "currently:\n\t- 2025-07-28 09:00 | Review <11111111111111111111111111111111>\n\
+             \t- 2025-07-28 10:00 | Deploy <11111111111111111111111111111111>\n\
+             later:\n\t- 2025-07-28 12:00 | Plan sprint <22222222222222222222222222222222>\n",
+        )
+        .unwrap();
+
+        handle_import(ImportOptions {
+            file: other,
+            kind: "taskpaper".to_string(),
+            section: None,
+            dry_run: false,
+            prefix: Some("[Ana] ".to_string()),
+            tag: Some("ana".to_string()),
+        })
+        .unwrap();
+
+        let doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        let currently = doing_file.get_entries("Currently").unwrap();
+        assert_eq!(currently.len(), 2);
+        // Same id as the existing entry but not the same entry: it gets a new id
+        assert_eq!(currently[1].description, "[Ana] Deploy");
+        assert!(currently[1].tags.contains_key("ana"));
+        assert_ne!(currently[1].uuid, currently[0].uuid);
+
+        let later = doing_file.get_entries("Later").unwrap();
+        assert_eq!(
+            later[0].uuid.simple().to_string(),
+            "22222222222222222222222222222222"
+        );
+        assert!(doing_file.get_entries("later").is_none());
+    }
+
+    #[test]
+    fn test_reimport_with_prefix_and_tag() {
+        let ctx = TestContext::new().unwrap();
+        ctx.create_test_file("Currently:\n").unwrap();
+        let file = export(&ctx, TIMEWARRIOR);
+        let import_prefixed = || {
+            handle_import(ImportOptions {
+                file: file.clone(),
+                kind: "timewarrior".to_string(),
+                section: None,
+                dry_run: false,
+                prefix: Some("TW: ".to_string()),
+                tag: Some("tw".to_string()),
+            })
+        };
+
+        import_prefixed().unwrap();
+        import_prefixed().unwrap();
+
+        let doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        let titles: Vec<&str> = doing_file
+            .get_all_entries()
+            .into_iter()
+            .map(|entry| entry.description.as_str())
+            .collect();
+        assert_eq!(titles, vec!["TW: Parser fixes", "TW: Standup"]);
+    }
+
+    fn local_minute(utc: &str) -> String {
+        utc.parse::<chrono::DateTime<chrono::Utc>>()
+            .unwrap()
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    }
+}
@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod tests {
+    use crate::commands::invoice::timesheet;
+    use crate::commands::{InvoiceOptions, handle_invoice};
+    use crate::storage::Config;
+    use crate::test_utils::utils::{TestContext, timed_entry};
+    use std::fs;
+
+    fn options(output: Option<&str>, by: &str) -> InvoiceOptions {
+        InvoiceOptions {
+            week: false,
+            month: false,
+            from: Some("2025-07-01 to 2025-07-31".to_string()),
+            output: output.map(str::to_string),
+            by: by.to_string(),
+            client: None,
+            sections: vec![],
+            tag: None,
+            title: None,
+        }
+    }
+
+    fn invoice(output: Option<&str>, by: &str) -> color_eyre::Result<()> {
+        handle_invoice(options(output, by))
+    }
+
+    #[test]
+    fn test_invoice_rates_rounding_and_clients() -> color_eyre::Result<()> {
+        let ctx = TestContext::new()?;
+        ctx.create_doing_file_from_entries(&[
+            timed_entry(
+                "Design review",
+                "2025-07-28 09:00",
+                Some("2025-07-28 09:50"),
+                &["client(acme)"],
+            ),
+            timed_entry(
+                "Support call",
+                "2025-07-29 10:00",
+                Some("2025-07-29 10:20"),
+                &["client(Globex)"],
+            ),
+            timed_entry(
+                "Invoicing",
+                "2025-07-29 11:00",
+                Some("2025-07-29 11:10"),
+                &[],
+            )
+            .with_section("Admin".to_string()),
+            timed_entry(
+                "Team lunch",
+                "2025-07-29 12:00",
+                Some("2025-07-29 13:00"),
+                &["client(acme)", "non-billable"],
+            ),
+            timed_entry(
+                "Month end call",
+                "2025-07-31 10:00",
+                Some("2025-07-31 10:30"),
+                &["client(Globex)"],
+            ),
+            timed_entry(
+                "Next month",
+                "2025-08-01 09:00",
+                Some("2025-08-01 10:00"),
+                &["client(acme)"],
+            ),
+            timed_entry("Still going", "2025-07-30 09:00", None, &["client(acme)"]),
+        ])?;
+
+        // Rates as they would be written in the config file
+        let mut config: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&ctx.config_path)?)?;
+        config["invoice"] = serde_json::json!({
+            "rate": 80,
+            "rounding": 15,
+            "currency": "$",
+            "clients": {"acme": {"rate": 120}, "admin": {"rate": 100, "rounding": 0}}
+        });
+        fs::write(&ctx.config_path, config.to_string())?;
+        assert_eq!(Config::load().invoice.clients["acme"].rate, Some(120.0));
+
+        let sheet = timesheet(options(None, "client"))?;
+        assert_eq!(sheet.title, "Timesheet for 2025-07-01 to 2025-07-31");
+        let clients: Vec<(&str, usize, f64, f64)> = sheet
+            .clients
+            .iter()
+            .map(|client| {
+                (
+                    client.name.as_str(),
+                    client.lines.len(),
+                    client.billed_hours(),
+                    client.amount(),
+                )
+            })
+            .collect();
+        // 50 minutes bill as 45 and 20 as 15; the call on the last day of
+        // the range counts too. Admin's 10 minutes are not rounded, and 0.17
+        // hours bill as 17.00, not 16.67
+        assert_eq!(
+            clients,
+            vec![
+                ("acme", 1, 0.75, 90.0),
+                ("Globex", 2, 0.75, 60.0),
+                ("Admin", 1, 0.17, 17.0),
+            ]
+        );
+        assert_eq!(sheet.amount(), 167.0);
+
+        let mut options = options(None, "section");
+        options.client = Some("currently".to_string());
+        let by_section = timesheet(options)?;
+        assert_eq!(by_section.clients.len(), 1);
+        assert_eq!(by_section.clients[0].lines.len(), 3);
+        assert_eq!(by_section.clients[0].billed_hours(), 1.5);
+
+        for output in [None, Some("csv"), Some("html")] {
+            invoice(output, "client")?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invoice_rejects_bad_options() {
+        let _ctx = TestContext::new().unwrap();
+        assert!(invoice(Some("json"), "client").is_err());
+        assert!(invoice(None, "project").is_err());
+    }
+}
@@ -1,16 +1,28 @@
 #[cfg(test)]
 mod archive_tests;
 #[cfg(test)]
+mod autotag_tests;
+#[cfg(test)]
+mod config_tests;
+#[cfg(test)]
 mod delete_tests;
 #[cfg(test)]
 mod done_tests;
 #[cfg(test)]
+mod editor_tests;
+#[cfg(test)]
+mod import_tests;
+#[cfg(test)]
+mod invoice_tests;
+#[cfg(test)]
 mod last_tests;
 #[cfg(test)]
 mod now_tests;
 #[cfg(test)]
 mod recent_tests;
 #[cfg(test)]
+mod report_tests;
+#[cfg(test)]
 mod rotate_tests;
 #[cfg(test)]
 mod sections_tests;
@@ -18,3 +30,7 @@ mod sections_tests;
 mod tags_tests;
 #[cfg(test)]
 mod today_tests;
+#[cfg(test)]
+mod undo_tests;
+#[cfg(test)]
+mod view_tests;
@@ -21,7 +21,7 @@ mod tests {
             from: None,
             editor: false,
             ask: false,
-            _noauto: false,
+            noauto: false,
         })?;
 
         let content = ctx.read_test_file()?;
@@ -51,7 +51,7 @@ mod tests {
             from: None,
             editor: false,
             ask: false,
-            _noauto: false,
+            noauto: false,
         })?;
 
         let content = ctx.read_test_file()?;
@@ -77,7 +77,7 @@ mod tests {
             from: None,
             editor: false,
             ask: false,
-            _noauto: false,
+            noauto: false,
         })?;
 
         let content = ctx.read_test_file()?;
@@ -102,7 +102,7 @@ mod tests {
             from: None,
             editor: false,
             ask: false,
-            _noauto: false,
+            noauto: false,
         })?;
 
         let content = ctx.read_test_file()?;
@@ -127,7 +127,7 @@ mod tests {
             from: None,
             editor: false,
             ask: false,
-            _noauto: false,
+            noauto: false,
         })?;
 
         let _content = ctx.read_test_file()?;
@@ -142,7 +142,7 @@ mod tests {
 
         let expected_time = Local::now() - Duration::hours(2);
         let time_diff = entry
-            .timestamp
+            .started_at
             .signed_duration_since(expected_time)
             .num_minutes()
             .abs();
@@ -177,7 +177,7 @@ mod tests {
             from: None,
             editor: false,
             ask: false,
-            _noauto: false,
+            noauto: false,
         })?;
 
         let doing_file = parse_taskpaper(&ctx.doing_file_path)?;
@@ -221,7 +221,7 @@ mod tests {
             from: Some("from 2pm to 3:30pm".to_string()),
             editor: false,
             ask: false,
-            _noauto: false,
+            noauto: false,
         })?;
 
         let doing_file = parse_taskpaper(&ctx.doing_file_path)?;
@@ -252,7 +252,7 @@ mod tests {
             from: None,
             editor: false,
             ask: false,
-            _noauto: false,
+            noauto: false,
         })?;
 
         let doing_file = parse_taskpaper(&ctx.doing_file_path)?;
@@ -301,7 +301,7 @@ mod tests {
             from: None,
             editor: false,
             ask: false,
-            _noauto: false,
+            noauto: false,
         })?;
 
         let doing_file = parse_taskpaper(&ctx.doing_file_path)?;
@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod tests {
+    use crate::commands::report::report;
+    use crate::commands::{ReportOptions, handle_report};
+    use crate::test_utils::utils::{TestContext, timed_entry};
+
+    fn options(output: Option<&str>) -> ReportOptions {
+        ReportOptions {
+            week: false,
+            month: false,
+            from: Some("2025-07-28 to 2025-08-03".to_string()),
+            output: output.map(str::to_string),
+            sections: vec![],
+            tag: None,
+            title: None,
+        }
+    }
+
+    fn create_week(ctx: &TestContext) -> color_eyre::Result<()> {
+        ctx.create_doing_file_from_entries(&[
+            timed_entry(
+                "Write code",
+                "2025-07-28 09:00",
+                Some("2025-07-28 11:00"),
+                &["code"],
+            ),
+            timed_entry(
+                "Planning",
+                "2025-07-29 09:00",
+                Some("2025-07-29 10:00"),
+                &["meeting"],
+            )
+            .with_section("Meetings".to_string()),
+            timed_entry("Refactor", "2025-07-29 10:00", None, &["code"]),
+            timed_entry(
+                "Last week",
+                "2025-07-25 09:00",
+                Some("2025-07-25 10:00"),
+                &[],
+            ),
+            timed_entry(
+                "Sunday review",
+                "2025-08-03 15:00",
+                Some("2025-08-03 15:30"),
+                &[],
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_report_days_and_open_items() -> color_eyre::Result<()> {
+        let ctx = TestContext::new()?;
+        create_week(&ctx)?;
+
+        let week = report(options(None))?;
+        assert_eq!(week.title, "Report for 2025-07-28 to 2025-08-03");
+        let days: Vec<(String, i64, Vec<&str>)> = week
+            .days
+            .iter()
+            .map(|day| {
+                (
+                    day.date.to_string(),
+                    day.tracked.num_minutes(),
+                    day.entries
+                        .iter()
+                        .map(|(_, entry)| entry.description.as_str())
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            days,
+            vec![
+                ("2025-07-28".to_string(), 120, vec!["Write code"]),
+                ("2025-07-29".to_string(), 60, vec!["Planning"]),
+                ("2025-08-03".to_string(), 30, vec!["Sunday review"]),
+            ]
+        );
+        assert_eq!(week.totals.tags[0].name, "code");
+        assert_eq!(week.open.len(), 1);
+        assert_eq!(week.open[0].1.description, "Refactor");
+
+        // The range covers whole days, whatever time it starts at
+        let mut from_noon = options(None);
+        from_noon.from = Some("2025-07-28 12:00 to 2025-08-03".to_string());
+        assert_eq!(report(from_noon)?.days.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_filters() -> color_eyre::Result<()> {
+        let ctx = TestContext::new()?;
+        create_week(&ctx)?;
+
+        let mut by_tag = options(None);
+        by_tag.tag = Some("code".to_string());
+        by_tag.title = Some("Code".to_string());
+        let code = report(by_tag)?;
+        assert_eq!(code.title, "Code");
+        assert_eq!(code.days.len(), 1);
+        assert_eq!(code.open.len(), 1);
+
+        let mut by_section = options(None);
+        by_section.sections = vec!["Meetings".to_string()];
+        let meetings = report(by_section)?;
+        assert_eq!(meetings.days.len(), 1);
+        assert_eq!(meetings.days[0].entries[0].1.description, "Planning");
+        assert!(meetings.open.is_empty());
+
+        for output in [None, Some("markdown"), Some("html"), Some("json")] {
+            handle_report(options(output))?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_rejects_other_formats() {
+        let _ctx = TestContext::new().unwrap();
+        assert!(handle_report(options(Some("csv"))).is_err());
+    }
+}
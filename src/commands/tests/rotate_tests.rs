@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::commands::{RotateOptions, handle_rotate};
+    use crate::storage::FileLock;
     use crate::test_utils::utils::*;
     use chrono::Local;
 
@@ -14,7 +15,7 @@ mod tests {
         // Rotate done entries
         let result = handle_rotate(RotateOptions {
             before: None,
-            _bool_op: "pattern".to_string(),
+            bool_op: "pattern".to_string(),
             case: "smart".to_string(),
             keep: None,
             not: false,
@@ -49,7 +50,7 @@ mod tests {
         // Rotate entries before yesterday
         let result = handle_rotate(RotateOptions {
             before: Some(yesterday.format("%Y-%m-%d").to_string()),
-            _bool_op: "pattern".to_string(),
+            bool_op: "pattern".to_string(),
             case: "smart".to_string(),
             keep: None,
             not: false,
@@ -72,7 +73,7 @@ mod tests {
         // Rotate only from Work section
         let result = handle_rotate(RotateOptions {
             before: None,
-            _bool_op: "pattern".to_string(),
+            bool_op: "pattern".to_string(),
             case: "smart".to_string(),
             keep: None,
             not: false,
@@ -95,7 +96,7 @@ mod tests {
         // Rotate only @bug entries
         let result = handle_rotate(RotateOptions {
             before: None,
-            _bool_op: "pattern".to_string(),
+            bool_op: "pattern".to_string(),
             case: "smart".to_string(),
             keep: None,
             not: false,
@@ -119,7 +120,7 @@ mod tests {
         // Rotate new entry
         let result = handle_rotate(RotateOptions {
             before: None,
-            _bool_op: "pattern".to_string(),
+            bool_op: "pattern".to_string(),
             case: "smart".to_string(),
             keep: None,
             not: false,
@@ -142,7 +143,7 @@ mod tests {
         // Try to rotate - should find no entries
         let result = handle_rotate(RotateOptions {
             before: None,
-            _bool_op: "pattern".to_string(),
+            bool_op: "pattern".to_string(),
             case: "smart".to_string(),
             keep: None,
             not: false,
@@ -165,7 +166,7 @@ mod tests {
         // Rotate only oldest 2 entries
         let result = handle_rotate(RotateOptions {
             before: None,
-            _bool_op: "pattern".to_string(),
+            bool_op: "pattern".to_string(),
             case: "smart".to_string(),
             keep: Some(2),
             not: false,
@@ -177,4 +178,34 @@ mod tests {
         });
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_rotate_waits_for_the_archive_lock() {
+        let ctx = TestContext::new().unwrap();
+        let content = "Currently:\n - 2025-07-28 10:00 | Completed task @done(2025-07-28 11:00) <11111111-1111-1111-1111-111111111111>\n";
+        ctx.create_test_file(content).unwrap();
+        let mut config: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&ctx.config_path).unwrap()).unwrap();
+        config["lock_timeout"] = serde_json::json!(0);
+        std::fs::write(&ctx.config_path, config.to_string()).unwrap();
+
+        // Another rotation holds the archive: nothing leaves the doing file
+        let archive = ctx.temp_dir.path().join("test_doing_archive.taskpaper");
+        let _lock = FileLock::acquire(&archive).unwrap();
+        let result = handle_rotate(RotateOptions {
+            before: None,
+            bool_op: "pattern".to_string(),
+            case: "smart".to_string(),
+            keep: None,
+            not: false,
+            section: None,
+            search: None,
+            tag: None,
+            val: vec![],
+            exact: false,
+        });
+        assert!(result.is_err());
+        assert_eq!(ctx.read_test_file().unwrap(), content);
+        assert!(!archive.exists());
+    }
 }
@@ -17,6 +17,7 @@ mod tests {
                 search: None,
                 tag: None,
                 val: vec![],
+                bool_op: "pattern".to_string(),
                 case: "smart".to_string(),
                 exact: false,
                 not: false,
@@ -47,6 +48,7 @@ mod tests {
                 search: None,
                 tag: None,
                 val: vec![],
+                bool_op: "pattern".to_string(),
                 case: "smart".to_string(),
                 exact: false,
                 not: false,
@@ -77,6 +79,7 @@ mod tests {
                 search: None,
                 tag: None,
                 val: vec![],
+                bool_op: "pattern".to_string(),
                 case: "smart".to_string(),
                 exact: false,
                 not: false,
@@ -107,6 +110,7 @@ mod tests {
                 search: Some("bug".to_string()),
                 tag: None,
                 val: vec![],
+                bool_op: "pattern".to_string(),
                 case: "smart".to_string(),
                 exact: false,
                 not: false,
@@ -137,6 +141,7 @@ mod tests {
                 search: None,
                 tag: None,
                 val: vec![],
+                bool_op: "pattern".to_string(),
                 case: "smart".to_string(),
                 exact: false,
                 not: false,
@@ -167,6 +172,7 @@ mod tests {
                 search: None,
                 tag: None,
                 val: vec![],
+                bool_op: "pattern".to_string(),
                 case: "smart".to_string(),
                 exact: false,
                 not: false,
@@ -196,6 +202,7 @@ mod tests {
                 search: None,
                 tag: None,
                 val: vec![],
+                bool_op: "pattern".to_string(),
                 case: "smart".to_string(),
                 exact: false,
                 not: false,
@@ -224,6 +231,7 @@ mod tests {
                 search: None,
                 tag: None,
                 val: vec![],
+                bool_op: "pattern".to_string(),
                 case: "smart".to_string(),
                 exact: false,
                 not: false,
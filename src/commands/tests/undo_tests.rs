@@ -0,0 +1,223 @@
+#[cfg(test)]
+mod tests {
+    use crate::commands::{
+        DeleteOptions, RotateOptions, handle_delete, handle_redo, handle_rotate, handle_undo,
+    };
+    use crate::storage::{Config, History, history, parse_taskpaper};
+    use crate::test_utils::utils::{TestContext, TestEntry};
+    use chrono::{Duration, Local};
+
+    fn delete_last() -> color_eyre::Result<()> {
+        handle_delete(DeleteOptions {
+            count: 1,
+            interactive: false,
+            not: false,
+            sections: vec![],
+            search: None,
+            tag: None,
+            exact: false,
+            force: true,
+        })
+    }
+
+    fn descriptions(ctx: &TestContext) -> Vec<String> {
+        parse_taskpaper(&ctx.doing_file_path)
+            .unwrap()
+            .get_all_entries()
+            .iter()
+            .map(|e| e.description.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_undo_restores_deleted_entry() -> color_eyre::Result<()> {
+        let ctx = TestContext::new()?;
+        let now = Local::now();
+        ctx.create_doing_file_with_entries(vec![
+            TestEntry::new("Keep this").with_timestamp(now - Duration::hours(1)),
+            TestEntry::new("Delete this").with_timestamp(now),
+        ])?;
+        let original = ctx.read_test_file()?;
+
+        history::begin_operation("delete --force");
+        delete_last()?;
+        assert_eq!(descriptions(&ctx), vec!["Keep this"]);
+
+        handle_undo(1, false)?;
+        assert_eq!(ctx.read_test_file()?, original);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_redo_reapplies_undone_change() -> color_eyre::Result<()> {
+        let ctx = TestContext::new()?;
+        let now = Local::now();
+        ctx.create_doing_file_with_entries(vec![
+            TestEntry::new("Keep this").with_timestamp(now - Duration::hours(1)),
+            TestEntry::new("Delete this").with_timestamp(now),
+        ])?;
+
+        history::begin_operation("delete --force");
+        delete_last()?;
+        let after_delete = ctx.read_test_file()?;
+
+        handle_undo(1, false)?;
+        assert_eq!(descriptions(&ctx).len(), 2);
+
+        handle_redo(1, false)?;
+        assert_eq!(ctx.read_test_file()?, after_delete);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_undo_multiple_steps() -> color_eyre::Result<()> {
+        let ctx = TestContext::new()?;
+        let now = Local::now();
+        ctx.create_doing_file_with_entries(vec![
+            TestEntry::new("Task 1").with_timestamp(now - Duration::hours(2)),
+            TestEntry::new("Task 2").with_timestamp(now - Duration::hours(1)),
+            TestEntry::new("Task 3").with_timestamp(now),
+        ])?;
+
+        history::begin_operation("delete first");
+        delete_last()?;
+        history::begin_operation("delete second");
+        delete_last()?;
+        assert_eq!(descriptions(&ctx), vec!["Task 1"]);
+
+        handle_undo(2, false)?;
+        assert_eq!(descriptions(&ctx).len(), 3);
+
+        let history = History::open(&Config::load())?;
+        assert!(history.undo_records().is_empty());
+        assert_eq!(history.redo_records().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_change_clears_redo() -> color_eyre::Result<()> {
+        let ctx = TestContext::new()?;
+        let now = Local::now();
+        ctx.create_doing_file_with_entries(vec![
+            TestEntry::new("Task 1").with_timestamp(now - Duration::hours(1)),
+            TestEntry::new("Task 2").with_timestamp(now),
+        ])?;
+
+        history::begin_operation("delete");
+        delete_last()?;
+        handle_undo(1, false)?;
+
+        history::begin_operation("delete again");
+        delete_last()?;
+
+        let history = History::open(&Config::load())?;
+        assert!(history.redo_records().is_empty());
+        assert_eq!(history.undo_records().len(), 1);
+        assert_eq!(history.undo_records()[0].command, "delete again");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_undo_rotate_restores_both_files() -> color_eyre::Result<()> {
+        let ctx = TestContext::new()?;
+        ctx.create_test_file(
+            "Currently:\n - 2025-07-28 10:00 | Completed task @done(2025-07-28 11:00) <11111111-1111-1111-1111-111111111111>\n",
+        )?;
+        let original = ctx.read_test_file()?;
+        let archive_path = ctx.temp_dir.path().join("test_doing_archive.taskpaper");
+
+        history::begin_operation("rotate");
+        handle_rotate(RotateOptions {
+            before: None,
+            bool_op: "pattern".to_string(),
+            case: "smart".to_string(),
+            keep: None,
+            not: false,
+            section: None,
+            search: None,
+            tag: None,
+            val: vec![],
+            exact: false,
+        })?;
+        assert!(archive_path.exists());
+
+        let history = History::open(&Config::load())?;
+        assert_eq!(history.undo_records().len(), 1);
+        assert_eq!(history.undo_records()[0].files.len(), 2);
+
+        handle_undo(1, false)?;
+        assert_eq!(ctx.read_test_file()?, original);
+        assert!(!archive_path.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_undo_refuses_to_revert_later_edits() -> color_eyre::Result<()> {
+        let ctx = TestContext::new()?;
+        let now = Local::now();
+        ctx.create_doing_file_with_entries(vec![
+            TestEntry::new("Keep this").with_timestamp(now - Duration::hours(1)),
+            TestEntry::new("Delete this").with_timestamp(now),
+        ])?;
+        let original = ctx.read_test_file()?;
+
+        history::begin_operation("delete --force");
+        delete_last()?;
+        let edited = format!(
+            "{}\t- 2025-07-28 09:00 | Added by hand\n",
+            ctx.read_test_file()?
+        );
+        ctx.create_test_file(&edited)?;
+
+        let err = handle_undo(1, false).unwrap_err();
+        assert!(err.to_string().contains("delete --force"));
+        assert_eq!(ctx.read_test_file()?, edited);
+        assert_eq!(History::open(&Config::load())?.undo_records().len(), 1);
+
+        handle_undo(1, true)?;
+        assert_eq!(ctx.read_test_file()?, original);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_redo_refuses_after_unrecorded_write() -> color_eyre::Result<()> {
+        let ctx = TestContext::new()?;
+        let now = Local::now();
+        ctx.create_doing_file_with_entries(vec![
+            TestEntry::new("Task 1").with_timestamp(now - Duration::hours(1)),
+            TestEntry::new("Task 2").with_timestamp(now),
+        ])?;
+
+        history::begin_operation("delete");
+        delete_last()?;
+        handle_undo(1, false)?;
+
+        // A process with history turned off changes the file behind the undo
+        let mut config: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&ctx.config_path)?)?;
+        config["history_size"] = serde_json::json!(0);
+        std::fs::write(&ctx.config_path, config.to_string())?;
+        history::begin_operation("delete without history");
+        delete_last()?;
+        let after_write = ctx.read_test_file()?;
+
+        assert!(handle_redo(1, false).is_err());
+        assert_eq!(ctx.read_test_file()?, after_write);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_undo_with_empty_history() -> color_eyre::Result<()> {
+        let _ctx = TestContext::new()?;
+        handle_undo(1, false)?;
+        handle_redo(1, false)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use crate::cli::ViewsAction;
+    use crate::commands::{
+        OnConfigOptions, OnDisplayOptions, OnFilterOptions, handle_on, handle_view, handle_views,
+    };
+    use crate::storage::Config;
+    use crate::storage::config::ViewCommand;
+    use crate::test_utils::utils::TestContext;
+    use std::fs;
+
+    fn on_today(save: Option<&str>) -> color_eyre::Result<()> {
+        handle_on(
+            OnFilterOptions {
+                date_string: "today".to_string(),
+                after: None,
+                before: None,
+                bool_op: "pattern".to_string(),
+                case: "smart".to_string(),
+                from: None,
+                not: false,
+                only_timed: false,
+                sections: vec!["Work".to_string()],
+                search: None,
+                tag: Some("meeting, @call".to_string()),
+                val: vec![],
+                exact: false,
+            },
+            OnDisplayOptions {
+                duration: false,
+                output: None,
+                times: true,
+                tag_order: None,
+                tag_sort: None,
+                totals: true,
+            },
+            OnConfigOptions {
+                config_template: None,
+                save: save.map(str::to_string),
+                template: None,
+                title: None,
+            },
+        )
+    }
+
+    #[test]
+    fn test_save_replay_and_delete_view() {
+        let ctx = TestContext::new().unwrap();
+        ctx.create_test_file("Currently:\n").unwrap();
+
+        on_today(Some("standups")).unwrap();
+
+        let config = Config::load();
+        let view = &config.views["standups"];
+        assert_eq!(view.command, ViewCommand::On);
+        assert_eq!(view.date.as_deref(), Some("today"));
+        assert_eq!(view.sections, vec!["Work"]);
+        assert_eq!(view.tags, vec!["meeting", "@call"]);
+        assert!(view.totals);
+        // The rest of the config is kept
+        assert_eq!(config.doing_file, ctx.doing_file_path);
+
+        handle_view("stand").unwrap();
+        assert!(handle_view("nothing").is_err());
+
+        handle_views(Some(ViewsAction::Delete {
+            name: "standups".to_string(),
+        }))
+        .unwrap();
+        assert!(Config::load().views.is_empty());
+        assert!(
+            handle_views(Some(ViewsAction::Delete {
+                name: "standups".to_string(),
+            }))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_doing_views_read_as_show_views() {
+        let ctx = TestContext::new().unwrap();
+        let mut config = fs::read_to_string(&ctx.config_path).unwrap();
+        config.truncate(config.trim_end().len() - 1);
+        config.push_str(
+            r#", "views": {"meetings": {"section": "Work", "tags": "meeting call", "tags_bool": "OR", "order": "asc", "count": 5}}}"#,
+        );
+        fs::write(&ctx.config_path, config).unwrap();
+
+        let view = &Config::load().views["meetings"];
+        assert_eq!(view.command, ViewCommand::Show);
+        assert_eq!(view.sections, vec!["Work"]);
+        assert_eq!(view.tags, vec!["meeting", "call"]);
+        assert_eq!(view.bool_op.as_deref(), Some("OR"));
+        assert_eq!(view.sort.as_deref(), Some("asc"));
+        assert_eq!(view.count, 5);
+        assert!(view.times);
+    }
+}
@@ -2,7 +2,7 @@ use crate::storage::{Config, parse_taskpaper};
 use chrono::{Local, Timelike};
 
 pub fn handle_today(section: Option<String>) -> color_eyre::Result<()> {
-    let config = Config::load();
+    let config = Config::try_load()?;
     let doing_file_path = config.doing_file_path();
 
     let doing_file = parse_taskpaper(&doing_file_path)?;
@@ -38,7 +38,7 @@ pub fn handle_today(section: Option<String>) -> color_eyre::Result<()> {
             current_section = &entry.section;
         }
 
-        print!("  {} - ", entry.timestamp.format("%H:%M"));
+        print!("  {} - ", entry.started_at.format("%H:%M"));
 
         if entry.is_done() {
             println!("✓ {}", entry.description);
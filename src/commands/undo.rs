@@ -0,0 +1,54 @@
+use crate::storage::{Config, FileLock, History};
+use color_eyre::Result;
+
+pub fn handle_undo(count: usize, force: bool) -> Result<()> {
+    let config = Config::try_load()?;
+    let doing_file_path = config.doing_file_path();
+    let _lock = FileLock::acquire(&doing_file_path)?;
+    let mut history = History::open(&config)?;
+
+    let mut undone = 0;
+    for _ in 0..count.max(1) {
+        match history.undo(force)? {
+            Some(record) => {
+                println!(
+                    "Undid: {} ({})",
+                    record.command,
+                    record.timestamp.format("%Y-%m-%d %H:%M")
+                );
+                undone += 1;
+            }
+            None => break,
+        }
+    }
+
+    if undone == 0 {
+        println!("Nothing to undo");
+    }
+
+    Ok(())
+}
+
+pub fn handle_redo(count: usize, force: bool) -> Result<()> {
+    let config = Config::try_load()?;
+    let doing_file_path = config.doing_file_path();
+    let _lock = FileLock::acquire(&doing_file_path)?;
+    let mut history = History::open(&config)?;
+
+    let mut redone = 0;
+    for _ in 0..count.max(1) {
+        match history.redo(force)? {
+            Some(record) => {
+                println!("Redid: {}", record.command);
+                redone += 1;
+            }
+            None => break,
+        }
+    }
+
+    if redone == 0 {
+        println!("Nothing to redo");
+    }
+
+    Ok(())
+}
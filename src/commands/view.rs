@@ -0,0 +1,311 @@
+use crate::cli::ViewsAction;
+use crate::commands::config::{remove_setting, set_setting, target_file};
+use crate::commands::{
+    GrepActionOptions, GrepConfigOptions, GrepDisplayOptions, GrepFilterOptions, OnConfigOptions,
+    OnDisplayOptions, OnFilterOptions, ShowConfigOptions, ShowDisplayOptions, ShowFilterOptions,
+    ShowUIOptions, SinceConfigOptions, SinceDisplayOptions, SinceFilterOptions, YesterdayOptions,
+    handle_grep, handle_on, handle_show, handle_since, handle_yesterday,
+};
+use crate::storage::Config;
+use crate::storage::config::{View, ViewCommand};
+use color_eyre::Result;
+use color_eyre::eyre::eyre;
+
+/// Show the entries of a saved view
+pub fn handle_view(name: &str) -> Result<()> {
+    let config = Config::try_load()?;
+    let (_, view) = find_view(&config, name)?;
+    replay(view.clone())
+}
+
+pub fn handle_views(action: Option<ViewsAction>) -> Result<()> {
+    match action.unwrap_or(ViewsAction::List) {
+        ViewsAction::List => {
+            let config = Config::try_load()?;
+            if config.views.is_empty() {
+                println!("No saved views");
+            }
+            let width = config.views.keys().map(|name| name.len()).max();
+            for (name, view) in &config.views {
+                println!(
+                    "{name:<width$}  {}",
+                    describe(view),
+                    width = width.unwrap_or(0)
+                );
+            }
+        }
+        ViewsAction::Show { name } => {
+            let config = Config::try_load()?;
+            let (name, view) = find_view(&config, &name)?;
+            println!("# {name}");
+            print!("{}", toml::to_string(view)?);
+        }
+        ViewsAction::Delete { name } => {
+            // Local files can define views too; delete it where it takes effect
+            let key = format!("views.{name}");
+            let mut deleted = false;
+            for file in Config::files().iter().rev() {
+                if remove_setting(file, &key)? {
+                    println!("Deleted view '{name}' from {}", file.path.display());
+                    deleted = true;
+                    break;
+                }
+            }
+            if !deleted {
+                return Err(eyre!("no view named '{name}'"));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Save `view` under `name` in the global config file, replacing any view of that name
+pub(crate) fn save_view(name: &str, view: &View) -> Result<()> {
+    if name.trim().is_empty() || name.contains('.') {
+        return Err(eyre!("invalid view name '{name}'"));
+    }
+
+    let file = target_file(false)?;
+    let key = format!("views.{name}");
+    remove_setting(&file, &key)?;
+    set_setting(&file, &key, serde_json::to_value(view)?)?;
+
+    // On stderr, so saving a view doesn't get in the way of `--output json` and friends
+    eprintln!("Saved view '{name}' to {}", file.path.display());
+    Ok(())
+}
+
+/// Look a view up by name, or by an unambiguous prefix of its name
+fn find_view<'a>(config: &'a Config, name: &str) -> Result<(&'a String, &'a View)> {
+    if let Some(found) = config.views.get_key_value(name) {
+        return Ok(found);
+    }
+
+    let prefix = name.to_lowercase();
+    let matches: Vec<(&String, &View)> = config
+        .views
+        .iter()
+        .filter(|(view_name, _)| view_name.to_lowercase().starts_with(&prefix))
+        .collect();
+
+    match matches.as_slice() {
+        [found] => Ok(*found),
+        [] => Err(eyre!("no view named '{name}'")),
+        _ => {
+            let names: Vec<&str> = matches.iter().map(|(name, _)| name.as_str()).collect();
+            Err(eyre!(
+                "'{name}' matches several views: {}",
+                names.join(", ")
+            ))
+        }
+    }
+}
+
+/// Run the command the view was saved from with the saved options
+fn replay(view: View) -> Result<()> {
+    let tag = (!view.tags.is_empty()).then(|| view.tags.join(","));
+    let bool_op = view.bool_op.unwrap_or_else(|| "pattern".to_string());
+    let case = view.case.unwrap_or_else(|| "smart".to_string());
+    let (tag_order, tag_sort) = (view.tag_order, view.tag_sort);
+    let date = view.date.unwrap_or_else(|| "today".to_string());
+
+    match view.command {
+        ViewCommand::Show => handle_show(
+            ShowFilterOptions {
+                args: vec![],
+                age: view.age.unwrap_or_else(|| "newest".to_string()),
+                after: view.after,
+                before: view.before,
+                bool_op,
+                case,
+                from: view.from,
+                not: view.not,
+                only_timed: view.only_timed,
+                sections: view.sections,
+                search: view.search,
+                tag,
+                val: view.val,
+                exact: view.exact,
+            },
+            ShowDisplayOptions {
+                count: view.count,
+                duration: view.duration,
+                hilite: view.hilite,
+                output: view.output,
+                sort: view.sort.unwrap_or_else(|| "desc".to_string()),
+                times: view.times,
+                tag_order,
+                tag_sort,
+                totals: view.totals,
+            },
+            ShowUIOptions {
+                interactive: false,
+                menu: false,
+                editor: false,
+            },
+            ShowConfigOptions {
+                config_template: view.config_template,
+                save: None,
+                template: view.template,
+                title: view.title,
+            },
+        ),
+        ViewCommand::Grep => handle_grep(
+            GrepFilterOptions {
+                pattern: view.search.unwrap_or_default(),
+                after: view.after,
+                before: view.before,
+                bool_op,
+                case,
+                from: view.from,
+                not: view.not,
+                only_timed: view.only_timed,
+                sections: view.sections,
+                tag,
+                val: view.val,
+                exact: view.exact,
+            },
+            GrepDisplayOptions {
+                duration: view.duration,
+                hilite: view.hilite,
+                output: view.output,
+                times: view.times,
+                tag_order,
+                tag_sort,
+                totals: view.totals,
+            },
+            GrepActionOptions {
+                delete: false,
+                interactive: false,
+                editor: false,
+            },
+            GrepConfigOptions {
+                config_template: view.config_template,
+                save: None,
+                template: view.template,
+                title: view.title,
+            },
+        ),
+        ViewCommand::On => handle_on(
+            OnFilterOptions {
+                date_string: date,
+                after: view.after,
+                before: view.before,
+                bool_op,
+                case,
+                from: view.from,
+                not: view.not,
+                only_timed: view.only_timed,
+                sections: view.sections,
+                search: view.search,
+                tag,
+                val: view.val,
+                exact: view.exact,
+            },
+            OnDisplayOptions {
+                duration: view.duration,
+                output: view.output,
+                times: view.times,
+                tag_order,
+                tag_sort,
+                totals: view.totals,
+            },
+            OnConfigOptions {
+                config_template: view.config_template,
+                save: None,
+                template: view.template,
+                title: view.title,
+            },
+        ),
+        ViewCommand::Since => handle_since(
+            SinceFilterOptions {
+                date_string: date,
+                bool_op,
+                case,
+                not: view.not,
+                only_timed: view.only_timed,
+                sections: view.sections,
+                search: view.search,
+                tag,
+                val: view.val,
+                exact: view.exact,
+            },
+            SinceDisplayOptions {
+                duration: view.duration,
+                output: view.output,
+                times: view.times,
+                tag_order,
+                tag_sort,
+                totals: view.totals,
+            },
+            SinceConfigOptions {
+                config_template: view.config_template,
+                save: None,
+                template: view.template,
+                title: view.title,
+            },
+        ),
+        ViewCommand::Yesterday => handle_yesterday(YesterdayOptions {
+            after: view.after,
+            before: view.before,
+            config_template: view.config_template,
+            duration: view.duration,
+            from: view.from,
+            output: view.output,
+            only_timed: view.only_timed,
+            sections: view.sections,
+            save: None,
+            times: view.times,
+            tag_order,
+            tag_sort,
+            template: view.template,
+            title: view.title,
+            totals: view.totals,
+        }),
+    }
+}
+
+/// A one-line summary of a view, written like the command line that saved it
+fn describe(view: &View) -> String {
+    let command = match view.command {
+        ViewCommand::Show => "show",
+        ViewCommand::Grep => "grep",
+        ViewCommand::On => "on",
+        ViewCommand::Since => "since",
+        ViewCommand::Yesterday => "yesterday",
+    };
+    let mut parts = vec![command.to_string()];
+
+    match view.command {
+        ViewCommand::Grep => parts.extend(view.search.as_ref().map(|s| format!("{s:?}"))),
+        ViewCommand::On | ViewCommand::Since => {
+            parts.extend(view.date.as_ref().map(|d| format!("{d:?}")))
+        }
+        _ => parts.extend(view.search.as_ref().map(|s| format!("--search {s:?}"))),
+    }
+    parts.extend(view.sections.iter().map(|section| format!("-s {section}")));
+    parts.extend(
+        view.tags
+            .iter()
+            .map(|tag| format!("@{}", tag.trim_start_matches('@'))),
+    );
+    parts.extend(view.val.iter().map(|query| format!("--val {query:?}")));
+    parts.extend(view.from.as_ref().map(|from| format!("--from {from:?}")));
+    parts.extend(
+        view.after
+            .as_ref()
+            .map(|after| format!("--after {after:?}")),
+    );
+    parts.extend(
+        view.before
+            .as_ref()
+            .map(|before| format!("--before {before:?}")),
+    );
+    if view.count > 0 {
+        parts.push(format!("--count {}", view.count));
+    }
+    parts.extend(view.output.as_ref().map(|output| format!("-o {output}")));
+
+    parts.join(" ")
+}
@@ -1,29 +1,41 @@
+use crate::commands::view;
 use crate::display::{DisplayOptions, OutputFormat, SortOrder, TagSort, display_entries};
 use crate::filtering::{FilterOptions, filter_entries, parse_date_filter, parse_date_range};
+use crate::storage::config::{View, ViewCommand};
 use crate::storage::{Config, parse_taskpaper};
+use crate::template;
 use chrono::{Duration, Local};
 
 #[derive(Debug)]
 pub struct YesterdayOptions {
     pub after: Option<String>,
     pub before: Option<String>,
-    pub _config_template: Option<String>,
+    pub config_template: Option<String>,
     pub duration: bool,
     pub from: Option<String>,
     pub output: Option<String>,
     pub only_timed: bool,
     pub sections: Vec<String>,
-    pub _save: Option<String>,
+    pub save: Option<String>,
     pub times: bool,
-    pub tag_order: String,
-    pub tag_sort: String,
-    pub _template: Option<String>,
-    pub _title: Option<String>,
+    pub tag_order: Option<String>,
+    pub tag_sort: Option<String>,
+    pub template: Option<String>,
+    pub title: Option<String>,
     pub totals: bool,
 }
 
 pub fn handle_yesterday(opts: YesterdayOptions) -> color_eyre::Result<()> {
-    let config = Config::load();
+    if let Some(name) = &opts.save {
+        view::save_view(name, &saved_view(&opts))?;
+    }
+
+    let config = Config::try_load()?;
+    let template = template::resolve(
+        opts.template.as_deref(),
+        opts.config_template.as_deref(),
+        &config,
+    )?;
     let doing_file_path = config.doing_file_path();
     let doing_file = parse_taskpaper(&doing_file_path)?;
 
@@ -121,17 +133,20 @@ pub fn handle_yesterday(opts: YesterdayOptions) -> color_eyre::Result<()> {
             Some("html") => OutputFormat::Html,
             Some("taskpaper") => OutputFormat::TaskPaper,
             Some("timeline") => OutputFormat::Timeline,
+            Some("ics") => OutputFormat::Ics,
             _ => OutputFormat::Default,
         },
-        tag_sort: match opts.tag_sort.as_str() {
+        tag_sort: opts.tag_sort.as_deref().map(|sort| match sort {
             "time" => TagSort::Time,
             _ => TagSort::Name,
-        },
-        tag_order: match opts.tag_order.as_str() {
+        }),
+        tag_order: opts.tag_order.as_deref().map(|order| match order {
             "desc" => SortOrder::Desc,
             _ => SortOrder::Asc,
-        },
+        }),
         section_filter: filter_opts.sections.clone(),
+        template,
+        title: opts.title,
     };
 
     // Display entries
@@ -139,3 +154,25 @@ pub fn handle_yesterday(opts: YesterdayOptions) -> color_eyre::Result<()> {
 
     Ok(())
 }
+
+/// The options of this listing as a view for `--save`
+fn saved_view(opts: &YesterdayOptions) -> View {
+    View {
+        command: ViewCommand::Yesterday,
+        sections: opts.sections.clone(),
+        only_timed: opts.only_timed,
+        after: opts.after.clone(),
+        before: opts.before.clone(),
+        from: opts.from.clone(),
+        output: opts.output.clone(),
+        tag_order: opts.tag_order.clone(),
+        tag_sort: opts.tag_sort.clone(),
+        duration: opts.duration,
+        times: opts.times,
+        totals: opts.totals,
+        template: opts.template.clone(),
+        config_template: opts.config_template.clone(),
+        title: opts.title.clone(),
+        ..View::default()
+    }
+}
@@ -1,6 +1,10 @@
 use crate::models::Entry;
+use crate::report::{Report, TOP_TAGS};
+use crate::template::Template;
+use crate::timesheet::Timesheet;
+use crate::totals::{Total, Totals};
 use chrono::{DateTime, Local};
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 pub struct DisplayOptions {
     pub times: bool,
@@ -9,9 +13,15 @@ pub struct DisplayOptions {
     pub hilite: bool,
     pub search_query: Option<String>,
     pub output_format: OutputFormat,
-    pub tag_sort: TagSort,
-    pub tag_order: SortOrder,
+    /// How tag totals are sorted, and entries' tags when given; entries keep
+    /// their tags in the order they were written otherwise
+    pub tag_sort: Option<TagSort>,
+    pub tag_order: Option<SortOrder>,
     pub section_filter: Vec<String>,
+    /// Replaces the default layout of each entry
+    pub template: Option<Template>,
+    /// Heading printed above the entries
+    pub title: Option<String>,
 }
 
 impl Default for DisplayOptions {
@@ -23,9 +33,11 @@ impl Default for DisplayOptions {
             hilite: false,
             search_query: None,
             output_format: OutputFormat::Default,
-            tag_sort: TagSort::Name,
-            tag_order: SortOrder::Asc,
+            tag_sort: None,
+            tag_order: None,
             section_filter: vec![],
+            template: None,
+            title: None,
         }
     }
 }
@@ -39,6 +51,7 @@ pub enum OutputFormat {
     Html,
     TaskPaper,
     Timeline,
+    Ics,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -57,20 +70,50 @@ pub fn display_entries(
     entries: &[(String, Entry)],
     options: &DisplayOptions,
 ) -> color_eyre::Result<()> {
+    let (tag_sort, tag_order) = (
+        options.tag_sort.clone().unwrap_or(TagSort::Name),
+        options.tag_order.clone().unwrap_or(SortOrder::Asc),
+    );
+    let totals = options
+        .totals
+        .then(|| Totals::new(entries, &tag_sort, &tag_order));
+    let totals = totals.as_ref();
+
+    let sorted: Vec<(String, Entry)>;
+    let entries = if options.tag_sort.is_some() || options.tag_order.is_some() {
+        sorted = entries
+            .iter()
+            .map(|(section, entry)| {
+                let mut entry = entry.clone();
+                sort_tags(&mut entry.tags, &tag_order);
+                (section.clone(), entry)
+            })
+            .collect();
+        &sorted
+    } else {
+        entries
+    };
+
     match options.output_format {
-        OutputFormat::Default => display_default(entries, options),
-        OutputFormat::Json => display_json(entries),
-        OutputFormat::Csv => display_csv(entries),
-        OutputFormat::Markdown => display_markdown(entries, options),
-        OutputFormat::Html => display_html(entries, options),
-        OutputFormat::TaskPaper => display_taskpaper(entries),
-        OutputFormat::Timeline => display_timeline(entries, options),
+        OutputFormat::Default => display_default(entries, options, totals),
+        OutputFormat::Json => display_json(entries, totals),
+        OutputFormat::Csv => display_csv(entries, totals),
+        OutputFormat::Markdown => display_markdown(entries, options, totals),
+        OutputFormat::Html => display_html(entries, options, totals),
+        OutputFormat::TaskPaper => display_taskpaper(entries, totals),
+        OutputFormat::Timeline => display_timeline(entries, options, totals),
+        // A calendar has no place for totals
+        OutputFormat::Ics => {
+            print!("{}", crate::ics::write_calendar(entries, Local::now()));
+            Ok(())
+        }
     }
 }
 
 fn display_default(
     entries: &[(String, Entry)],
     options: &DisplayOptions,
+    totals: Option<&Totals>,
 ) -> color_eyre::Result<()> {
     if entries.is_empty() {
         println!("No entries found");
@@ -78,29 +121,32 @@ fn display_default(
     }
 
     let now = Local::now();
-    let mut total_duration = chrono::Duration::zero();
+    let color = crate::template::use_color();
 
     // Calculate maximum width for description
     let max_desc_width = 50;
 
+    if let Some(title) = &options.title {
+        println!("{title}\n");
+    }
+
     for (section, entry) in entries.iter() {
         // Format date
-        let date_str = format_date(&entry.timestamp, &now);
-        let time_str = entry.timestamp.format("%H:%M").to_string();
+        let date_str = format_date(&entry.started_at, &now);
+        let time_str = entry.started_at.format("%H:%M").to_string();
 
         // Calculate duration if done
-        let (duration_str, duration) = if options.times && entry.is_done() {
-            calculate_duration(entry)
+        let duration_str = if options.times && entry.is_done() {
+            calculate_duration(entry).0
         } else if options.duration && !entry.is_done() {
-            let duration = now - entry.timestamp;
-            let duration_str = format!(" ({})", format_duration(&duration));
-            (duration_str, Some(duration))
+            format!(" ({})", format_duration(&(now - entry.started_at)))
         } else {
-            (String::new(), None)
+            String::new()
         };
 
-        if let Some(d) = duration {
-            total_duration += d;
+        if let Some(template) = &options.template {
+            println!("{}", template.render(section, entry, color));
+            continue;
         }
 
         // Build section string
@@ -112,15 +158,17 @@ fn display_default(
 
         // Build description with tags
         let mut desc = entry.description.clone();
-        let tags = format_tags(&entry.tags, options);
+        let tags = format_tags(&entry.tags);
         if !tags.is_empty() {
             desc.push(' ');
             desc.push_str(&tags);
         }
 
         // Highlight search matches if requested
-        if options.hilite && options.search_query.is_some() {
-            desc = highlight_matches(&desc, options.search_query.as_ref().unwrap());
+        if options.hilite
+            && let Some(query) = &options.search_query
+        {
+            desc = highlight_matches(&desc, query);
         }
 
         // Truncate description if too long
@@ -148,43 +196,103 @@ fn display_default(
         }
     }
 
-    // Show totals if requested
-    if options.totals && !total_duration.is_zero() {
-        println!(
-            "\n{:>10} {:>5} ═══════════════════════════════════════════════════════════",
-            "", ""
-        );
-        println!(
-            "{:>10} {:>5} Total: {}",
-            "",
-            "",
-            format_duration(&total_duration)
-        );
+    if let Some(totals) = totals {
+        print_totals(totals);
     }
 
     Ok(())
 }
 
-fn display_json(entries: &[(String, Entry)]) -> color_eyre::Result<()> {
+/// The plain text totals report shared by the default and timeline formats
+fn print_totals(totals: &Totals) {
+    if totals.is_empty() {
+        return;
+    }
+
+    let width = totals
+        .groups()
+        .iter()
+        .flat_map(|(_, group)| group.iter().map(|total| total.name.chars().count()))
+        .max()
+        .unwrap_or(0);
+
+    println!(
+        "\n{:>10} {:>5} ═══════════════════════════════════════════════════════════",
+        "", ""
+    );
+    for (heading, group) in totals.groups() {
+        if group.is_empty() {
+            continue;
+        }
+        println!("{:>10} {:>5} {heading} totals", "", "");
+        for total in group {
+            println!(
+                "{:>10} {:>5}   {:<width$}  {:>8}  {:>3}%",
+                "",
+                "",
+                total.name,
+                format_duration(&total.duration),
+                totals.percent(total.duration)
+            );
+        }
+    }
+    println!(
+        "{:>10} {:>5} Total: {}",
+        "",
+        "",
+        format_duration(&totals.total)
+    );
+}
+
+fn display_json(entries: &[(String, Entry)], totals: Option<&Totals>) -> color_eyre::Result<()> {
     let json_entries: Vec<serde_json::Value> = entries
         .iter()
-        .map(|(section, entry)| {
-            serde_json::json!({
-                "section": section,
-                "timestamp": entry.timestamp.to_rfc3339(),
-                "description": entry.description,
-                "tags": entry.tags,
-                "note": entry.note,
-                "uuid": entry.uuid.to_string(),
-            })
-        })
+        .map(|(section, entry)| entry_json(section, entry))
         .collect();
 
-    println!("{}", serde_json::to_string_pretty(&json_entries)?);
+    // With totals the entries move under a key, next to the totals
+    let output = match totals {
+        Some(totals) => serde_json::json!({
+            "entries": json_entries,
+            "totals": totals.to_json(),
+        }),
+        None => serde_json::Value::Array(json_entries),
+    };
+
+    println!("{}", serde_json::to_string_pretty(&output)?);
     Ok(())
 }
 
-fn display_csv(entries: &[(String, Entry)]) -> color_eyre::Result<()> {
+fn entry_json(section: &str, entry: &Entry) -> serde_json::Value {
+    serde_json::json!({
+        "section": section,
+        "timestamp": entry.started_at.to_rfc3339(),
+        "description": entry.description,
+        "tags": entry.tags,
+        "note": entry.note,
+        "uuid": entry.uuid.to_string(),
+    })
+}
+
+fn display_csv(entries: &[(String, Entry)], totals: Option<&Totals>) -> color_eyre::Result<()> {
+    // With totals the output is the totals table alone, so it stays one CSV
+    if let Some(totals) = totals {
+        println!("group,name,seconds,percent");
+        for (heading, group) in totals.groups() {
+            for total in group {
+                println!(
+                    "{},{},{},{}",
+                    heading.to_lowercase(),
+                    escape_csv(&total.name),
+                    total.duration.num_seconds(),
+                    totals.percent(total.duration)
+                );
+            }
+        }
+        println!("total,,{},100", totals.total.num_seconds());
+        return Ok(());
+    }
+
     // CSV header
     println!("timestamp,description,section,tags,note,uuid");
 
@@ -206,7 +314,7 @@ fn display_csv(entries: &[(String, Entry)]) -> color_eyre::Result<()> {
 
         println!(
             "{},{},{},{},{},{}",
-            entry.timestamp.to_rfc3339(),
+            entry.started_at.to_rfc3339(),
             escape_csv(&entry.description),
             escape_csv(section),
             escape_csv(&tags_str),
@@ -221,13 +329,17 @@ fn display_csv(entries: &[(String, Entry)]) -> color_eyre::Result<()> {
 fn display_markdown(
     entries: &[(String, Entry)],
     options: &DisplayOptions,
+    totals: Option<&Totals>,
 ) -> color_eyre::Result<()> {
-    println!("# Doing Entries\n");
+    println!(
+        "# {}\n",
+        options.title.as_deref().unwrap_or("Doing Entries")
+    );
 
     let mut current_date = None;
 
     for (section, entry) in entries {
-        let entry_date = entry.timestamp.date_naive();
+        let entry_date = entry.started_at.date_naive();
 
         // Add date header if changed
         if current_date != Some(entry_date) {
@@ -238,12 +350,12 @@ fn display_markdown(
         // Time and description
         print!(
             "- **{}** - {}",
-            entry.timestamp.format("%H:%M"),
+            entry.started_at.format("%H:%M"),
             entry.description
         );
 
         // Tags
-        let tags = format_tags(&entry.tags, options);
+        let tags = format_tags(&entry.tags);
         if !tags.is_empty() {
             print!(" {tags}");
         }
@@ -263,15 +375,65 @@ fn display_markdown(
         }
     }
 
+    if let Some(totals) = totals.filter(|totals| !totals.is_empty()) {
+        println!("\n## Totals");
+        for (heading, group) in totals.groups() {
+            print_markdown_totals(heading, group, totals);
+        }
+        println!("\n**Total:** {}", format_duration(&totals.total));
+    }
+
+    Ok(())
+}
+
+fn print_markdown_totals(heading: &str, group: &[Total], totals: &Totals) {
+    if group.is_empty() {
+        return;
+    }
+    println!("\n| {heading} | Time | % |\n| --- | ---: | ---: |");
+    for total in group {
+        println!(
+            "| {} | {} | {}% |",
+            total.name.replace('|', "\\|"),
+            format_duration(&total.duration),
+            totals.percent(total.duration)
+        );
+    }
+}
+
+fn display_html(
+    entries: &[(String, Entry)],
+    options: &DisplayOptions,
+    totals: Option<&Totals>,
+) -> color_eyre::Result<()> {
+    print_html_head(options.title.as_deref().unwrap_or("Doing Entries"));
+
+    for (section, entry) in entries {
+        print_html_entry(section, entry);
+    }
+
+    if let Some(totals) = totals.filter(|totals| !totals.is_empty()) {
+        println!("    <h2>Totals</h2>");
+        for (heading, group) in totals.groups() {
+            print_html_totals(heading, group, totals);
+        }
+        println!(
+            r#"    <p class="total">Total: {}</p>"#,
+            format_duration(&totals.total)
+        );
+    }
+
+    println!("</body>\n</html>");
     Ok(())
 }
 
-fn display_html(entries: &[(String, Entry)], options: &DisplayOptions) -> color_eyre::Result<()> {
+fn print_html_head(title: &str) {
+    let title = html_escape(title);
     println!(
         r#"<!DOCTYPE html>
 <html>
 <head>
-    <title>Doing Entries</title>
+    <title>{title}</title>
     <style>
         body {{ font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; }}
         .entry {{ margin: 10px 0; padding: 10px; border-left: 3px solid #007acc; }}
@@ -282,44 +444,63 @@ fn display_html(entries: &[(String, Entry)], options: &DisplayOptions) -> color_
     </style>
 </head>
 <body>
-    <h1>Doing Entries</h1>"#
+    <h1>{title}</h1>"#
     );
+}
 
-    for (section, entry) in entries {
-        println!(r#"    <div class="entry">"#);
+fn print_html_entry(section: &str, entry: &Entry) {
+    println!(r#"    <div class="entry">"#);
+    print!(
+        r#"        <span class="timestamp">{}</span> - {}"#,
+        entry.started_at.format("%Y-%m-%d %H:%M"),
+        html_escape(&entry.description)
+    );
+
+    let tags = format_tags(&entry.tags);
+    if !tags.is_empty() {
+        print!(r#" <span class="tags">{}</span>"#, html_escape(&tags));
+    }
+
+    if section != "Currently" {
         print!(
-            r#"        <span class="timestamp">{}</span> - {}"#,
-            entry.timestamp.format("%Y-%m-%d %H:%M"),
-            html_escape(&entry.description)
+            r#" <span class="section">[{}]</span>"#,
+            html_escape(section)
         );
+    }
 
-        let tags = format_tags(&entry.tags, options);
-        if !tags.is_empty() {
-            print!(r#" <span class="tags">{}</span>"#, html_escape(&tags));
-        }
-
-        if section != "Currently" {
-            print!(
-                r#" <span class="section">[{}]</span>"#,
-                html_escape(section)
-            );
-        }
+    if let Some(note) = &entry.note {
+        println!(r#"        <div class="note">{}</div>"#, html_escape(note));
+    }
 
-        if let Some(note) = &entry.note {
-            println!(r#"        <div class="note">{}</div>"#, html_escape(note));
-        }
+    println!(r#"    </div>"#);
+}
 
-        println!(r#"    </div>"#);
+fn print_html_totals(heading: &str, group: &[Total], totals: &Totals) {
+    if group.is_empty() {
+        return;
     }
-
-    println!("</body>\n</html>");
-    Ok(())
+    println!(
+        r#"    <table class="totals">
+        <tr><th>{heading}</th><th>Time</th><th>%</th></tr>"#
+    );
+    for total in group {
+        println!(
+            "        <tr><td>{}</td><td>{}</td><td>{}%</td></tr>",
+            html_escape(&total.name),
+            format_duration(&total.duration),
+            totals.percent(total.duration)
+        );
+    }
+    println!("    </table>");
 }
 
-fn display_taskpaper(entries: &[(String, Entry)]) -> color_eyre::Result<()> {
-    let mut sections: HashMap<String, Vec<&Entry>> = HashMap::new();
+fn display_taskpaper(
+    entries: &[(String, Entry)],
+    totals: Option<&Totals>,
+) -> color_eyre::Result<()> {
+    let mut sections: IndexMap<String, Vec<&Entry>> = IndexMap::new();
 
-    // Group by section
+    // Group by section, keeping the order sections first appear in
     for (section, entry) in entries {
         sections.entry(section.clone()).or_default().push(entry);
     }
@@ -330,7 +511,7 @@ fn display_taskpaper(entries: &[(String, Entry)]) -> color_eyre::Result<()> {
         for entry in entries {
             print!(
                 "- {} | {}",
-                entry.timestamp.format("%Y-%m-%d %H:%M"),
+                entry.started_at.format("%Y-%m-%d %H:%M"),
                 entry.description
             );
 
@@ -354,12 +535,29 @@ fn display_taskpaper(entries: &[(String, Entry)]) -> color_eyre::Result<()> {
         println!();
     }
 
+    // Plain lines rather than tasks, so the totals don't read back as entries
+    if let Some(totals) = totals.filter(|totals| !totals.is_empty()) {
+        println!("Totals:");
+        for (heading, group) in totals.groups() {
+            for total in group {
+                println!(
+                    "  {heading} {}: {} ({}%)",
+                    total.name,
+                    format_duration(&total.duration),
+                    totals.percent(total.duration)
+                );
+            }
+        }
+        println!("  Total: {}", format_duration(&totals.total));
+    }
+
     Ok(())
 }
 
 fn display_timeline(
     entries: &[(String, Entry)],
     options: &DisplayOptions,
+    totals: Option<&Totals>,
 ) -> color_eyre::Result<()> {
     if entries.is_empty() {
         return Ok(());
@@ -368,7 +566,7 @@ fn display_timeline(
     let mut current_date = None;
 
     for (section, entry) in entries {
-        let entry_date = entry.timestamp.date_naive();
+        let entry_date = entry.started_at.date_naive();
 
         // Add date separator if changed
         if current_date != Some(entry_date) {
@@ -380,7 +578,7 @@ fn display_timeline(
         }
 
         // Time
-        print!("{} ", entry.timestamp.format("%H:%M"));
+        print!("{} ", entry.started_at.format("%H:%M"));
 
         // Duration if done
         if options.times && entry.is_done() {
@@ -392,7 +590,7 @@ fn display_timeline(
 
         // Description and tags
         print!("│ {}", entry.description);
-        let tags = format_tags(&entry.tags, options);
+        let tags = format_tags(&entry.tags);
         if !tags.is_empty() {
             print!(" {tags}");
         }
@@ -412,10 +610,267 @@ fn display_timeline(
         }
     }
 
+    if let Some(totals) = totals {
+        print_totals(totals);
+    }
+
     Ok(())
 }
 
-fn format_date(timestamp: &DateTime<Local>, now: &DateTime<Local>) -> String {
+/// Print a period report as Markdown, HTML or JSON
+pub fn display_report(report: &Report, format: &OutputFormat) -> color_eyre::Result<()> {
+    match format {
+        OutputFormat::Markdown => display_report_markdown(report),
+        OutputFormat::Html => display_report_html(report),
+        OutputFormat::Json => display_report_json(report)?,
+        _ => {
+            return Err(color_eyre::eyre::eyre!(
+                "reports can be written as markdown, html or json"
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn report_top_tags(report: &Report) -> &[Total] {
+    &report.totals.tags[..report.totals.tags.len().min(TOP_TAGS)]
+}
+
+fn display_report_markdown(report: &Report) {
+    println!("# {}", report.title);
+    println!(
+        "\n{} to {} · {} tracked",
+        report.start.format("%Y-%m-%d"),
+        report.end.format("%Y-%m-%d"),
+        format_duration(&report.totals.total)
+    );
+
+    for day in &report.days {
+        println!(
+            "\n## {} ({})\n",
+            day.date.format("%A, %B %d, %Y"),
+            format_duration(&day.tracked)
+        );
+        for (section, entry) in &day.entries {
+            print!(
+                "- **{}** - {}",
+                entry.started_at.format("%H:%M"),
+                entry.title()
+            );
+            if section != "Currently" {
+                print!(" _[{section}]_");
+            }
+            println!("{}", calculate_duration(entry).0);
+        }
+    }
+
+    if !report.totals.is_empty() {
+        println!("\n## Top tags");
+        print_markdown_totals("Tag", report_top_tags(report), &report.totals);
+        println!("\n## Sections");
+        print_markdown_totals("Section", &report.totals.sections, &report.totals);
+    }
+
+    if !report.open.is_empty() {
+        println!("\n## Open items\n");
+        for (section, entry) in &report.open {
+            println!(
+                "- {} - {} _[{section}]_",
+                entry.started_at.format("%Y-%m-%d %H:%M"),
+                entry.title()
+            );
+        }
+    }
+}
+
+fn display_report_html(report: &Report) {
+    print_html_head(&report.title);
+    println!(
+        "    <p>{} to {} &middot; {} tracked</p>",
+        report.start.format("%Y-%m-%d"),
+        report.end.format("%Y-%m-%d"),
+        format_duration(&report.totals.total)
+    );
+
+    for day in &report.days {
+        println!(
+            "    <h2>{} ({})</h2>",
+            day.date.format("%A, %B %d, %Y"),
+            format_duration(&day.tracked)
+        );
+        for (section, entry) in &day.entries {
+            print_html_entry(section, entry);
+        }
+    }
+
+    if !report.totals.is_empty() {
+        println!("    <h2>Top tags</h2>");
+        print_html_totals("Tag", report_top_tags(report), &report.totals);
+        println!("    <h2>Sections</h2>");
+        print_html_totals("Section", &report.totals.sections, &report.totals);
+    }
+
+    if !report.open.is_empty() {
+        println!("    <h2>Open items</h2>");
+        for (section, entry) in &report.open {
+            print_html_entry(section, entry);
+        }
+    }
+
+    println!("</body>\n</html>");
+}
+
+fn display_report_json(report: &Report) -> color_eyre::Result<()> {
+    let days: Vec<serde_json::Value> = report
+        .days
+        .iter()
+        .map(|day| {
+            serde_json::json!({
+                "date": day.date.format("%Y-%m-%d").to_string(),
+                "seconds": day.tracked.num_seconds(),
+                "entries": day
+                    .entries
+                    .iter()
+                    .map(|(section, entry)| entry_json(section, entry))
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+
+    let mut totals = report.totals.to_json();
+    if let Some(tags) = totals.get_mut("tags").and_then(|tags| tags.as_array_mut()) {
+        tags.truncate(TOP_TAGS);
+    }
+
+    let output = serde_json::json!({
+        "title": report.title,
+        "start": report.start.to_rfc3339(),
+        "end": report.end.to_rfc3339(),
+        "days": days,
+        "totals": totals,
+        "open": report
+            .open
+            .iter()
+            .map(|(section, entry)| entry_json(section, entry))
+            .collect::<Vec<_>>(),
+    });
+
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
+
+/// Print a timesheet as CSV or HTML
+pub fn display_timesheet(sheet: &Timesheet, format: &OutputFormat) -> color_eyre::Result<()> {
+    match format {
+        OutputFormat::Csv => display_timesheet_csv(sheet),
+        OutputFormat::Html => display_timesheet_html(sheet),
+        _ => {
+            return Err(color_eyre::eyre::eyre!(
+                "timesheets can be written as csv or html"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// One table: a row per entry, then a total row for each client and one for
+/// the whole sheet, with "Total" in the description column
+fn display_timesheet_csv(sheet: &Timesheet) {
+    println!("client,date,start,end,section,description,hours,billed_hours,rate,amount");
+    for client in &sheet.clients {
+        for line in &client.lines {
+            println!(
+                "{},{},{},{},{},{},{:.2},{:.2},{:.2},{:.2}",
+                escape_csv(&client.name),
+                line.entry.started_at.format("%Y-%m-%d"),
+                line.entry.started_at.format("%H:%M"),
+                line.entry
+                    .finished_at
+                    .unwrap_or(line.entry.started_at)
+                    .format("%H:%M"),
+                escape_csv(&line.section),
+                escape_csv(&line.entry.description),
+                line.worked_hours(),
+                line.billed_hours(),
+                client.rate,
+                client.line_amount(line)
+            );
+        }
+        println!(
+            "{},,,,,Total,{:.2},{:.2},{:.2},{:.2}",
+            escape_csv(&client.name),
+            client.worked_hours(),
+            client.billed_hours(),
+            client.rate,
+            client.amount()
+        );
+    }
+    println!(
+        ",,,,,Total,{:.2},{:.2},,{:.2}",
+        sheet.worked_hours(),
+        sheet.billed_hours(),
+        sheet.amount()
+    );
+}
+
+fn display_timesheet_html(sheet: &Timesheet) {
+    let money = |amount: f64| html_escape(&format!("{}{amount:.2}", sheet.currency));
+
+    print_html_head(&sheet.title);
+    println!(
+        "    <p>{} to {} &middot; {:.2} hours &middot; {}</p>",
+        sheet.start.format("%Y-%m-%d"),
+        sheet.end.format("%Y-%m-%d"),
+        sheet.billed_hours(),
+        money(sheet.amount())
+    );
+
+    for client in &sheet.clients {
+        println!(
+            "    <h2>{} ({}/h)</h2>",
+            html_escape(&client.name),
+            money(client.rate)
+        );
+        println!(
+            r#"    <table class="timesheet">
+        <tr><th>Date</th><th>Time</th><th>Description</th><th>Hours</th><th>Amount</th></tr>"#
+        );
+        for line in &client.lines {
+            println!(
+                "        <tr><td>{}</td><td>{}&ndash;{}</td><td>{}</td><td>{:.2}</td><td>{}</td></tr>",
+                line.entry.started_at.format("%Y-%m-%d"),
+                line.entry.started_at.format("%H:%M"),
+                line.entry
+                    .finished_at
+                    .unwrap_or(line.entry.started_at)
+                    .format("%H:%M"),
+                html_escape(&line.entry.description),
+                line.billed_hours(),
+                money(client.line_amount(line))
+            );
+        }
+        println!(
+            r#"        <tr class="total"><th colspan="3">Total</th><th>{:.2}</th><th>{}</th></tr>
+    </table>"#,
+            client.billed_hours(),
+            money(client.amount())
+        );
+    }
+
+    if sheet.is_empty() {
+        println!("    <p>No billable entries</p>");
+    } else {
+        println!(
+            r#"    <p class="total">Total: {:.2} hours, {}</p>"#,
+            sheet.billed_hours(),
+            money(sheet.amount())
+        );
+    }
+
+    println!("</body>\n</html>");
+}
+
+pub(crate) fn format_date(timestamp: &DateTime<Local>, now: &DateTime<Local>) -> String {
     let days_diff = (now.date_naive() - timestamp.date_naive()).num_days();
 
     if days_diff == 0 {
@@ -431,19 +886,14 @@ fn format_date(timestamp: &DateTime<Local>, now: &DateTime<Local>) -> String {
     }
 }
 
-fn calculate_duration(entry: &Entry) -> (String, Option<chrono::Duration>) {
-    if let Some(Some(done_str)) = entry.tags.get("done")
-        && let Ok(done_time) =
-            chrono::DateTime::parse_from_str(&format!("{done_str} +0000"), "%Y-%m-%d %H:%M %z")
-    {
-        let duration = done_time.with_timezone(&Local) - entry.timestamp;
-        let duration_str = format!(" ({})", format_duration(&duration));
-        return (duration_str, Some(duration));
+pub(crate) fn calculate_duration(entry: &Entry) -> (String, Option<chrono::Duration>) {
+    match entry.duration() {
+        Some(duration) => (format!(" ({})", format_duration(&duration)), Some(duration)),
+        None => (String::new(), None),
     }
-    (String::new(), None)
 }
 
-fn format_duration(duration: &chrono::Duration) -> String {
+pub(crate) fn format_duration(duration: &chrono::Duration) -> String {
     let total_seconds = duration.num_seconds();
     let hours = total_seconds / 3600;
     let minutes = (total_seconds % 3600) / 60;
@@ -458,30 +908,18 @@ fn format_duration(duration: &chrono::Duration) -> String {
     }
 }
 
-fn format_tags(tags: &HashMap<String, Option<String>>, options: &DisplayOptions) -> String {
-    let mut tag_vec: Vec<(String, Option<String>)> =
-        tags.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
-
-    // Sort tags
-    match options.tag_sort {
-        TagSort::Name => {
-            tag_vec.sort_by(|a, b| match options.tag_order {
-                SortOrder::Asc => a.0.cmp(&b.0),
-                SortOrder::Desc => b.0.cmp(&a.0),
-            });
-        }
-        TagSort::Time => {
-            // For time sorting, we'd need to parse date values in tags
-            // For now, fall back to name sorting
-            tag_vec.sort_by(|a, b| match options.tag_order {
-                SortOrder::Asc => a.0.cmp(&b.0),
-                SortOrder::Desc => b.0.cmp(&a.0),
-            });
-        }
+/// Sort an entry's tags as `--tag_sort` and `--tag_order` ask. Tags carry no
+/// time of their own, so they are sorted by name either way.
+fn sort_tags(tags: &mut IndexMap<String, Option<String>>, order: &SortOrder) {
+    match order {
+        SortOrder::Asc => tags.sort_keys(),
+        SortOrder::Desc => tags.sort_by(|a, _, b, _| b.cmp(a)),
     }
+}
 
-    tag_vec
-        .iter()
+/// Tags in the order of the map: as written in the entry unless sorted
+pub(crate) fn format_tags(tags: &IndexMap<String, Option<String>>) -> String {
+    tags.iter()
         .map(|(tag, value)| {
             if let Some(v) = value {
                 format!("@{tag}({v})")
@@ -514,3 +952,20 @@ fn html_escape(s: &str) -> String {
         .replace('"', "&quot;")
         .replace('\'', "&#39;")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::parse_title;
+
+    #[test]
+    fn test_tags_keep_their_order_unless_sorted() {
+        let (_, mut tags) = parse_title("Review @urgent @client(acme) @bug");
+        assert_eq!(format_tags(&tags), "@urgent @client(acme) @bug");
+
+        sort_tags(&mut tags, &SortOrder::Asc);
+        assert_eq!(format_tags(&tags), "@bug @client(acme) @urgent");
+        sort_tags(&mut tags, &SortOrder::Desc);
+        assert_eq!(format_tags(&tags), "@urgent @client(acme) @bug");
+    }
+}
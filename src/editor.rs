@@ -0,0 +1,317 @@
+use crate::models::Entry;
+use crate::storage::{
+    Config, FileLock, parse_taskpaper, parse_taskpaper_content, parse_title, save_taskpaper,
+};
+use color_eyre::Result;
+use color_eyre::eyre::{WrapErr, eyre};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use uuid::Uuid;
+
+const ENTRY_INSTRUCTIONS: &str = "\
+# The first line is the entry title, including @tags; the lines below it are the note.
+# Lines starting with # are ignored. Save an empty file to cancel.
+";
+
+const NOTE_INSTRUCTIONS: &str = "\
+# Write the note above. Lines starting with # are ignored. Save an empty file to cancel.
+";
+
+const BULK_INSTRUCTIONS: &str = "\
+# Edit titles, @tags, notes, start times or sections, then save and quit.
+# Keep the <id> at the end of each entry line. Entries removed here are left unchanged.
+# Lines starting with # are ignored. Save an empty file to cancel.
+";
+
+/// The editor to launch: the `editor_app` setting, then `$VISUAL`, then `$EDITOR`, then `vi`
+pub fn editor_command() -> Result<String> {
+    Ok(Config::try_load()?
+        .editor_app
+        .into_iter()
+        .chain(
+            ["VISUAL", "EDITOR"]
+                .iter()
+                .filter_map(|var| std::env::var(var).ok()),
+        )
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string()))
+}
+
+/// Open `path` in the user's editor and wait for it to exit.
+///
+/// The editor command may carry its own arguments, e.g. `code --wait`.
+pub fn edit_file(path: &Path) -> Result<()> {
+    let command = editor_command()?;
+    let mut parts = command.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .wrap_err_with(|| format!("failed to launch editor '{command}'"))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(eyre!("editor '{command}' exited with {status}"))
+    }
+}
+
+/// Let the user edit `text` in a temporary file and return what they saved
+pub fn edit_text(text: &str) -> Result<String> {
+    let mut file = tempfile::Builder::new()
+        .prefix("daily-log-")
+        .suffix(".md")
+        .tempfile()?;
+    file.write_all(text.as_bytes())?;
+    file.flush()?;
+
+    edit_file(file.path())?;
+    // Read by path: many editors save by replacing the file
+    Ok(fs::read_to_string(file.path())?)
+}
+
+/// Editor buffer for one entry: the title on line 1, the note below it
+pub fn format_entry(entry: &Entry) -> String {
+    let mut text = entry.title();
+    text.push('\n');
+    if let Some(note) = &entry.note {
+        text.push_str(note);
+        text.push('\n');
+    }
+    text.push('\n');
+    text.push_str(ENTRY_INSTRUCTIONS);
+    text
+}
+
+/// Read an edited buffer back into `entry`'s description, tags and note.
+/// Returns `None` if nothing but comments and whitespace is left.
+pub fn parse_entry(text: &str, entry: &Entry) -> Option<Entry> {
+    let lines: Vec<&str> = text.lines().filter(|line| !line.starts_with('#')).collect();
+    let start = lines.iter().position(|line| !line.trim().is_empty())?;
+
+    let (description, tags) = parse_title(lines[start].trim());
+    let mut edited = entry.clone();
+    edited.description = description;
+    edited.tags = tags;
+    edited.sync_finished_at();
+    edited.note = parse_note(&lines[start + 1..].join("\n"));
+    Some(edited)
+}
+
+/// Edit a single entry; `None` means the user cancelled with an empty buffer
+pub fn edit_entry(entry: &Entry) -> Result<Option<Entry>> {
+    let text = edit_text(&format_entry(entry))?;
+    Ok(parse_entry(&text, entry))
+}
+
+/// Edit a note on its own; `None` means the user cancelled with an empty buffer
+pub fn edit_note(note: Option<&str>) -> Result<Option<String>> {
+    let mut text = note.map(|note| format!("{note}\n")).unwrap_or_default();
+    text.push('\n');
+    text.push_str(NOTE_INSTRUCTIONS);
+    Ok(parse_note(&edit_text(&text)?))
+}
+
+fn parse_note(text: &str) -> Option<String> {
+    let lines: Vec<&str> = text.lines().filter(|line| !line.starts_with('#')).collect();
+    let note = lines.join("\n").trim_matches('\n').to_string();
+    (!note.trim().is_empty()).then_some(note)
+}
+
+/// Editor buffer for several entries, grouped by section in doing file format
+pub fn format_entries(entries: &[Entry]) -> String {
+    let mut text = String::from(BULK_INSTRUCTIONS);
+    let mut current_section: Option<&str> = None;
+    for entry in entries {
+        if current_section != Some(entry.section.as_str()) {
+            text.push_str(&entry.section);
+            text.push_str(":\n");
+            current_section = Some(&entry.section);
+        }
+        text.push_str(&entry.to_taskpaper());
+        text.push('\n');
+    }
+    text
+}
+
+/// Read an edited bulk buffer. Every entry must keep one of `ids`; anything
+/// that isn't an entry, a note or a comment is an error so nothing is lost.
+/// Returns `None` if nothing but comments and whitespace is left.
+pub fn parse_entries(text: &str, ids: &[Uuid]) -> Result<Option<Vec<Entry>>> {
+    if text
+        .lines()
+        .all(|line| line.starts_with('#') || line.trim().is_empty())
+    {
+        return Ok(None);
+    }
+
+    let parsed = parse_taskpaper_content(Path::new("editor"), text)?;
+    if let Some(line) = parsed
+        .unknown_lines
+        .iter()
+        .find(|line| !line.is_blank() && !line.text.starts_with('#'))
+    {
+        return Err(eyre!(
+            "line {} is not an entry: {}",
+            line.line_number,
+            line.text
+        ));
+    }
+
+    let entries: Vec<Entry> = parsed.get_all_entries().into_iter().cloned().collect();
+    if let Some(entry) = entries.iter().find(|entry| !ids.contains(&entry.uuid)) {
+        return Err(eyre!(
+            "'{}' doesn't have the id of an edited entry; keep the <id> at the end of each line",
+            entry.description
+        ));
+    }
+
+    Ok(Some(entries))
+}
+
+/// Open `entries` in the editor together and write the changes back to the
+/// doing file. The file is only locked after the editor closes. Returns the
+/// number of entries changed, or `None` if the user cancelled.
+pub fn edit_entries(config: &Config, entries: &[Entry]) -> Result<Option<usize>> {
+    let ids: Vec<Uuid> = entries.iter().map(|entry| entry.uuid).collect();
+    let text = edit_text(&format_entries(entries))?;
+    let Some(edited) = parse_entries(&text, &ids)? else {
+        return Ok(None);
+    };
+
+    let doing_file_path = config.doing_file_path();
+    let _lock = FileLock::acquire(&doing_file_path)?;
+    let mut doing_file = parse_taskpaper(&doing_file_path)?;
+
+    let mut changed = 0;
+    for entry in edited {
+        let unchanged = entries.contains(&entry);
+        if !unchanged && doing_file.update_entry(entry) {
+            changed += 1;
+        }
+    }
+
+    if changed > 0 {
+        save_taskpaper(&doing_file, config)?;
+    }
+    Ok(Some(changed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    fn sample_entry() -> Entry {
+        Entry::new("Write docs".to_string(), "Currently".to_string())
+            .with_tag("docs".to_string(), None)
+            .with_tag("project".to_string(), Some("api".to_string()))
+            .with_note("First line\n\nSecond paragraph".to_string())
+            .with_started_at(Local.with_ymd_and_hms(2025, 7, 28, 9, 30, 0).unwrap())
+    }
+
+    #[test]
+    fn test_entry_round_trip() {
+        let entry = sample_entry();
+        let text = format_entry(&entry);
+        assert!(
+            text.starts_with("Write docs @docs @project(api)\nFirst line\n\nSecond paragraph\n")
+        );
+        assert_eq!(parse_entry(&text, &entry), Some(entry));
+    }
+
+    #[test]
+    fn test_parse_edited_entry() {
+        let entry = sample_entry();
+        let edited = parse_entry("\n  Review docs @review  \n\n# comment\n", &entry).unwrap();
+        assert_eq!(edited.description, "Review docs");
+        assert_eq!(edited.tags.keys().collect::<Vec<_>>(), vec!["review"]);
+        assert_eq!(edited.note, None);
+        assert_eq!(edited.uuid, entry.uuid);
+        assert_eq!(edited.started_at, entry.started_at);
+    }
+
+    #[test]
+    fn test_note_buffer() {
+        assert_eq!(
+            parse_note("\nFirst\n\n  indented\n\n# comment\n"),
+            Some("First\n\n  indented".to_string())
+        );
+        assert_eq!(parse_note(NOTE_INSTRUCTIONS), None);
+    }
+
+    #[test]
+    fn test_empty_buffer_cancels() {
+        let entry = sample_entry();
+        assert_eq!(parse_entry("", &entry), None);
+        assert_eq!(parse_entry(ENTRY_INSTRUCTIONS, &entry), None);
+        assert!(
+            parse_entries(BULK_INSTRUCTIONS, &[entry.uuid])
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_bulk_round_trip() {
+        let first = sample_entry();
+        let second = Entry::new("Old task".to_string(), "Archive".to_string())
+            .with_started_at(Local.with_ymd_and_hms(2025, 7, 27, 16, 0, 0).unwrap());
+        let entries = vec![first.clone(), second.clone()];
+        let ids = vec![first.uuid, second.uuid];
+
+        let text = format_entries(&entries);
+        assert_eq!(parse_entries(&text, &ids).unwrap(), Some(entries));
+
+        // Moving an entry under another header moves it to that section
+        let moved = text
+            .replace("Archive:\n", "")
+            .replace("Currently:\n", "Later:\n");
+        let parsed = parse_entries(&moved, &ids).unwrap().unwrap();
+        assert!(parsed.iter().all(|entry| entry.section == "Later"));
+    }
+
+    #[test]
+    fn test_bulk_rejects_lost_ids_and_stray_text() {
+        let entry = sample_entry();
+        let text = format_entries(std::slice::from_ref(&entry));
+
+        let without_id = text.replace(&format!(" <{}>", entry.uuid), "");
+        assert!(parse_entries(&without_id, &[entry.uuid]).is_err());
+
+        let stray = format!("{text}just some text\n");
+        assert!(parse_entries(&stray, &[entry.uuid]).is_err());
+    }
+
+    #[test]
+    fn test_edit_entries_without_ids() {
+        let ctx = crate::test_utils::utils::TestContext::new().unwrap();
+        ctx.create_test_file(
+            "Currently:\n\t- 2016-03-02 09:15 | Answering email\n\t- 2016-03-02 10:00 | Planning the sprint @meeting\n",
+        )
+        .unwrap();
+        // A non-interactive "editor" that renames one entry in place
+        let config = Config {
+            editor_app: Some("sed -i s/Planning/Reviewing/".to_string()),
+            ..Config::load()
+        };
+        fs::write(&ctx.config_path, serde_json::to_string(&config).unwrap()).unwrap();
+
+        let entries: Vec<Entry> = parse_taskpaper(&ctx.doing_file_path)
+            .unwrap()
+            .get_all_entries()
+            .into_iter()
+            .cloned()
+            .collect();
+        assert_eq!(edit_entries(&config, &entries).unwrap(), Some(1));
+
+        let saved = ctx.read_test_file().unwrap();
+        assert!(saved.contains("| Reviewing the sprint @meeting <"));
+        assert!(saved.contains("| Answering email"));
+        assert!(!saved.contains("Planning"));
+    }
+}
@@ -1,8 +1,11 @@
+pub mod value_query;
+
 use crate::models::{DoingFile, Entry};
 use chrono::{DateTime, Local, NaiveTime};
 use chrono_english::{Dialect, parse_date_string};
 use regex::Regex;
 use std::collections::HashSet;
+pub use value_query::{ValueQuery, matches_value_queries, parse_value_queries};
 
 #[derive(Debug, Clone)]
 pub struct FilterOptions {
@@ -54,6 +57,18 @@ pub enum BoolOp {
     Pattern,
 }
 
+impl From<&str> for BoolOp {
+    /// Read a `--bool` argument; anything unrecognized is `pattern`
+    fn from(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "and" => BoolOp::And,
+            "or" => BoolOp::Or,
+            "not" => BoolOp::Not,
+            _ => BoolOp::Pattern,
+        }
+    }
+}
+
 pub fn filter_entries(
     doing_file: &DoingFile,
     options: &FilterOptions,
@@ -145,12 +160,12 @@ fn filter_by_after(entries: Vec<(String, Entry)>, after: &DateTime<Local>) -> Ve
         let time = after.time();
         entries
             .into_iter()
-            .filter(|(_, entry)| entry.timestamp.time() >= time)
+            .filter(|(_, entry)| entry.started_at.time() >= time)
             .collect()
     } else {
         entries
             .into_iter()
-            .filter(|(_, entry)| entry.timestamp >= *after)
+            .filter(|(_, entry)| entry.started_at >= *after)
             .collect()
     }
 }
@@ -164,12 +179,12 @@ fn filter_by_before(
         let time = before.time();
         entries
             .into_iter()
-            .filter(|(_, entry)| entry.timestamp.time() <= time)
+            .filter(|(_, entry)| entry.started_at.time() <= time)
             .collect()
     } else {
         entries
             .into_iter()
-            .filter(|(_, entry)| entry.timestamp <= *before)
+            .filter(|(_, entry)| entry.started_at <= *before)
             .collect()
     }
 }
@@ -182,12 +197,12 @@ fn filter_by_range(
     if let Some(to) = to {
         entries
             .into_iter()
-            .filter(|(_, entry)| entry.timestamp >= *from && entry.timestamp <= *to)
+            .filter(|(_, entry)| entry.started_at >= *from && entry.started_at <= *to)
             .collect()
     } else {
         entries
             .into_iter()
-            .filter(|(_, entry)| entry.timestamp >= *from)
+            .filter(|(_, entry)| entry.started_at >= *from)
             .collect()
     }
 }
@@ -372,13 +387,14 @@ fn has_tag(entry: &Entry, tag_pattern: &str) -> bool {
 
 fn filter_by_value_queries(
     entries: Vec<(String, Entry)>,
-    _queries: &[String],
-    _bool_op: &BoolOp,
+    queries: &[String],
+    bool_op: &BoolOp,
 ) -> Result<Vec<(String, Entry)>, color_eyre::eyre::Error> {
-    // Parse value queries like "@done > 2 hours ago"
-    // This is a simplified implementation
-    // In a full implementation, we'd parse and evaluate these queries properly
-    Ok(entries)
+    let queries = parse_value_queries(queries)?;
+    Ok(entries
+        .into_iter()
+        .filter(|(_, entry)| matches_value_queries(entry, &queries, bool_op))
+        .collect())
 }
 
 pub fn parse_date_filter(date_str: &str) -> color_eyre::Result<DateTime<Local>> {
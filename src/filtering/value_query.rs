@@ -0,0 +1,382 @@
+use super::{BoolOp, parse_date_filter};
+use crate::models::Entry;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime};
+use color_eyre::eyre::eyre;
+use regex::Regex;
+use std::cmp::Ordering;
+use std::sync::LazyLock;
+
+static QUERY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?ix)
+        ^\s*(?P<negate>!)?\s*
+        (?P<subject>@?[\w.-]+)
+        (?:\s*
+            (?P<op><=|>=|==|!=|\*=|\^=|\$=|=~|<|>|=
+                |\s(?:contains|starts\s+with|ends\s+with|matches)\s)
+            \s*(?P<value>.*?)
+        )?\s*$",
+    )
+    .unwrap()
+});
+
+/// Duration units, longest spelling first so `mins` isn't read as `m` + `ins`
+const DURATION_UNITS: &str = r"days?|d|hours?|hrs?|h|minutes?|mins?|m|seconds?|secs?|s";
+
+static DURATION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)^(?:\s*\d+(?:\.\d+)?\s*(?:{DURATION_UNITS}))+\s*$"
+    ))
+    .unwrap()
+});
+
+static DURATION_PART_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"(?i)(\d+(?:\.\d+)?)\s*({DURATION_UNITS})")).unwrap());
+
+/// A `--val` query on an entry's tag values or properties, e.g.
+/// `@done > yesterday`, `@progress >= 50` or `!@client contains acme`.
+#[derive(Debug, Clone)]
+pub struct ValueQuery {
+    subject: Subject,
+    /// `None` only checks that the tag is present
+    comparison: Option<(Operator, String)>,
+    negate: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Subject {
+    Tag(String),
+    /// When the entry started
+    Start,
+    /// Time from start to `@done`, for finished entries
+    Duration,
+    Title,
+    Note,
+    Section,
+}
+
+#[derive(Debug, Clone)]
+enum Operator {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Matches(Regex),
+}
+
+/// The value a query compares against, typed where the entry knows more than text
+enum Operand {
+    Text(String),
+    Time(DateTime<Local>),
+    Span(Duration),
+}
+
+impl ValueQuery {
+    /// Parse `[!]SUBJECT [OPERATOR VALUE]`.
+    ///
+    /// `@name` is always a tag. A bare name is one of the properties `start`,
+    /// `duration`, `title`, `note` or `section`, or a tag otherwise.
+    pub fn parse(query: &str) -> color_eyre::Result<Self> {
+        let captures = QUERY_REGEX
+            .captures(query)
+            .ok_or_else(|| eyre!("invalid value query: {query}"))?;
+
+        let subject = match &captures["subject"] {
+            tag if tag.starts_with('@') => Subject::Tag(tag[1..].to_string()),
+            name => match name.to_lowercase().as_str() {
+                "start" | "date" => Subject::Start,
+                "duration" | "interval" => Subject::Duration,
+                "title" => Subject::Title,
+                "note" => Subject::Note,
+                "section" => Subject::Section,
+                _ => Subject::Tag(name.to_string()),
+            },
+        };
+
+        let comparison = match (captures.name("op"), captures.name("value")) {
+            (Some(op), Some(value)) => {
+                let value = unquote(value.as_str());
+                let operator = match op.as_str().trim().to_lowercase().as_str() {
+                    "<" => Operator::Lt,
+                    "<=" => Operator::Le,
+                    ">" => Operator::Gt,
+                    ">=" => Operator::Ge,
+                    "=" | "==" => Operator::Eq,
+                    "!=" => Operator::Ne,
+                    "*=" | "contains" => Operator::Contains,
+                    "^=" => Operator::StartsWith,
+                    "$=" => Operator::EndsWith,
+                    "=~" | "matches" => Operator::Matches(Regex::new(&value)?),
+                    words if words.starts_with("starts") => Operator::StartsWith,
+                    _ => Operator::EndsWith,
+                };
+                Some((operator, value))
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            subject,
+            comparison,
+            negate: captures.name("negate").is_some(),
+        })
+    }
+
+    pub fn matches(&self, entry: &Entry) -> bool {
+        let matched = match (&self.comparison, &self.subject) {
+            (None, Subject::Tag(tag)) => entry.tags.contains_key(tag),
+            (None, _) => self.operand(entry).is_some(),
+            (Some((operator, value)), _) => self
+                .operand(entry)
+                .is_some_and(|operand| compare(&operand, operator, value)),
+        };
+        matched != self.negate
+    }
+
+    fn operand(&self, entry: &Entry) -> Option<Operand> {
+        match &self.subject {
+            Subject::Tag(tag) => entry.tags.get(tag)?.clone().map(Operand::Text),
+            Subject::Start => Some(Operand::Time(entry.started_at)),
+            Subject::Duration => entry.duration().map(Operand::Span),
+            Subject::Title => Some(Operand::Text(entry.title())),
+            Subject::Note => entry.note.clone().map(Operand::Text),
+            Subject::Section => Some(Operand::Text(entry.section.clone())),
+        }
+    }
+}
+
+/// Parse every `--val` query up front so a typo fails before anything is changed
+pub fn parse_value_queries(queries: &[String]) -> color_eyre::Result<Vec<ValueQuery>> {
+    queries
+        .iter()
+        .map(|query| ValueQuery::parse(query))
+        .collect()
+}
+
+/// Whether `entry` satisfies `queries` combined with `--bool`.
+/// `pattern` (the default) requires every query, like `and`.
+pub fn matches_value_queries(entry: &Entry, queries: &[ValueQuery], bool_op: &BoolOp) -> bool {
+    match bool_op {
+        BoolOp::And | BoolOp::Pattern => queries.iter().all(|query| query.matches(entry)),
+        BoolOp::Or => queries.iter().any(|query| query.matches(entry)),
+        BoolOp::Not => !queries.iter().any(|query| query.matches(entry)),
+    }
+}
+
+fn compare(operand: &Operand, operator: &Operator, value: &str) -> bool {
+    let text = match operand {
+        Operand::Text(text) => text.as_str(),
+        Operand::Time(time) => {
+            return parse_time(value)
+                .is_some_and(|value| ordering_matches(time.cmp(&value), operator));
+        }
+        Operand::Span(span) => {
+            return parse_duration(value)
+                .is_some_and(|value| ordering_matches(span.cmp(&value), operator));
+        }
+    };
+
+    let lower_text = text.to_lowercase();
+    let lower_value = value.to_lowercase();
+    match operator {
+        Operator::Contains => return lower_text.contains(&lower_value),
+        Operator::StartsWith => return lower_text.starts_with(&lower_value),
+        Operator::EndsWith => return lower_text.ends_with(&lower_value),
+        Operator::Matches(regex) => return regex.is_match(text),
+        _ => {}
+    }
+
+    // Compare as the most specific type both sides agree on
+    if let (Some(a), Some(b)) = (parse_number(text), parse_number(value)) {
+        return a
+            .partial_cmp(&b)
+            .is_some_and(|ordering| ordering_matches(ordering, operator));
+    }
+    if let (Some(a), Some(b)) = (parse_duration(text), parse_duration(value)) {
+        return ordering_matches(a.cmp(&b), operator);
+    }
+    if let (Some(a), Some(b)) = (parse_time(text), parse_time(value)) {
+        return ordering_matches(a.cmp(&b), operator);
+    }
+
+    match operator {
+        Operator::Eq => wildcard_eq(&lower_text, &lower_value),
+        Operator::Ne => !wildcard_eq(&lower_text, &lower_value),
+        _ => ordering_matches(lower_text.cmp(&lower_value), operator),
+    }
+}
+
+fn ordering_matches(ordering: Ordering, operator: &Operator) -> bool {
+    match operator {
+        Operator::Lt => ordering == Ordering::Less,
+        Operator::Le => ordering != Ordering::Greater,
+        Operator::Gt => ordering == Ordering::Greater,
+        Operator::Ge => ordering != Ordering::Less,
+        Operator::Eq => ordering == Ordering::Equal,
+        Operator::Ne => ordering != Ordering::Equal,
+        _ => false,
+    }
+}
+
+/// String equality where `*` and `?` in the query are wildcards
+fn wildcard_eq(text: &str, pattern: &str) -> bool {
+    if !pattern.contains(['*', '?']) {
+        return text == pattern;
+    }
+    let pattern = regex::escape(pattern)
+        .replace(r"\*", ".*")
+        .replace(r"\?", ".");
+    Regex::new(&format!("^{pattern}$")).is_ok_and(|regex| regex.is_match(text))
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner.to_string();
+        }
+    }
+    value.to_string()
+}
+
+/// A number, allowing a trailing `%` as in `@progress(50%)`
+fn parse_number(value: &str) -> Option<f64> {
+    value.trim().trim_end_matches('%').trim().parse().ok()
+}
+
+/// `1h30m`, `90 minutes`, `2d` or `1:30` (hours and minutes)
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Some((hours, minutes)) = value.split_once(':')
+        && let (Ok(hours), Ok(minutes)) = (hours.parse::<i64>(), minutes.parse::<i64>())
+    {
+        return Some(Duration::hours(hours) + Duration::minutes(minutes));
+    }
+
+    if !DURATION_REGEX.is_match(value) {
+        return None;
+    }
+    let seconds: f64 = DURATION_PART_REGEX
+        .captures_iter(value)
+        .filter_map(|part| {
+            let amount: f64 = part[1].parse().ok()?;
+            let unit = match part[2].to_lowercase().chars().next() {
+                Some('d') => 86_400.0,
+                Some('h') => 3_600.0,
+                Some('m') => 60.0,
+                _ => 1.0,
+            };
+            Some(amount * unit)
+        })
+        .sum();
+    Some(Duration::seconds(seconds as i64))
+}
+
+/// A doing file timestamp, or a natural language date such as `yesterday`
+fn parse_time(value: &str) -> Option<DateTime<Local>> {
+    let value = value.trim();
+    let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S"))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)
+        });
+
+    match naive {
+        Some(naive) => naive.and_local_timezone(Local).earliest(),
+        None => parse_date_filter(value).ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry() -> Entry {
+        let start = Local.with_ymd_and_hms(2025, 7, 28, 9, 0, 0).unwrap();
+        Entry::new("Write the API docs".to_string(), "Currently".to_string())
+            .with_started_at(start)
+            .with_tag("client".to_string(), Some("Acme Corp".to_string()))
+            .with_tag("progress".to_string(), Some("50%".to_string()))
+            .with_tag("estimate".to_string(), Some("1h30m".to_string()))
+            .with_tag("done".to_string(), Some("2025-07-28 11:00".to_string()))
+            .with_tag("review".to_string(), None)
+    }
+
+    fn matches(query: &str) -> bool {
+        ValueQuery::parse(query).unwrap().matches(&entry())
+    }
+
+    #[test]
+    fn test_numbers_durations_and_dates() {
+        assert!(matches("@progress >= 50"));
+        assert!(!matches("@progress > 50"));
+        assert!(matches("@estimate < 2h"));
+        assert!(matches("@estimate == 90m"));
+        assert!(matches("@estimate == 1 hour 30 mins"));
+        assert!(parse_duration("90 minutes").is_some());
+        assert!(parse_duration("5 months").is_none());
+        assert!(parse_duration("3 hms").is_none());
+        assert!(matches("@done > 2025-07-28"));
+        assert!(matches("@done < today"));
+        assert!(matches("duration == 2h"));
+        assert!(matches("start <= 2025-07-28 09:00"));
+    }
+
+    #[test]
+    fn test_string_operators() {
+        assert!(matches("@client == 'acme corp'"));
+        assert!(matches("@client = acme*"));
+        assert!(matches("@client != initech"));
+        assert!(matches("@client contains corp"));
+        assert!(matches("@client starts with acme"));
+        assert!(matches("@client $= corp"));
+        assert!(matches("@client =~ ^Ac"));
+        assert!(matches("title *= api"));
+        assert!(matches("section == currently"));
+        assert!(!matches("note contains anything"));
+    }
+
+    #[test]
+    fn test_presence_and_negation() {
+        assert!(matches("@review"));
+        assert!(matches("review"));
+        assert!(!matches("@missing"));
+        assert!(matches("!@missing"));
+        assert!(!matches("!@client == acme*"));
+        // A tag without a value can't satisfy a comparison
+        assert!(!matches("@review == yes"));
+    }
+
+    #[test]
+    fn test_bool_op_combines_queries() {
+        let queries = parse_value_queries(&[
+            "@progress > 10".to_string(),
+            "@client == initech".to_string(),
+        ])
+        .unwrap();
+        let entry = entry();
+        assert!(!matches_value_queries(&entry, &queries, &BoolOp::Pattern));
+        assert!(!matches_value_queries(&entry, &queries, &BoolOp::And));
+        assert!(matches_value_queries(&entry, &queries, &BoolOp::Or));
+        assert!(!matches_value_queries(&entry, &queries, &BoolOp::Not));
+    }
+
+    #[test]
+    fn test_invalid_queries_are_errors() {
+        assert!(ValueQuery::parse("@client == ").is_ok());
+        assert!(ValueQuery::parse("> 5").is_err());
+        assert!(ValueQuery::parse("@client =~ (").is_err());
+    }
+}
@@ -0,0 +1,1030 @@
+//! Reading and writing iCalendar (RFC 5545) files.
+//!
+//! Entries are written as events and events are read back as plain values;
+//! turning those into entries is left to the importer.
+
+use crate::models::Entry;
+use chrono::{
+    DateTime, Datelike, Days, Duration, FixedOffset, Local, Months, NaiveDate, NaiveDateTime,
+    NaiveTime, TimeZone, Utc, Weekday,
+};
+use color_eyre::Result;
+use color_eyre::eyre::eyre;
+use std::collections::{HashMap, HashSet};
+
+const UTC_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Longest a content line may be, in bytes, before it is folded
+const LINE_LIMIT: usize = 75;
+
+/// Most periods of a recurrence rule looked at, so a rule that never
+/// matches a day can't loop forever
+const MAX_PERIODS: u32 = 100_000;
+
+/// The parameters of a content line, like `TZID=Europe/Madrid`, names in upper case
+type Params = Vec<(String, String)>;
+
+/// A content line: its upper-case name, parameters and value
+type Property = (String, Params, String);
+
+/// An event read from a calendar
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub uid: Option<String>,
+    pub summary: String,
+    pub description: Option<String>,
+    pub categories: Vec<String>,
+    /// None for all-day events
+    pub start: Option<DateTime<Local>>,
+    /// From DTEND, or DTSTART plus DURATION
+    pub end: Option<DateTime<Local>>,
+    pub cancelled: bool,
+    /// One occurrence of a recurring event; they all share its UID
+    pub recurring: bool,
+}
+
+/// A calendar with one event per entry. Finished entries end at their
+/// `@done` time; running entries have no end.
+pub fn write_calendar(entries: &[(String, Entry)], now: DateTime<Local>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//daily-log//daily-log//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for (_, entry) in entries {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", entry.uuid));
+        lines.push(format!("DTSTAMP:{}", utc(now)));
+        lines.push(format!("DTSTART:{}", utc(entry.started_at)));
+        if let Some(end) = entry.finished_at {
+            lines.push(format!("DTEND:{}", utc(end)));
+        }
+        lines.push(format!("SUMMARY:{}", escape(&entry.description)));
+
+        let categories: Vec<String> = entry
+            .tags
+            .iter()
+            .filter(|(tag, _)| tag.as_str() != "done")
+            .map(|(tag, value)| match value {
+                Some(value) => escape(&format!("{tag}({value})")),
+                None => escape(tag),
+            })
+            .collect();
+        if !categories.is_empty() {
+            lines.push(format!("CATEGORIES:{}", categories.join(",")));
+        }
+        if let Some(note) = entry.note.as_deref().filter(|note| !note.is_empty()) {
+            lines.push(format!("DESCRIPTION:{}", escape(note)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold(line) + "\r\n")
+        .collect::<String>()
+}
+
+/// Read the events of a calendar. Alarms and other components inside an
+/// event are skipped. Times with a TZID are converted from the time zone the
+/// calendar defines for it; a TZID the calendar doesn't define is an error.
+/// Recurring events are expanded into one event per occurrence, up to the
+/// occurrences starting by `until`.
+pub fn read_events(content: &str, until: DateTime<Local>) -> Result<Vec<Event>> {
+    let content = content.trim_start_matches('\u{feff}');
+    if !content.trim_start().starts_with("BEGIN:VCALENDAR") {
+        return Err(eyre!("not an iCalendar file"));
+    }
+
+    let mut raw_events: Vec<Vec<Property>> = Vec::new();
+    let mut zones: HashMap<String, Zone> = HashMap::new();
+    // Names of the components the current line is in, innermost last
+    let mut components: Vec<String> = Vec::new();
+    let mut event: Option<Vec<Property>> = None;
+    let mut zone: Option<(Option<String>, Zone)> = None;
+    let mut observance: Option<Vec<Property>> = None;
+
+    for line in unfold(content) {
+        let Some((name, params, value)) = split_line(&line) else {
+            continue;
+        };
+        let inside = components.last().map(String::as_str);
+        match name.as_str() {
+            "BEGIN" => {
+                let component = value.to_uppercase();
+                match (component.as_str(), inside) {
+                    ("VEVENT", _) if event.is_none() => event = Some(Vec::new()),
+                    ("VTIMEZONE", _) if zone.is_none() => zone = Some((None, Zone::default())),
+                    ("STANDARD" | "DAYLIGHT", Some("VTIMEZONE")) => observance = Some(Vec::new()),
+                    _ => {}
+                }
+                components.push(component);
+            }
+            "END" => match components.pop().as_deref() {
+                Some("VEVENT") => raw_events.extend(event.take()),
+                Some("VTIMEZONE") => {
+                    if let Some((Some(tzid), zone)) = zone.take() {
+                        zones.insert(tzid, zone);
+                    }
+                }
+                Some("STANDARD" | "DAYLIGHT") => {
+                    if let (Some(properties), Some((_, zone))) = (observance.take(), zone.as_mut())
+                    {
+                        zone.observances.extend(Observance::parse(&properties)?);
+                    }
+                }
+                _ => {}
+            },
+            _ => match (inside, event.as_mut(), zone.as_mut(), observance.as_mut()) {
+                (Some("VEVENT"), Some(event), _, _) => event.push((name, params, value)),
+                (Some("VTIMEZONE"), _, Some((tzid, _)), _) if name == "TZID" => {
+                    *tzid = Some(value);
+                }
+                (Some("STANDARD" | "DAYLIGHT"), _, _, Some(observance)) => {
+                    observance.push((name, params, value));
+                }
+                _ => {}
+            },
+        }
+    }
+
+    // Occurrences moved or changed by an event of their own
+    let mut overrides = HashSet::new();
+    for properties in &raw_events {
+        if let (Some((_, _, uid)), Some((_, params, value))) = (
+            property(properties, "UID"),
+            property(properties, "RECURRENCE-ID"),
+        ) && let Some(time) = resolve(parse_value(value, params)?, clock(&zones, params)?)
+        {
+            overrides.insert((uid.clone(), time));
+        }
+    }
+
+    let mut events = Vec::new();
+    for properties in &raw_events {
+        events.extend(build_events(properties, &zones, &overrides, until)?);
+    }
+    Ok(events)
+}
+
+/// The events for one VEVENT: the event itself, or each occurrence of a
+/// recurring event
+fn build_events(
+    properties: &[Property],
+    zones: &HashMap<String, Zone>,
+    overrides: &HashSet<(String, DateTime<Local>)>,
+    until: DateTime<Local>,
+) -> Result<Vec<Event>> {
+    let mut event = Event {
+        uid: None,
+        summary: String::new(),
+        description: None,
+        categories: Vec::new(),
+        start: None,
+        end: None,
+        cancelled: false,
+        recurring: false,
+    };
+    let mut start = None;
+    let mut duration = None;
+    let mut rule = None;
+    let mut excluded = HashSet::new();
+
+    for (name, params, value) in properties {
+        match name.as_str() {
+            "UID" => event.uid = Some(value.clone()),
+            "SUMMARY" => event.summary = unescape(value),
+            "DESCRIPTION" => event.description = Some(unescape(value)),
+            "CATEGORIES" => event.categories.extend(
+                split_list(value)
+                    .iter()
+                    .map(|category| unescape(category))
+                    .filter(|category| !category.trim().is_empty()),
+            ),
+            "STATUS" => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            "DTSTART" => {
+                let time = parse_value(value, params)?;
+                // Occurrences of a UTC start repeat in UTC
+                let clock = match time {
+                    CalTime::Utc(_) => Clock::Utc,
+                    _ => clock(zones, params)?,
+                };
+                event.start = resolve(time, clock);
+                start = Some((time, clock));
+            }
+            "DTEND" => event.end = resolve(parse_value(value, params)?, clock(zones, params)?),
+            "DURATION" => duration = parse_duration(value),
+            "RRULE" => rule = Some(Rule::parse(value)?),
+            "EXDATE" => {
+                let clock = clock(zones, params)?;
+                for value in value.split(',') {
+                    excluded.extend(resolve(parse_value(value, params)?, clock));
+                }
+            }
+            "RECURRENCE-ID" => event.recurring = true,
+            _ => {}
+        }
+    }
+    if event.end.is_none()
+        && let (Some(start), Some(duration)) = (event.start, duration)
+    {
+        event.end = Some(start + duration);
+    }
+
+    let (Some(rule), Some((CalTime::Utc(first) | CalTime::Wall(first), clock)), Some(start)) =
+        (rule, start, event.start)
+    else {
+        return Ok(vec![event]);
+    };
+    // Time zone changes move the occurrences' times by at most a day
+    let last = match rule.until {
+        Some(rule_until) => clock
+            .wall_time(rule_until)
+            .min(clock.wall(until) + Duration::days(1)),
+        None => clock.wall(until) + Duration::days(1),
+    };
+    let length = event.end.map(|end| end - start);
+    let uid = event.uid.clone().unwrap_or_default();
+
+    Ok(rule
+        .occurrences(first, last)
+        .into_iter()
+        .filter_map(|wall| clock.instant(wall))
+        .filter(|start| *start <= until && !excluded.contains(start))
+        .filter(|start| !overrides.contains(&(uid.clone(), *start)))
+        .map(|start| Event {
+            start: Some(start),
+            end: length.map(|length| start + length),
+            recurring: true,
+            ..event.clone()
+        })
+        .collect())
+}
+
+/// The first property called `name`
+fn property<'a>(properties: &'a [Property], name: &str) -> Option<&'a Property> {
+    properties.iter().find(|(property, _, _)| property == name)
+}
+
+/// A time value as written, before it is placed in a time zone
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CalTime {
+    Date(NaiveDate),
+    Utc(NaiveDateTime),
+    /// Wall-clock time, in the time zone of its TZID or floating
+    Wall(NaiveDateTime),
+}
+
+/// A `DATE` or `DATE-TIME` value
+fn parse_value(value: &str, params: &[(String, String)]) -> Result<CalTime> {
+    let is_date = params
+        .iter()
+        .any(|(name, value)| name == "VALUE" && value.eq_ignore_ascii_case("DATE"));
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y%m%d") {
+        return Ok(CalTime::Date(date));
+    }
+    let invalid = || eyre!("invalid calendar time '{value}'");
+    if is_date {
+        return Err(invalid());
+    }
+    match value.strip_suffix('Z') {
+        Some(utc) => NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").map(CalTime::Utc),
+        None => NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").map(CalTime::Wall),
+    }
+    .map_err(|_| invalid())
+}
+
+/// The instant a time value stands for. Dates are days, not times, and
+/// stand for none.
+fn resolve(time: CalTime, clock: Clock) -> Option<DateTime<Local>> {
+    match time {
+        CalTime::Date(_) => None,
+        CalTime::Utc(time) => Clock::Utc.instant(time),
+        CalTime::Wall(time) => clock.instant(time),
+    }
+}
+
+/// The clock a time with these parameters is read on: its TZID, or the
+/// local time of whoever reads the calendar when it has none
+fn clock<'a>(zones: &'a HashMap<String, Zone>, params: &[(String, String)]) -> Result<Clock<'a>> {
+    let Some((_, tzid)) = params.iter().find(|(name, _)| name == "TZID") else {
+        return Ok(Clock::Floating);
+    };
+    if let Some(zone) = zones.get(tzid) {
+        return Ok(Clock::Zone(zone));
+    }
+    match tzid.to_uppercase().as_str() {
+        "UTC" | "GMT" | "Z" | "ETC/UTC" | "ETC/GMT" => Ok(Clock::Utc),
+        _ => Err(eyre!(
+            "time zone '{tzid}' is not defined in the calendar (it has no VTIMEZONE for it)"
+        )),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Clock<'a> {
+    Floating,
+    Utc,
+    Zone(&'a Zone),
+}
+
+impl Clock<'_> {
+    fn instant(self, wall: NaiveDateTime) -> Option<DateTime<Local>> {
+        match self {
+            Clock::Floating => wall.and_local_timezone(Local).earliest(),
+            Clock::Utc => Some(wall.and_utc().with_timezone(&Local)),
+            Clock::Zone(zone) => FixedOffset::east_opt(zone.offset_at(wall))?
+                .from_local_datetime(&wall)
+                .earliest()
+                .map(|time| time.with_timezone(&Local)),
+        }
+    }
+
+    /// The time on this clock at `instant`
+    fn wall(self, instant: DateTime<Local>) -> NaiveDateTime {
+        match self {
+            Clock::Floating => instant.naive_local(),
+            Clock::Utc => instant.naive_utc(),
+            Clock::Zone(zone) => {
+                let utc = instant.naive_utc();
+                utc + Duration::seconds(i64::from(zone.offset_at(utc)))
+            }
+        }
+    }
+
+    /// The time on this clock for a time value; a date is its last second
+    fn wall_time(self, time: CalTime) -> NaiveDateTime {
+        match time {
+            CalTime::Date(date) => date.and_time(NaiveTime::MIN) + Duration::seconds(86399),
+            CalTime::Utc(time) => self.wall(time.and_utc().with_timezone(&Local)),
+            CalTime::Wall(time) => time,
+        }
+    }
+}
+
+/// A time zone defined by a VTIMEZONE, as the offsets from UTC it changes
+/// between
+#[derive(Debug, Default)]
+struct Zone {
+    observances: Vec<Observance>,
+}
+
+impl Zone {
+    /// Offset from UTC in seconds at a wall-clock time in the zone
+    fn offset_at(&self, wall: NaiveDateTime) -> i32 {
+        self.observances
+            .iter()
+            .filter_map(|observance| {
+                observance
+                    .last_onset(wall)
+                    .map(|onset| (onset, observance.offset_to))
+            })
+            .max_by_key(|(onset, _)| *onset)
+            .map(|(_, offset)| offset)
+            // Before the zone's first change, it was at the offset changed from
+            .or_else(|| {
+                self.observances
+                    .iter()
+                    .min_by_key(|observance| observance.onset)
+                    .map(|observance| observance.offset_from)
+            })
+            .unwrap_or(0)
+    }
+}
+
+/// A STANDARD or DAYLIGHT part of a VTIMEZONE: from `onset`, and every
+/// time its rule repeats, the zone is at `offset_to`
+#[derive(Debug)]
+struct Observance {
+    onset: NaiveDateTime,
+    offset_from: i32,
+    offset_to: i32,
+    rule: Option<Rule>,
+}
+
+impl Observance {
+    fn parse(properties: &[Property]) -> Result<Option<Self>> {
+        let value = |name: &str| property(properties, name).map(|(_, _, value)| value.as_str());
+        let (Some(onset), Some(offset_from), Some(offset_to)) = (
+            value("DTSTART"),
+            value("TZOFFSETFROM").and_then(parse_offset),
+            value("TZOFFSETTO").and_then(parse_offset),
+        ) else {
+            return Ok(None);
+        };
+        let (CalTime::Wall(onset) | CalTime::Utc(onset)) = parse_value(onset, &[])? else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            onset,
+            offset_from,
+            offset_to,
+            rule: value("RRULE").map(Rule::parse).transpose()?,
+        }))
+    }
+
+    /// The last time the zone changed to this observance by `wall`
+    fn last_onset(&self, wall: NaiveDateTime) -> Option<NaiveDateTime> {
+        let Some(rule) = &self.rule else {
+            return (self.onset <= wall).then_some(self.onset);
+        };
+        let last = match rule.until {
+            Some(CalTime::Utc(until)) => {
+                wall.min(until + Duration::seconds(i64::from(self.offset_from)))
+            }
+            Some(until) => wall.min(Clock::Floating.wall_time(until)),
+            None => wall,
+        };
+        rule.occurrences(self.onset, last).last().copied()
+    }
+}
+
+/// A `UTC-OFFSET` value like `+0100` or `-0430`, in seconds
+fn parse_offset(value: &str) -> Option<i32> {
+    let (sign, digits) = match value.trim().split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    if !matches!(digits.len(), 4 | 6) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let part =
+        |range: std::ops::Range<usize>| digits.get(range).and_then(|d| d.parse::<i32>().ok());
+    let seconds = part(0..2)? * 3600 + part(2..4)? * 60 + part(4..6).unwrap_or(0);
+    Some(sign * seconds)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A recurrence rule (`RRULE`). Only the parts calendars use for meetings
+/// and time zone changes are understood; a rule with any other part is
+/// rejected rather than read as something else.
+#[derive(Debug, Clone)]
+struct Rule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<usize>,
+    until: Option<CalTime>,
+    /// Weekdays, with their ordinal in the month for monthly and yearly rules
+    by_day: Vec<(Option<i32>, Weekday)>,
+    by_month: Vec<u32>,
+    /// Days of the month, negative counting from its end
+    by_month_day: Vec<i32>,
+}
+
+impl Rule {
+    fn parse(value: &str) -> Result<Self> {
+        let unsupported = || eyre!("unsupported recurrence rule '{value}'");
+        let mut frequency = None;
+        let mut rule = Rule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month: Vec::new(),
+            by_month_day: Vec::new(),
+        };
+
+        for part in value.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part.split_once('=').ok_or_else(unsupported)?;
+            let list = || value.split(',').map(str::trim);
+            match name.to_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(unsupported()),
+                    });
+                }
+                "INTERVAL" => {
+                    rule.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|interval| *interval > 0)
+                        .ok_or_else(unsupported)?;
+                }
+                "COUNT" => rule.count = Some(value.parse().map_err(|_| unsupported())?),
+                "UNTIL" => rule.until = Some(parse_value(value, &[])?),
+                "BYDAY" => {
+                    for day in list() {
+                        rule.by_day
+                            .push(parse_weekday(day).ok_or_else(unsupported)?);
+                    }
+                }
+                "BYMONTH" => {
+                    for month in list() {
+                        let month = month.parse().ok().filter(|month| (1..=12).contains(month));
+                        rule.by_month.push(month.ok_or_else(unsupported)?);
+                    }
+                }
+                "BYMONTHDAY" => {
+                    for day in list() {
+                        let day = day
+                            .parse()
+                            .ok()
+                            .filter(|day: &i32| *day != 0 && (-31..=31).contains(day));
+                        rule.by_month_day.push(day.ok_or_else(unsupported)?);
+                    }
+                }
+                // Weeks are taken to start on Monday, the default
+                "WKST" => {}
+                _ => return Err(unsupported()),
+            }
+        }
+
+        rule.frequency = frequency.ok_or_else(unsupported)?;
+        // Ordinals are only read within a month
+        let ordinals = rule.by_day.iter().any(|(ordinal, _)| ordinal.is_some());
+        let yearly_by_day = rule.frequency == Frequency::Yearly
+            && !rule.by_day.is_empty()
+            && rule.by_month.is_empty();
+        let ordinal_outside_month =
+            ordinals && matches!(rule.frequency, Frequency::Daily | Frequency::Weekly);
+        if yearly_by_day || ordinal_outside_month {
+            return Err(unsupported());
+        }
+        Ok(rule)
+    }
+
+    /// The occurrences from `start` up to `last`, in the event's own time.
+    /// `start` is always the first, as RFC 5545 counts it.
+    fn occurrences(&self, start: NaiveDateTime, last: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let mut occurrences = Vec::new();
+        if start > last {
+            return occurrences;
+        }
+        occurrences.push(start);
+
+        for period in 0..MAX_PERIODS {
+            let Some(first) = self.period_start(start.date(), period * self.interval) else {
+                break;
+            };
+            if first.and_time(start.time()) > last {
+                break;
+            }
+            for day in self.days(start.date(), first) {
+                let occurrence = day.and_time(start.time());
+                if occurrence <= start {
+                    continue;
+                }
+                if occurrence > last || self.count.is_some_and(|count| occurrences.len() >= count) {
+                    return occurrences;
+                }
+                occurrences.push(occurrence);
+            }
+        }
+        occurrences
+    }
+
+    /// The first day of the `n`th period from the one `start` is in
+    fn period_start(&self, start: NaiveDate, n: u32) -> Option<NaiveDate> {
+        match self.frequency {
+            Frequency::Daily => start.checked_add_days(Days::new(n.into())),
+            Frequency::Weekly => start
+                .week(Weekday::Mon)
+                .first_day()
+                .checked_add_days(Days::new(u64::from(n) * 7)),
+            Frequency::Monthly => start.with_day(1)?.checked_add_months(Months::new(n)),
+            Frequency::Yearly => NaiveDate::from_ymd_opt(start.year(), 1, 1)?
+                .checked_add_months(Months::new(n.checked_mul(12)?)),
+        }
+    }
+
+    /// The days of the period starting on `first` the rule picks, in order
+    fn days(&self, start: NaiveDate, first: NaiveDate) -> Vec<NaiveDate> {
+        let mut days = match self.frequency {
+            Frequency::Daily => vec![first],
+            Frequency::Weekly if self.by_day.is_empty() => {
+                vec![first + Days::new(start.weekday().num_days_from_monday().into())]
+            }
+            Frequency::Weekly => self
+                .by_day
+                .iter()
+                .map(|(_, weekday)| first + Days::new(weekday.num_days_from_monday().into()))
+                .collect(),
+            Frequency::Monthly => self.month_days(start, first.year(), first.month()),
+            Frequency::Yearly => {
+                let months = match self.by_month.as_slice() {
+                    [] => vec![start.month()],
+                    months => months.to_vec(),
+                };
+                months
+                    .into_iter()
+                    .flat_map(|month| self.month_days(start, first.year(), month))
+                    .collect()
+            }
+        };
+
+        days.retain(|day| {
+            (self.by_month.is_empty() || self.by_month.contains(&day.month()))
+                && (self.by_day.is_empty()
+                    || self
+                        .by_day
+                        .iter()
+                        .any(|(_, weekday)| *weekday == day.weekday()))
+                && (self.by_month_day.is_empty() || month_day_matches(&self.by_month_day, *day))
+        });
+        days.sort();
+        days.dedup();
+        days
+    }
+
+    /// The days of a month the rule picks: its month days, its weekdays, or
+    /// the day of the month the event started on
+    fn month_days(&self, start: NaiveDate, year: i32, month: u32) -> Vec<NaiveDate> {
+        let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+            return Vec::new();
+        };
+        let month_length = first
+            .checked_add_months(Months::new(1))
+            .map_or(31, |next| (next - first).num_days() as u32);
+        let all: Vec<NaiveDate> = (0..month_length)
+            .map(|offset| first + Days::new(offset.into()))
+            .collect();
+
+        if !self.by_month_day.is_empty() {
+            return all
+                .into_iter()
+                .filter(|day| month_day_matches(&self.by_month_day, *day))
+                .collect();
+        }
+        if !self.by_day.is_empty() {
+            let mut days = Vec::new();
+            for (ordinal, weekday) in &self.by_day {
+                let matching: Vec<NaiveDate> = all
+                    .iter()
+                    .copied()
+                    .filter(|day| day.weekday() == *weekday)
+                    .collect();
+                match ordinal {
+                    None => days.extend(matching),
+                    Some(n) if *n > 0 => days.extend(matching.get(*n as usize - 1)),
+                    Some(n) => days.extend(
+                        matching
+                            .len()
+                            .checked_sub(n.unsigned_abs() as usize)
+                            .and_then(|index| matching.get(index)),
+                    ),
+                }
+            }
+            return days;
+        }
+        NaiveDate::from_ymd_opt(year, month, start.day())
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Whether `day` is one of the month days, negative ones counting from the
+/// end of the month
+fn month_day_matches(month_days: &[i32], day: NaiveDate) -> bool {
+    let days_in_month = day
+        .with_day(1)
+        .and_then(|first| first.checked_add_months(Months::new(1)))
+        .map_or(31, |next| {
+            next.pred_opt().map_or(31, |last| last.day() as i32)
+        });
+    month_days.iter().any(|month_day| {
+        let month_day = if *month_day < 0 {
+            days_in_month + month_day + 1
+        } else {
+            *month_day
+        };
+        month_day == day.day() as i32
+    })
+}
+
+/// A `BYDAY` item like `MO`, `2TU` or `-1SU`
+fn parse_weekday(value: &str) -> Option<(Option<i32>, Weekday)> {
+    let (ordinal, day) = value.split_at_checked(value.len().checked_sub(2)?)?;
+    let weekday = match day.to_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    };
+    let ordinal = match ordinal {
+        "" => None,
+        ordinal => Some(
+            ordinal
+                .trim_start_matches('+')
+                .parse::<i32>()
+                .ok()
+                .filter(|n| *n != 0 && (-5..=5).contains(n))?,
+        ),
+    };
+    Some((ordinal, weekday))
+}
+
+fn utc(time: DateTime<Local>) -> String {
+    time.with_timezone(&Utc).format(UTC_FORMAT).to_string()
+}
+
+/// A `DURATION` value like `PT1H30M` or `P1D`
+fn parse_duration(value: &str) -> Option<Duration> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let mut rest = value.strip_prefix('P')?;
+    let mut duration = Duration::zero();
+    let mut in_time = false;
+
+    while !rest.is_empty() {
+        if let Some(time) = rest.strip_prefix('T') {
+            in_time = true;
+            rest = time;
+            continue;
+        }
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let amount: i64 = rest[..digits].parse().ok()?;
+        duration += match (rest[digits..].chars().next()?, in_time) {
+            ('W', false) => Duration::weeks(amount),
+            ('D', false) => Duration::days(amount),
+            ('H', true) => Duration::hours(amount),
+            ('M', true) => Duration::minutes(amount),
+            ('S', true) => Duration::seconds(amount),
+            _ => return None,
+        };
+        rest = &rest[digits + 1..];
+    }
+
+    Some(if negative { -duration } else { duration })
+}
+
+/// Escape text for a `TEXT` value
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+/// Split a list value on the commas that aren't escaped
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = vec![String::new()];
+    let mut escaped = false;
+    for c in value.chars() {
+        match c {
+            ',' if !escaped => items.push(String::new()),
+            _ => {
+                escaped = c == '\\' && !escaped;
+                if let Some(item) = items.last_mut() {
+                    item.push(c);
+                }
+            }
+        }
+    }
+    items
+}
+
+/// Break a content line into lines of at most 75 bytes, each continuation
+/// starting with a space
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > LINE_LIMIT {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+/// Join folded lines back together
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Split `NAME;PARAM=value:VALUE` into its upper-case name, parameters and
+/// value. Colons inside quoted parameter values don't end the parameters.
+fn split_line(line: &str) -> Option<(String, Params, String)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(index, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(index),
+        _ => None,
+    })?;
+
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_uppercase();
+    let params = parts
+        .filter_map(|param| param.split_once('='))
+        .map(|(name, value)| (name.to_uppercase(), value.trim_matches('"').to_string()))
+        .collect();
+    Some((name, params, value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::entry::parse_time as entry_time;
+
+    #[test]
+    fn test_entries_round_trip() {
+        let mut done = Entry::new("Review, then merge".to_string(), "Work".to_string())
+            .with_started_at(entry_time("2025-07-28 09:00").unwrap())
+            .with_tag("client".to_string(), Some("Acme".to_string()))
+            .with_note(format!("Line one\n{}", "é".repeat(60)));
+        done.finish(entry_time("2025-07-28 10:30").unwrap());
+        let running = Entry::new("Plan sprint".to_string(), "Work".to_string())
+            .with_started_at(entry_time("2025-07-28 11:00").unwrap());
+        let entries = vec![
+            ("Work".to_string(), done.clone()),
+            ("Work".to_string(), running.clone()),
+        ];
+
+        let calendar = write_calendar(&entries, Local::now());
+        assert!(calendar.contains("SUMMARY:Review\\, then merge\r\n"));
+        assert!(calendar.contains("CATEGORIES:client(Acme)\r\n"));
+        assert!(calendar.lines().all(|line| line.len() <= LINE_LIMIT));
+
+        let events = read_events(&calendar, Local::now()).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].uid, Some(done.uuid.to_string()));
+        assert_eq!(events[0].summary, "Review, then merge");
+        assert_eq!(events[0].categories, vec!["client(Acme)"]);
+        assert_eq!(events[0].description, done.note);
+        assert_eq!(events[0].start, Some(done.started_at));
+        assert_eq!(events[0].end, done.finished_at);
+        assert_eq!(events[1].end, None);
+    }
+
+    const MADRID: &str = "BEGIN:VTIMEZONE\r\nTZID:Europe/Madrid\r\n\
+         BEGIN:DAYLIGHT\r\nDTSTART:19810329T020000\r\nTZOFFSETFROM:+0100\r\nTZOFFSETTO:+0200\r\n\
+         RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU\r\nEND:DAYLIGHT\r\n\
+         BEGIN:STANDARD\r\nDTSTART:19961027T030000\r\nTZOFFSETFROM:+0200\r\nTZOFFSETTO:+0100\r\n\
+         RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\n";
+
+    fn utc(date: &str) -> Option<DateTime<Local>> {
+        NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M")
+            .ok()
+            .map(|time| time.and_utc().with_timezone(&Local))
+    }
+
+    fn calendar(events: &str) -> String {
+        format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{MADRID}{events}END:VCALENDAR\r\n")
+    }
+
+    #[test]
+    fn test_read_calendar_meetings() {
+        let events = read_events(
+            &calendar(
+                "BEGIN:VEVENT\r\nUID:abc@example.com\r\nDTSTART;TZID=\"Europe/Madrid\":20250728T090000\r\n\
+                 DURATION:PT1H30M\r\nSUMMARY:Weekly planning with the whole team and\r\n  guests\r\n\
+                 BEGIN:VALARM\r\nDESCRIPTION:Reminder\r\nEND:VALARM\r\nEND:VEVENT\r\n\
+                 BEGIN:VEVENT\r\nDTSTART;TZID=Europe/Madrid:20250113T090000\r\nSUMMARY:Winter\r\nEND:VEVENT\r\n\
+                 BEGIN:VEVENT\r\nDTSTART:20250728T090000\r\nSUMMARY:Floating\r\nEND:VEVENT\r\n\
+                 BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250729\r\nSUMMARY:Holiday\r\nEND:VEVENT\r\n",
+            ),
+            Local::now(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            events[0].summary,
+            "Weekly planning with the whole team and guests"
+        );
+        assert_eq!(events[0].description, None);
+        // Summer time in Madrid is two hours ahead of UTC, winter time one
+        assert_eq!(events[0].start, utc("2025-07-28 07:00"));
+        assert_eq!(events[0].end, utc("2025-07-28 08:30"));
+        assert_eq!(events[1].start, utc("2025-01-13 08:00"));
+        // Without a TZID a time is local wherever the calendar is read
+        assert_eq!(events[2].start, entry_time("2025-07-28 09:00"));
+        assert_eq!(events[3].start, None);
+        assert!(events.iter().all(|event| !event.recurring));
+
+        assert!(read_events("[]", Local::now()).is_err());
+        let undefined =
+            "BEGIN:VEVENT\r\nDTSTART;TZID=America/Lima:20250728T090000\r\nEND:VEVENT\r\n";
+        let error = read_events(&calendar(undefined), Local::now()).unwrap_err();
+        assert!(error.to_string().contains("America/Lima"));
+    }
+
+    #[test]
+    fn test_expand_recurring_events() {
+        let events = read_events(
+            &calendar(
+                // Mondays and Wednesdays, five times, across the change to
+                // winter time; the 29th is cancelled and the 3rd moved
+                "BEGIN:VEVENT\r\nUID:sync@example.com\r\nSUMMARY:Sync\r\n\
+                 DTSTART;TZID=Europe/Madrid:20251022T090000\r\nDTEND;TZID=Europe/Madrid:20251022T093000\r\n\
+                 RRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=5\r\nEXDATE;TZID=Europe/Madrid:20251029T090000\r\n\
+                 END:VEVENT\r\n\
+                 BEGIN:VEVENT\r\nUID:sync@example.com\r\nSUMMARY:Sync (moved)\r\n\
+                 RECURRENCE-ID;TZID=Europe/Madrid:20251103T090000\r\n\
+                 DTSTART;TZID=Europe/Madrid:20251103T120000\r\nDTEND;TZID=Europe/Madrid:20251103T123000\r\n\
+                 END:VEVENT\r\n\
+                 BEGIN:VEVENT\r\nUID:review@example.com\r\nSUMMARY:Review\r\n\
+                 DTSTART:20250131T160000Z\r\nRRULE:FREQ=MONTHLY;BYDAY=-1FR\r\nEND:VEVENT\r\n",
+            ),
+            utc("2025-11-05 09:00").unwrap(),
+        )
+        .unwrap();
+
+        let starts = |summary: &str| -> Vec<Option<DateTime<Local>>> {
+            events
+                .iter()
+                .filter(|event| event.summary == summary)
+                .map(|event| event.start)
+                .collect()
+        };
+        assert_eq!(
+            starts("Sync"),
+            vec![
+                utc("2025-10-22 07:00"),
+                utc("2025-10-27 08:00"),
+                utc("2025-11-05 08:00"),
+            ]
+        );
+        assert_eq!(starts("Sync (moved)"), vec![utc("2025-11-03 11:00")]);
+        // The last Friday of each month, up to the date read to
+        assert_eq!(starts("Review").len(), 10);
+        assert_eq!(starts("Review")[1], utc("2025-02-28 16:00"));
+        assert_eq!(starts("Review")[9], utc("2025-10-31 16:00"));
+
+        let sync = events.iter().find(|event| event.summary == "Sync").unwrap();
+        assert_eq!(sync.uid.as_deref(), Some("sync@example.com"));
+        assert_eq!(sync.end, utc("2025-10-22 07:30"));
+        assert!(events.iter().all(|event| event.recurring));
+
+        let unsupported = "BEGIN:VEVENT\r\nDTSTART:20250728T090000\r\n\
+                           RRULE:FREQ=MONTHLY;BYDAY=MO;BYSETPOS=1\r\nEND:VEVENT\r\n";
+        assert!(read_events(&calendar(unsupported), Local::now()).is_err());
+    }
+
+    #[test]
+    fn test_daily_rule_without_end() {
+        let rule = Rule::parse("FREQ=DAILY;INTERVAL=2;BYDAY=MO,TU,WE,TH,FR").unwrap();
+        let start = NaiveDateTime::parse_from_str("2025-07-01 10:00", "%Y-%m-%d %H:%M").unwrap();
+        let occurrences = rule.occurrences(start, start + Duration::days(9));
+        assert_eq!(
+            occurrences
+                .iter()
+                .map(|time| time.format("%a %d").to_string())
+                .collect::<Vec<_>>(),
+            vec!["Tue 01", "Thu 03", "Mon 07", "Wed 09"]
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("PT1H30M"), Some(Duration::minutes(90)));
+        assert_eq!(
+            parse_duration("P1W2DT3S"),
+            Some(Duration::seconds(9 * 86400 + 3))
+        );
+        assert_eq!(parse_duration("-PT15M"), Some(Duration::minutes(-15)));
+        assert_eq!(parse_duration("PT1D"), None);
+    }
+}
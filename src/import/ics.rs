@@ -0,0 +1,109 @@
+use super::{tag_name, tag_value};
+use crate::ics::{Event, read_events};
+use crate::models::Entry;
+use chrono::Local;
+use color_eyre::Result;
+use uuid::Uuid;
+
+/// Read the events of an iCalendar file as done entries. All-day and
+/// cancelled events are skipped; an event without an end, as written for a
+/// running entry by `--output ics`, stays open. Recurring events become one
+/// entry per occurrence up to now.
+pub(super) fn read(content: &str) -> Result<Vec<Entry>> {
+    Ok(read_events(content, Local::now())?
+        .into_iter()
+        .filter(|event| !event.cancelled)
+        .filter_map(from_event)
+        .collect())
+}
+
+fn from_event(event: Event) -> Option<Entry> {
+    let start = event.start?;
+    let title = match event.summary.trim() {
+        "" => "Calendar event",
+        summary => summary,
+    };
+
+    let mut entry = Entry::new(title.to_string(), String::new()).with_started_at(start);
+    // The occurrences of a recurring event share its UID
+    if let Some(uuid) = event
+        .uid
+        .filter(|_| !event.recurring)
+        .and_then(|uid| Uuid::parse_str(&uid).ok())
+    {
+        entry.uuid = uuid;
+    }
+    // `--output ics` writes `@tag(value)` as `tag(value)`
+    for category in &event.categories {
+        let tag = match category
+            .trim()
+            .strip_suffix(')')
+            .and_then(|c| c.split_once('('))
+        {
+            Some((name, value)) => tag_name(name).map(|name| (name, tag_value(value))),
+            None => tag_name(category).map(|name| (name, None)),
+        };
+        if let Some((name, value)) = tag.filter(|(name, _)| name != "done") {
+            entry.tags.insert(name, value);
+        }
+    }
+    entry.note = event.description.filter(|note| !note.trim().is_empty());
+    if let Some(end) = event.end {
+        entry.finish(end);
+    }
+    Some(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::entry::parse_time;
+
+    #[test]
+    fn test_read_meetings() {
+        let entries = read(
+            "BEGIN:VCALENDAR\r\n\
+             BEGIN:VEVENT\r\nUID:040000008200E00074C5B7101A82E008@example.com\r\n\
+             DTSTART:20250728T090000\r\nDTEND:20250728T093000\r\nSUMMARY:Standup\r\n\
+             CATEGORIES:Meetings,client(Acme)\r\nDESCRIPTION:Room 4\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nDTSTART:20250728T150000\r\nDTEND:20250728T160000\r\n\
+             SUMMARY:Moved\r\nSTATUS:CANCELLED\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250729\r\nSUMMARY:Holiday\r\nEND:VEVENT\r\n\
+             END:VCALENDAR\r\n",
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.description, "Standup");
+        assert_eq!(entry.started_at, parse_time("2025-07-28 09:00").unwrap());
+        assert_eq!(entry.duration(), Some(chrono::Duration::minutes(30)));
+        assert_eq!(
+            entry.tags.keys().collect::<Vec<_>>(),
+            vec!["Meetings", "client", "done"]
+        );
+        assert_eq!(entry.tags["client"].as_deref(), Some("Acme"));
+        assert_eq!(entry.note.as_deref(), Some("Room 4"));
+    }
+
+    #[test]
+    fn test_read_recurring_meeting() {
+        let uid = "6f1c2b0e-8a4d-4c1e-9b7a-2d3e4f5a6b7c";
+        let entries = read(&format!(
+            "BEGIN:VCALENDAR\r\n\
+             BEGIN:VEVENT\r\nUID:{uid}\r\nDTSTART:20250707T090000\r\n\
+             DTEND:20250707T091500\r\nSUMMARY:Standup\r\nRRULE:FREQ=DAILY;COUNT=3\r\n\
+             END:VEVENT\r\nEND:VCALENDAR\r\n"
+        ))
+        .unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            entries[2].started_at,
+            parse_time("2025-07-09 09:00").unwrap()
+        );
+        // Each occurrence is an entry of its own, not the same one three times
+        assert!(entries.iter().all(|entry| entry.uuid.to_string() != uid));
+        assert_ne!(entries[0].uuid, entries[1].uuid);
+    }
+}
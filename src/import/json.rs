@@ -0,0 +1,154 @@
+use crate::models::Entry;
+use crate::storage::parse_title;
+use chrono::{DateTime, FixedOffset, Local};
+use color_eyre::Result;
+use color_eyre::eyre::{WrapErr, eyre};
+use indexmap::IndexMap;
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Export {
+    /// `--output json`
+    Entries(Vec<JsonEntry>),
+    /// `--output json --totals`
+    WithTotals { entries: Vec<JsonEntry> },
+    /// doing's `--output json`
+    Doing { items: Vec<DoingItem> },
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonEntry {
+    section: String,
+    timestamp: DateTime<FixedOffset>,
+    description: String,
+    #[serde(default)]
+    tags: IndexMap<String, Option<String>>,
+    note: Option<String>,
+    uuid: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DoingItem {
+    date: String,
+    end_date: Option<String>,
+    title: String,
+    section: Option<String>,
+    #[serde(default)]
+    note: serde_json::Value,
+    id: Option<String>,
+}
+
+/// Read the JSON written by `--output json`, here or in doing. Ids, sections
+/// and `@done` times are kept.
+pub(super) fn read(content: &str) -> Result<Vec<Entry>> {
+    let export: Export =
+        serde_json::from_str(content).wrap_err("not a daily-log or doing JSON export")?;
+
+    match export {
+        Export::Entries(entries) | Export::WithTotals { entries } => {
+            Ok(entries.into_iter().map(from_json_entry).collect())
+        }
+        Export::Doing { items } => items.into_iter().map(from_doing_item).collect(),
+    }
+}
+
+fn from_json_entry(json: JsonEntry) -> Entry {
+    let mut entry = Entry::new(json.description, json.section)
+        .with_started_at(json.timestamp.with_timezone(&Local));
+    entry.tags = json.tags;
+    entry.sync_finished_at();
+    entry.note = json.note.filter(|note| !note.trim().is_empty());
+    if let Some(uuid) = json.uuid {
+        entry.uuid = uuid;
+    }
+    entry
+}
+
+fn from_doing_item(item: DoingItem) -> Result<Entry> {
+    let (description, tags) = parse_title(&item.title);
+    let mut entry = Entry::new(description, item.section.unwrap_or_default())
+        .with_started_at(parse_doing_time(&item.date)?);
+    entry.tags = tags;
+    entry.sync_finished_at();
+    if entry.finished_at.is_none()
+        && let Some(end) = item.end_date.as_deref().filter(|end| !end.is_empty())
+    {
+        entry.finish(parse_doing_time(end)?);
+    }
+
+    // doing writes notes as a string or as a list of lines
+    let note = match item.note {
+        serde_json::Value::String(note) => note,
+        serde_json::Value::Array(lines) => lines
+            .iter()
+            .filter_map(|line| line.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    };
+    entry.note = (!note.trim().is_empty()).then_some(note);
+
+    if let Some(uuid) = item.id.and_then(|id| Uuid::parse_str(&id).ok()) {
+        entry.uuid = uuid;
+    }
+    Ok(entry)
+}
+
+/// doing writes `2025-07-28 09:00:00 +0200`
+fn parse_doing_time(value: &str) -> Result<DateTime<Local>> {
+    DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S %z")
+        .or_else(|_| DateTime::parse_from_rfc3339(value))
+        .map(|time| time.with_timezone(&Local))
+        .map_err(|_| eyre!("invalid doing time '{value}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_own_json() {
+        let entries = read(
+            r#"{"entries": [{"section": "Work", "timestamp": "2025-07-28T09:00:00+00:00",
+                "description": "Review", "tags": {"code": null, "done": "2025-07-28 10:00"},
+                "note": null, "uuid": "11111111-1111-1111-1111-111111111111"}],
+                "totals": {}}"#,
+        )
+        .unwrap();
+
+        let entry = &entries[0];
+        assert_eq!(entry.section, "Work");
+        assert_eq!(
+            entry.uuid.to_string(),
+            "11111111-1111-1111-1111-111111111111"
+        );
+        assert!(entry.finished_at.is_some());
+        assert!(entry.tags.contains_key("code"));
+    }
+
+    #[test]
+    fn test_read_doing_json() {
+        let entries = read(
+            r#"{"section": "Currently", "items": [{"date": "2025-07-28 09:00:00 +0000",
+                "end_date": "2025-07-28 09:30:00 +0000", "title": "Standup @meeting",
+                "section": "Currently", "note": ["Line one", "Line two"],
+                "id": "2a3b4c5d6e7f48091a2b3c4d5e6f7081", "tags": ["meeting"]}]}"#,
+        )
+        .unwrap();
+
+        let entry = &entries[0];
+        assert_eq!(entry.description, "Standup");
+        assert_eq!(
+            entry.tags.keys().collect::<Vec<_>>(),
+            vec!["meeting", "done"]
+        );
+        assert_eq!(entry.duration(), Some(chrono::Duration::minutes(30)));
+        assert_eq!(entry.note.as_deref(), Some("Line one\nLine two"));
+        assert_eq!(
+            entry.uuid.simple().to_string(),
+            "2a3b4c5d6e7f48091a2b3c4d5e6f7081"
+        );
+    }
+}
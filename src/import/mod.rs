@@ -0,0 +1,110 @@
+//! Readers for the exports of other time trackers and calendars, and of
+//! daily-log and doing files.
+//!
+//! Each reader turns a file into entries with `@done` set from the end time.
+//! Entries keep the section of their source, or an empty section when the
+//! source has none, and the caller decides where they go.
+
+mod ics;
+mod json;
+mod taskpaper;
+mod timewarrior;
+mod toggl;
+mod watson;
+
+use crate::models::Entry;
+use color_eyre::Result;
+use color_eyre::eyre::eyre;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportType {
+    Timewarrior,
+    Watson,
+    TogglCsv,
+    Json,
+    TaskPaper,
+    Ics,
+}
+
+impl ImportType {
+    pub const NAMES: [&str; 6] = [
+        "timewarrior",
+        "watson",
+        "toggl-csv",
+        "json",
+        "taskpaper",
+        "ics",
+    ];
+
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "timewarrior" | "timew" => Ok(Self::Timewarrior),
+            "watson" => Ok(Self::Watson),
+            "toggl-csv" | "toggl" => Ok(Self::TogglCsv),
+            "json" => Ok(Self::Json),
+            "taskpaper" | "doing" => Ok(Self::TaskPaper),
+            "ics" | "ical" | "icalendar" => Ok(Self::Ics),
+            _ => Err(eyre!(
+                "unknown import type '{name}': use {}",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Read the entries in `content`, an export of the given type
+pub fn read_entries(kind: ImportType, content: &str) -> Result<Vec<Entry>> {
+    match kind {
+        ImportType::Timewarrior => timewarrior::read(content),
+        ImportType::Watson => watson::read(content),
+        ImportType::TogglCsv => toggl::read(content),
+        ImportType::Json => json::read(content),
+        ImportType::TaskPaper => taskpaper::read(content),
+        ImportType::Ics => ics::read(content),
+    }
+}
+
+/// A tag name from a project, client or tag in another tracker: runs of
+/// characters a tag can't hold become `_`
+fn tag_name(name: &str) -> Option<String> {
+    let mut tag = String::new();
+    for c in name.trim().trim_start_matches('@').chars() {
+        if c.is_alphanumeric() || c == '_' || c == '-' || c == '.' {
+            tag.push(c);
+        } else if !tag.ends_with('_') {
+            tag.push('_');
+        }
+    }
+    let tag = tag.trim_matches(|c| c == '_' || c == '-' || c == '.');
+    (!tag.is_empty()).then(|| tag.to_string())
+}
+
+/// A value for `@tag(value)`; parentheses would end the value early
+fn tag_value(value: &str) -> Option<String> {
+    let value = value.trim().replace(['(', ')'], "");
+    (!value.is_empty()).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_names_from_other_trackers() {
+        assert_eq!(tag_name("client work").as_deref(), Some("client_work"));
+        assert_eq!(tag_name("@Acme, Inc.").as_deref(), Some("Acme_Inc"));
+        assert_eq!(tag_name("v2.1-beta").as_deref(), Some("v2.1-beta"));
+        assert_eq!(tag_name(" !! "), None);
+        assert_eq!(tag_value("Acme (EU)").as_deref(), Some("Acme EU"));
+    }
+
+    #[test]
+    fn test_parse_import_type() {
+        assert_eq!(ImportType::parse("timew").unwrap(), ImportType::Timewarrior);
+        assert_eq!(
+            ImportType::parse("Toggl-CSV").unwrap(),
+            ImportType::TogglCsv
+        );
+        assert!(ImportType::parse("harvest").is_err());
+    }
+}
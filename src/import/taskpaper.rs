@@ -0,0 +1,33 @@
+use crate::models::Entry;
+use crate::storage::parse_taskpaper_content;
+use color_eyre::Result;
+use std::path::Path;
+
+/// Read another daily-log or doing file, keeping sections and ids
+pub(super) fn read(content: &str) -> Result<Vec<Entry>> {
+    let doing_file = parse_taskpaper_content(Path::new(""), content)?;
+    Ok(doing_file.get_all_entries().into_iter().cloned().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_doing_file() {
+        let entries = read(
+            "Currently:\n\t- 2025-07-28 09:00 | Review @done(2025-07-28 10:00) <2a3b4c5d6e7f48091a2b3c4d5e6f7081>\n\
+             Later:\n\t- 2025-07-28 11:00 | Plan sprint\n",
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].section, "Currently");
+        assert_eq!(
+            entries[0].uuid.simple().to_string(),
+            "2a3b4c5d6e7f48091a2b3c4d5e6f7081"
+        );
+        assert!(entries[0].is_done());
+        assert_eq!(entries[1].section, "Later");
+    }
+}
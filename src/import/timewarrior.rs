@@ -0,0 +1,91 @@
+use super::tag_name;
+use crate::models::Entry;
+use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use color_eyre::Result;
+use color_eyre::eyre::{WrapErr, eyre};
+use serde::Deserialize;
+
+/// An interval from `timew export`
+#[derive(Debug, Deserialize)]
+struct Interval {
+    start: String,
+    end: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    annotation: Option<String>,
+}
+
+/// Read the JSON written by `timew export`. The annotation is the title, or
+/// the tags when there is none; every tag is also added as a tag.
+pub(super) fn read(content: &str) -> Result<Vec<Entry>> {
+    let intervals: Vec<Interval> =
+        serde_json::from_str(content).wrap_err("not a Timewarrior export")?;
+
+    intervals
+        .into_iter()
+        .map(|interval| {
+            let description = interval
+                .annotation
+                .filter(|annotation| !annotation.trim().is_empty())
+                .unwrap_or_else(|| interval.tags.join(", "));
+            let mut entry = Entry::new(description.trim().to_string(), String::new())
+                .with_started_at(parse_time(&interval.start)?);
+            for tag in interval.tags.iter().filter_map(|tag| tag_name(tag)) {
+                entry.tags.insert(tag, None);
+            }
+            if let Some(end) = &interval.end {
+                entry.finish(parse_time(end)?);
+            }
+            Ok(entry)
+        })
+        .collect()
+}
+
+/// Timewarrior writes UTC times as `20250728T090000Z`
+fn parse_time(value: &str) -> Result<DateTime<Local>> {
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ")
+        .map(|time| time.and_utc().with_timezone(&Local))
+        .or_else(|_| {
+            value
+                .parse::<DateTime<Utc>>()
+                .map(|time| time.with_timezone(&Local))
+        })
+        .map_err(|_| eyre!("invalid Timewarrior time '{value}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_read_export() {
+        let entries = read(
+            r#"[
+                {"id":2,"start":"20250728T090000Z","end":"20250728T103000Z","tags":["client work","rust"],"annotation":"Parser fixes"},
+                {"id":1,"start":"20250728T110000Z","tags":["meeting"]}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(entries[0].description, "Parser fixes");
+        assert_eq!(
+            entries[0].tags.keys().collect::<Vec<_>>(),
+            vec!["client_work", "rust", "done"]
+        );
+        assert_eq!(
+            entries[0].started_at,
+            Utc.with_ymd_and_hms(2025, 7, 28, 9, 0, 0).unwrap()
+        );
+        assert_eq!(entries[0].duration(), Some(chrono::Duration::minutes(90)));
+
+        // Still running, and titled by its tags
+        assert_eq!(entries[1].description, "meeting");
+        assert!(entries[1].is_running());
+    }
+
+    #[test]
+    fn test_rejects_other_json() {
+        assert!(read(r#"{"frames": []}"#).is_err());
+    }
+}
@@ -0,0 +1,163 @@
+use super::{tag_name, tag_value};
+use crate::models::Entry;
+use chrono::{DateTime, Local, NaiveDateTime};
+use color_eyre::Result;
+use color_eyre::eyre::eyre;
+
+/// Read a Toggl Track detailed report exported as CSV. The project becomes a
+/// tag and the client a `@client(name)` tag.
+pub(super) fn read(content: &str) -> Result<Vec<Entry>> {
+    let mut rows = parse_csv(content.trim_start_matches('\u{feff}')).into_iter();
+    let header: Vec<String> = rows
+        .next()
+        .ok_or_else(|| eyre!("the Toggl export is empty"))?
+        .iter()
+        .map(|name| name.trim().to_lowercase())
+        .collect();
+    let column = |name: &str| header.iter().position(|column| column == name);
+    let required = |name: &str| {
+        column(name).ok_or_else(|| eyre!("not a Toggl CSV export: no '{name}' column"))
+    };
+
+    let description = required("description")?;
+    let start_date = required("start date")?;
+    let start_time = required("start time")?;
+    let end_date = column("end date");
+    let end_time = column("end time");
+    let project = column("project");
+    let client = column("client");
+    let tags = column("tags");
+
+    let mut entries = Vec::new();
+    for (line, row) in rows.enumerate() {
+        if row.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|index| row.get(index))
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+        };
+        let time = |date: Option<usize>, time: Option<usize>| -> Result<Option<DateTime<Local>>> {
+            let (Some(date), Some(time)) = (field(date), field(time)) else {
+                return Ok(None);
+            };
+            parse_time(date, time)
+                .map(Some)
+                .ok_or_else(|| eyre!("row {}: invalid time '{date} {time}'", line + 2))
+        };
+
+        let start = time(Some(start_date), Some(start_time))?
+            .ok_or_else(|| eyre!("row {}: no start time", line + 2))?;
+        let title = field(Some(description))
+            .or(field(project))
+            .unwrap_or("Toggl entry");
+
+        let mut entry = Entry::new(title.to_string(), String::new()).with_started_at(start);
+        if let Some(project) = field(project).and_then(tag_name) {
+            entry.tags.insert(project, None);
+        }
+        if let Some(client) = field(client).and_then(tag_value) {
+            entry.tags.insert("client".to_string(), Some(client));
+        }
+        for tag in field(tags)
+            .into_iter()
+            .flat_map(|tags| tags.split(','))
+            .filter_map(tag_name)
+        {
+            entry.tags.insert(tag, None);
+        }
+        if let Some(end) = time(end_date, end_time)? {
+            entry.finish(end);
+        }
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+fn parse_time(date: &str, time: &str) -> Option<DateTime<Local>> {
+    let value = format!("{date} {time}");
+    [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%m/%d/%Y %H:%M:%S",
+        "%d.%m.%Y %H:%M:%S",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(&value, format).ok())?
+    .and_local_timezone(Local)
+    .earliest()
+}
+
+/// Split CSV into rows of fields, following RFC 4180 quoting
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = "\u{feff}User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags\r\n\
+        Ana,ana@example.com,Acme (EU),Website Redesign,,\"Header, footer\",Yes,2025-07-28,09:00:00,2025-07-28,10:15:00,01:15:00,\"design, review\"\r\n\
+        Ana,ana@example.com,,,,,No,2025-07-28,11:00:00,,,,\r\n";
+
+    #[test]
+    fn test_read_detailed_report() {
+        let entries = read(EXPORT).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let entry = &entries[0];
+        assert_eq!(entry.description, "Header, footer");
+        assert_eq!(
+            entry.tags.keys().collect::<Vec<_>>(),
+            vec!["Website_Redesign", "client", "design", "review", "done"]
+        );
+        assert_eq!(entry.tags["client"].as_deref(), Some("Acme EU"));
+        assert_eq!(entry.duration(), Some(chrono::Duration::minutes(75)));
+
+        assert_eq!(entries[1].description, "Toggl entry");
+        assert!(entries[1].is_running());
+    }
+
+    #[test]
+    fn test_missing_columns_are_an_error() {
+        assert!(read("Date,Hours\n2025-07-28,1\n").is_err());
+    }
+
+    #[test]
+    fn test_quoted_fields() {
+        assert_eq!(
+            parse_csv("a,\"b \"\"c\"\"\",\"d\ne\"\n1,2,3"),
+            vec![vec!["a", "b \"c\"", "d\ne"], vec!["1", "2", "3"]]
+        );
+    }
+}
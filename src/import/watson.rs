@@ -0,0 +1,129 @@
+use super::tag_name;
+use crate::models::Entry;
+use chrono::{DateTime, Local, TimeZone};
+use color_eyre::Result;
+use color_eyre::eyre::{WrapErr, eyre};
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// A frame from `watson log --json`, or a row of Watson's own `frames` file
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Frame {
+    Log {
+        id: Option<String>,
+        project: String,
+        start: DateTime<chrono::FixedOffset>,
+        stop: Option<DateTime<chrono::FixedOffset>>,
+        #[serde(default)]
+        tags: Vec<String>,
+        note: Option<String>,
+    },
+    /// `[start, stop, project, id, tags, updated_at]` with Unix times
+    Row(
+        i64,
+        i64,
+        String,
+        String,
+        #[serde(default)] Vec<String>,
+        #[serde(default)] serde::de::IgnoredAny,
+    ),
+}
+
+/// Read `watson log --json` output or Watson's `frames` file. The project is
+/// the title and the frame id, a UUID without dashes, is kept as the entry id.
+pub(super) fn read(content: &str) -> Result<Vec<Entry>> {
+    let frames: Vec<Frame> = serde_json::from_str(content).wrap_err("not a Watson export")?;
+
+    frames
+        .into_iter()
+        .map(|frame| {
+            let (id, project, start, stop, tags, note) = match frame {
+                Frame::Log {
+                    id,
+                    project,
+                    start,
+                    stop,
+                    tags,
+                    note,
+                } => (
+                    id,
+                    project,
+                    start.with_timezone(&Local),
+                    stop.map(|stop| stop.with_timezone(&Local)),
+                    tags,
+                    note,
+                ),
+                Frame::Row(start, stop, project, id, tags, _) => (
+                    Some(id),
+                    project,
+                    unix_time(start)?,
+                    Some(unix_time(stop)?),
+                    tags,
+                    None,
+                ),
+            };
+
+            let mut entry =
+                Entry::new(project.trim().to_string(), String::new()).with_started_at(start);
+            if let Some(uuid) = id.and_then(|id| Uuid::parse_str(&id).ok()) {
+                entry.uuid = uuid;
+            }
+            for tag in tags.iter().filter_map(|tag| tag_name(tag)) {
+                entry.tags.insert(tag, None);
+            }
+            entry.note = note.filter(|note| !note.trim().is_empty());
+            if let Some(stop) = stop {
+                entry.finish(stop);
+            }
+            Ok(entry)
+        })
+        .collect()
+}
+
+fn unix_time(seconds: i64) -> Result<DateTime<Local>> {
+    Local
+        .timestamp_opt(seconds, 0)
+        .single()
+        .ok_or_else(|| eyre!("invalid Watson time {seconds}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_log_json() {
+        let entries = read(
+            r#"[{"id":"c2ee8b9f4b1546a49cc2a6b9ad1e0e1b","project":"daily-log",
+                 "start":"2025-07-28T09:00:00+02:00","stop":"2025-07-28T09:45:00+02:00",
+                 "tags":["code","review"],"note":"PR 42"}]"#,
+        )
+        .unwrap();
+
+        let entry = &entries[0];
+        assert_eq!(entry.description, "daily-log");
+        assert_eq!(
+            entry.uuid,
+            Uuid::parse_str("c2ee8b9f-4b15-46a4-9cc2-a6b9ad1e0e1b").unwrap()
+        );
+        assert_eq!(
+            entry.tags.keys().collect::<Vec<_>>(),
+            vec!["code", "review", "done"]
+        );
+        assert_eq!(entry.note.as_deref(), Some("PR 42"));
+        assert_eq!(entry.duration(), Some(chrono::Duration::minutes(45)));
+    }
+
+    #[test]
+    fn test_read_frames_file() {
+        let entries = read(
+            r#"[[1753693200, 1753696800, "website", "0a1b2c3d4e5f40718293a4b5c6d7e8f9", ["design"], 1753696900]]"#,
+        )
+        .unwrap();
+
+        assert_eq!(entries[0].description, "website");
+        assert_eq!(entries[0].started_at.timestamp(), 1753693200);
+        assert_eq!(entries[0].duration(), Some(chrono::Duration::hours(1)));
+    }
+}
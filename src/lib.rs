@@ -1,11 +1,20 @@
 pub mod app;
+pub mod autotag;
 pub mod cli;
 pub mod commands;
 pub mod display;
+pub mod editor;
 pub mod filtering;
+pub mod ics;
+pub mod import;
 pub mod models;
+pub mod picker;
+pub mod report;
 pub mod services;
 pub mod storage;
+pub mod template;
+pub mod timesheet;
+pub mod totals;
 
 #[cfg(test)]
 pub mod test_utils;
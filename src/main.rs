@@ -8,6 +8,16 @@ fn main() -> color_eyre::Result<()> {
 
     let cli = Cli::parse();
 
+    // The TUI owns the terminal, so parse warnings are only printed for CLI commands
+    if !matches!(cli.command, Some(Commands::Tui { .. })) {
+        daily_log::storage::taskpaper::set_report_warnings(true);
+    }
+
+    // Report a broken config up front; `config` itself stays usable to fix it
+    if !matches!(cli.command, Some(Commands::Config { .. })) {
+        daily_log::storage::Config::try_load()?;
+    }
+
     match cli.command {
         Some(Commands::Now {
             entry,
@@ -29,7 +39,7 @@ fn main() -> color_eyre::Result<()> {
                 from,
                 editor,
                 ask,
-                _noauto: noauto,
+                noauto,
             })?;
         }
         Some(Commands::Last) => {
@@ -42,8 +52,9 @@ fn main() -> color_eyre::Result<()> {
             commands::handle_today(section)?;
         }
         Some(Commands::Tui { section }) => {
+            let app = App::new_with_section(section)?;
             let terminal = ratatui::init();
-            let result = App::new_with_section(section).run(terminal);
+            let result = app.run(terminal);
             ratatui::restore();
             result?;
         }
@@ -77,7 +88,7 @@ fn main() -> color_eyre::Result<()> {
                 remove,
                 unfinished,
                 _date: date,
-                _noauto: noauto,
+                noauto,
             })?;
         }
         Some(Commands::Finish {
@@ -150,7 +161,7 @@ fn main() -> color_eyre::Result<()> {
                 remove,
                 unfinished,
                 _date: date,
-                _noauto: noauto,
+                noauto,
             })?;
         }
         Some(Commands::Cancel {
@@ -218,7 +229,7 @@ fn main() -> color_eyre::Result<()> {
                 noauto,
                 ask,
                 back,
-                _bool_op: bool_op,
+                bool_op,
                 case,
                 editor,
                 interactive,
@@ -228,7 +239,7 @@ fn main() -> color_eyre::Result<()> {
                 sections,
                 search,
                 tag,
-                _val: val,
+                val,
                 exact,
             })?;
         }
@@ -255,8 +266,8 @@ fn main() -> color_eyre::Result<()> {
         }) => {
             commands::handle_tag(commands::TagOptions {
                 tags,
-                _autotag: autotag,
-                _bool_op: bool_op,
+                autotag,
+                bool_op,
                 count,
                 case,
                 date,
@@ -271,14 +282,14 @@ fn main() -> color_eyre::Result<()> {
                 tag,
                 unfinished,
                 value,
-                _val: val,
+                val,
                 exact,
             })?;
         }
         Some(Commands::Note {
             note,
             ask,
-            bool_op: _,
+            bool_op,
             case,
             editor,
             interactive,
@@ -287,7 +298,7 @@ fn main() -> color_eyre::Result<()> {
             sections,
             search,
             tag,
-            val: _,
+            val,
             exact,
         }) => {
             commands::handle_note(
@@ -295,6 +306,8 @@ fn main() -> color_eyre::Result<()> {
                     sections,
                     search,
                     tag,
+                    val,
+                    bool_op,
                     case,
                     exact,
                     not,
@@ -330,7 +343,7 @@ fn main() -> color_eyre::Result<()> {
                 noauto,
                 ask,
                 back,
-                _bool_op: bool_op,
+                bool_op,
                 case,
                 editor,
                 interactive,
@@ -340,7 +353,7 @@ fn main() -> color_eyre::Result<()> {
                 sections,
                 search,
                 tag,
-                _val: val,
+                val,
                 exact,
             })?;
         }
@@ -361,7 +374,7 @@ fn main() -> color_eyre::Result<()> {
             exact,
         }) => {
             commands::handle_mark(commands::MarkOptions {
-                _bool_op: bool_op,
+                bool_op,
                 count,
                 case,
                 date,
@@ -373,7 +386,7 @@ fn main() -> color_eyre::Result<()> {
                 search,
                 tag,
                 unfinished,
-                _val: val,
+                val,
                 exact,
             })?;
         }
@@ -395,7 +408,7 @@ fn main() -> color_eyre::Result<()> {
         }) => {
             // Flag is an alias for mark
             commands::handle_mark(commands::MarkOptions {
-                _bool_op: bool_op,
+                bool_op,
                 count,
                 case,
                 date,
@@ -407,7 +420,7 @@ fn main() -> color_eyre::Result<()> {
                 search,
                 tag,
                 unfinished,
-                _val: val,
+                val,
                 exact,
             })?;
         }
@@ -429,7 +442,7 @@ fn main() -> color_eyre::Result<()> {
         }) => {
             commands::handle_reset(commands::ResetOptions {
                 date_string,
-                _bool_op: bool_op,
+                bool_op,
                 case,
                 from,
                 interactive,
@@ -440,7 +453,7 @@ fn main() -> color_eyre::Result<()> {
                 search,
                 took,
                 tag,
-                _val: val,
+                val,
                 exact,
             })?;
         }
@@ -463,7 +476,7 @@ fn main() -> color_eyre::Result<()> {
             // Begin is an alias for reset
             commands::handle_reset(commands::ResetOptions {
                 date_string,
-                _bool_op: bool_op,
+                bool_op,
                 case,
                 from,
                 interactive,
@@ -474,7 +487,7 @@ fn main() -> color_eyre::Result<()> {
                 search,
                 took,
                 tag,
-                _val: val,
+                val,
                 exact,
             })?;
         }
@@ -541,13 +554,13 @@ fn main() -> color_eyre::Result<()> {
                 commands::ShowUIOptions {
                     interactive,
                     menu,
-                    _editor: editor,
+                    editor,
                 },
                 commands::ShowConfigOptions {
-                    _config_template: config_template,
-                    _save: save,
-                    _template: template,
-                    _title: title,
+                    config_template,
+                    save,
+                    template,
+                    title,
                 },
             )?;
         }
@@ -606,13 +619,13 @@ fn main() -> color_eyre::Result<()> {
                 commands::GrepActionOptions {
                     delete,
                     interactive,
-                    _editor: editor,
+                    editor,
                 },
                 commands::GrepConfigOptions {
-                    _config_template: config_template,
-                    _save: save,
-                    _template: template,
-                    _title: title,
+                    config_template,
+                    save,
+                    template,
+                    title,
                 },
             )?;
         }
@@ -672,13 +685,13 @@ fn main() -> color_eyre::Result<()> {
                 commands::GrepActionOptions {
                     delete,
                     interactive,
-                    _editor: editor,
+                    editor,
                 },
                 commands::GrepConfigOptions {
-                    _config_template: config_template,
-                    _save: save,
-                    _template: template,
-                    _title: title,
+                    config_template,
+                    save,
+                    template,
+                    title,
                 },
             )?;
         }
@@ -732,10 +745,10 @@ fn main() -> color_eyre::Result<()> {
                     totals,
                 },
                 commands::OnConfigOptions {
-                    _config_template: config_template,
-                    _save: save,
-                    _template: template,
-                    _title: title,
+                    config_template,
+                    save,
+                    template,
+                    title,
                 },
             )?;
         }
@@ -783,10 +796,10 @@ fn main() -> color_eyre::Result<()> {
                     totals,
                 },
                 commands::SinceConfigOptions {
-                    _config_template: config_template,
-                    _save: save,
-                    _template: template,
-                    _title: title,
+                    config_template,
+                    save,
+                    template,
+                    title,
                 },
             )?;
         }
@@ -810,21 +823,69 @@ fn main() -> color_eyre::Result<()> {
             commands::handle_yesterday(commands::YesterdayOptions {
                 after,
                 before,
-                _config_template: config_template,
+                config_template,
                 duration,
                 from,
                 output,
                 only_timed,
                 sections,
-                _save: save,
+                save,
                 times,
                 tag_order,
                 tag_sort,
-                _template: template,
-                _title: title,
+                template,
+                title,
                 totals,
             })?;
         }
+        Some(Commands::View { name }) => {
+            commands::handle_view(&name)?;
+        }
+        Some(Commands::Views { action }) => {
+            commands::handle_views(action)?;
+        }
+        Some(Commands::Report {
+            week,
+            month,
+            from,
+            output,
+            sections,
+            tag,
+            title,
+        }) => {
+            commands::handle_report(commands::ReportOptions {
+                week,
+                month,
+                from,
+                output,
+                sections,
+                tag,
+                title,
+            })?;
+        }
+        Some(Commands::Invoice {
+            week,
+            month,
+            from,
+            output,
+            by,
+            client,
+            sections,
+            tag,
+            title,
+        }) => {
+            commands::handle_invoice(commands::InvoiceOptions {
+                week,
+                month,
+                from,
+                output,
+                by,
+                client,
+                sections,
+                tag,
+                title,
+            })?;
+        }
         Some(Commands::Sections { action }) => {
             commands::handle_sections(action)?;
         }
@@ -848,7 +909,7 @@ fn main() -> color_eyre::Result<()> {
                 target,
                 after,
                 before,
-                _bool_op: bool_op,
+                bool_op,
                 case,
                 from,
                 keep,
@@ -861,6 +922,23 @@ fn main() -> color_eyre::Result<()> {
                 exact,
             })?;
         }
+        Some(Commands::Import {
+            file,
+            kind,
+            section,
+            dry_run,
+            prefix,
+            tag,
+        }) => {
+            commands::handle_import(commands::ImportOptions {
+                file,
+                kind,
+                section,
+                dry_run,
+                prefix,
+                tag,
+            })?;
+        }
         Some(Commands::Rotate {
             before,
             bool_op,
@@ -875,7 +953,7 @@ fn main() -> color_eyre::Result<()> {
         }) => {
             commands::handle_rotate(commands::RotateOptions {
                 before,
-                _bool_op: bool_op,
+                bool_op,
                 case,
                 keep,
                 not,
@@ -886,9 +964,21 @@ fn main() -> color_eyre::Result<()> {
                 exact,
             })?;
         }
+        Some(Commands::Undo { count, force }) => {
+            commands::handle_undo(count, force)?;
+        }
+        Some(Commands::Redo { count, force }) => {
+            commands::handle_redo(count, force)?;
+        }
+        Some(Commands::History { count }) => {
+            commands::handle_history(count)?;
+        }
+        Some(Commands::Config { action }) => {
+            commands::handle_config(action)?;
+        }
         Some(Commands::Tags {
             max_count,
-            bool_op: _,
+            bool_op,
             counts,
             case,
             interactive,
@@ -908,6 +998,7 @@ fn main() -> color_eyre::Result<()> {
                     search,
                     tag,
                     val,
+                    bool_op,
                     case,
                     exact,
                     not,
@@ -934,7 +1025,7 @@ fn main() -> color_eyre::Result<()> {
                     from: None,
                     editor: false,
                     ask: false,
-                    _noauto: false,
+                    noauto: false,
                 })?;
             } else {
                 // If no command and no task words, show recent entries
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Layout used when writing entries. daily-log's native format indents
+/// entries with a space and uses hyphenated ids; Ruby doing uses tabs and
+/// unhyphenated ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Dialect {
+    #[default]
+    DailyLog,
+    Doing,
+}
+
+impl Dialect {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "daily-log" | "dailylog" | "daily_log" => Some(Dialect::DailyLog),
+            "doing" => Some(Dialect::Doing),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Dialect::DailyLog => "daily-log",
+            Dialect::Doing => "doing",
+        }
+    }
+
+    pub fn entry_prefix(&self) -> &str {
+        match self {
+            Dialect::DailyLog => " - ",
+            Dialect::Doing => "\t- ",
+        }
+    }
+
+    pub fn note_prefix(&self) -> &str {
+        match self {
+            Dialect::DailyLog => "  ",
+            Dialect::Doing => "\t\t",
+        }
+    }
+
+    /// daily-log separates sections with a blank line; doing writes them back to back
+    pub fn blank_line_between_sections(&self) -> bool {
+        matches!(self, Dialect::DailyLog)
+    }
+
+    pub fn format_id(&self, uuid: &Uuid) -> String {
+        match self {
+            Dialect::DailyLog => uuid.as_hyphenated().to_string(),
+            Dialect::Doing => uuid.as_simple().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dialect() {
+        assert_eq!(Dialect::parse("doing"), Some(Dialect::Doing));
+        assert_eq!(Dialect::parse("Daily-Log"), Some(Dialect::DailyLog));
+        assert_eq!(Dialect::parse("ruby"), None);
+    }
+
+    #[test]
+    fn test_format_id() {
+        let uuid = Uuid::parse_str("7a1185c6-0241-52ac-0771-83f31c40acdd").unwrap();
+        assert_eq!(
+            Dialect::DailyLog.format_id(&uuid),
+            "7a1185c6-0241-52ac-0771-83f31c40acdd"
+        );
+        assert_eq!(
+            Dialect::Doing.format_id(&uuid),
+            "7a1185c6024152ac077183f31c40acdd"
+        );
+    }
+}
@@ -1,20 +1,76 @@
-use super::{Entry, Section};
+use super::{Dialect, Entry, Section};
 use chrono::{DateTime, Local};
+use indexmap::IndexMap;
 use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Fingerprint of the file contents at the time it was parsed, used to
+/// detect changes made by other processes before saving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskState {
+    Missing,
+    Present { len: u64, hash: u64 },
+}
+
+impl DiskState {
+    pub fn from_contents(contents: &[u8]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        contents.hash(&mut hasher);
+        DiskState::Present {
+            len: contents.len() as u64,
+            hash: hasher.finish(),
+        }
+    }
+}
+
+/// A line `parse_taskpaper` couldn't interpret, kept verbatim so saving never drops it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownLine {
+    /// 1-based line number in the file it was read from
+    pub line_number: usize,
+    /// Section the line appeared in, `None` before the first section header
+    pub section: Option<String>,
+    /// Entry the line followed, `None` if it came before the section's first entry
+    pub after: Option<Uuid>,
+    pub text: String,
+}
+
+impl UnknownLine {
+    /// Blank lines are kept in place too, but aren't worth a warning
+    pub fn is_blank(&self) -> bool {
+        self.text.trim().is_empty()
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct DoingFile {
     pub path: PathBuf,
-    pub sections: HashMap<String, Vec<Entry>>,
+    pub sections: IndexMap<String, Vec<Entry>>,
+    /// `None` for files built in memory, which are saved without a conflict check
+    pub disk_state: Option<DiskState>,
+    pub unknown_lines: Vec<UnknownLine>,
+    /// Blank lines read between each section and the next header, written
+    /// back instead of the dialect's separator
+    pub section_gaps: HashMap<String, usize>,
+    /// Style used when writing entries back out
+    pub dialect: Dialect,
 }
 
 impl DoingFile {
     pub fn new(path: PathBuf) -> Self {
-        let mut sections = HashMap::new();
+        let mut sections = IndexMap::new();
         sections.insert(Section::Currently.as_str().to_string(), Vec::new());
 
-        Self { path, sections }
+        Self {
+            path,
+            sections,
+            disk_state: None,
+            unknown_lines: Vec::new(),
+            section_gaps: HashMap::new(),
+            dialect: Dialect::default(),
+        }
     }
 
     pub fn add_entry(&mut self, entry: Entry) {
@@ -29,10 +85,41 @@ impl DoingFile {
         self.sections.entry(section).or_default().push(entry);
     }
 
+    /// Replace the entry with the same id, moving it if its section changed.
+    /// Returns false if no such entry exists.
+    pub fn update_entry(&mut self, entry: Entry) -> bool {
+        let Some((section, pos)) = self.sections.iter().find_map(|(section, entries)| {
+            entries
+                .iter()
+                .position(|e| e.uuid == entry.uuid)
+                .map(|pos| (section.clone(), pos))
+        }) else {
+            return false;
+        };
+
+        let Some(entries) = self.sections.get_mut(&section) else {
+            return false;
+        };
+        if entry.section == section {
+            entries[pos] = entry;
+        } else {
+            entries.remove(pos);
+            self.add_entry(entry);
+        }
+        true
+    }
+
     pub fn get_entries(&self, section: &str) -> Option<&Vec<Entry>> {
         self.sections.get(section)
     }
 
+    pub fn get_entry(&self, uuid: Uuid) -> Option<&Entry> {
+        self.sections
+            .values()
+            .flat_map(|entries| entries.iter())
+            .find(|entry| entry.uuid == uuid)
+    }
+
     pub fn get_all_entries(&self) -> Vec<&Entry> {
         self.sections
             .values()
@@ -42,53 +129,95 @@ impl DoingFile {
 
     pub fn get_recent_entries(&self, count: usize) -> Vec<&Entry> {
         let mut all_entries: Vec<&Entry> = self.get_all_entries();
-        all_entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        all_entries.sort_by_key(|e| std::cmp::Reverse(e.started_at));
         all_entries.into_iter().take(count).collect()
     }
 
     pub fn get_entries_since(&self, since: DateTime<Local>) -> Vec<&Entry> {
         self.get_all_entries()
             .into_iter()
-            .filter(|entry| entry.timestamp >= since)
+            .filter(|entry| entry.started_at >= since)
             .collect()
     }
 
     pub fn get_last_entry(&self) -> Option<&Entry> {
         self.get_all_entries()
             .into_iter()
-            .max_by_key(|entry| entry.timestamp)
+            .max_by_key(|entry| entry.started_at)
     }
 
     pub fn to_taskpaper(&self) -> String {
         let mut result = String::new();
 
-        // Sort sections to ensure consistent output
-        let mut sorted_sections: Vec<(&String, &Vec<Entry>)> = self.sections.iter().collect();
-        sorted_sections.sort_by_key(|(name, _)| {
-            // Currently always comes first, then alphabetical
-            if *name == "Currently" {
-                ("", name.as_str())
-            } else {
-                ("z", name.as_str())
-            }
-        });
-
-        for (section_name, entries) in sorted_sections {
-            result.push_str(&Section::parse(section_name).to_taskpaper());
+        for line in self.unknown_lines.iter().filter(|l| l.section.is_none()) {
+            result.push_str(&line.text);
             result.push('\n');
+        }
+
+        // Sections keep the order they were read or created in
+        for (section_name, entries) in &self.sections {
+            result.push_str(section_name);
+            result.push_str(":\n");
 
+            let section_lines: Vec<&UnknownLine> = self
+                .unknown_lines
+                .iter()
+                .filter(|l| l.section.as_deref() == Some(section_name.as_str()))
+                .collect();
+            let push_lines_after = |result: &mut String, after: Option<Uuid>| {
+                for line in section_lines.iter().filter(|l| l.after == after) {
+                    result.push_str(&line.text);
+                    result.push('\n');
+                }
+            };
+
+            push_lines_after(&mut result, None);
             for entry in entries {
-                result.push_str(&entry.to_taskpaper());
+                result.push_str(&entry.to_taskpaper_as(self.dialect));
                 result.push('\n');
+                push_lines_after(&mut result, Some(entry.uuid));
+            }
+
+            // Lines whose entry was removed or moved stay at the end of the
+            // section, except blank lines, which only separated that entry
+            for line in &section_lines {
+                if !line.is_blank()
+                    && line
+                        .after
+                        .is_some_and(|uuid| !entries.iter().any(|e| e.uuid == uuid))
+                {
+                    result.push_str(&line.text);
+                    result.push('\n');
+                }
             }
 
-            // Add blank line between sections
-            if !result.ends_with("\n\n") {
+            // Blank lines between sections, as read or as the dialect has them
+            match self.section_gaps.get(section_name) {
+                Some(gap) => result.push_str(&"\n".repeat(*gap)),
+                None if self.dialect.blank_line_between_sections() && !result.ends_with("\n\n") => {
+                    result.push('\n')
+                }
+                None => {}
+            }
+        }
+
+        // Lines from sections that no longer exist go at the end
+        for line in &self.unknown_lines {
+            if line
+                .section
+                .as_ref()
+                .is_some_and(|section| !self.sections.contains_key(section))
+            {
+                result.push_str(&line.text);
                 result.push('\n');
             }
         }
 
-        result.trim_end().to_string()
+        let mut result = result.trim_end().to_string();
+        if !result.is_empty() {
+            result.push('\n');
+        }
+        result
     }
 }
 
@@ -136,4 +265,40 @@ mod tests {
         assert!(output.contains("Archive:"));
         assert!(output.contains(" | Archived task <"));
     }
+
+    #[test]
+    fn test_unknown_lines_follow_their_anchor() {
+        let mut file = DoingFile::new(PathBuf::from("test.taskpaper"));
+        let first = Entry::new("First".to_string(), "Currently".to_string());
+        let second = Entry::new("Second".to_string(), "Currently".to_string());
+        let first_uuid = first.uuid;
+        file.add_entry(first);
+        file.add_entry(second);
+
+        file.unknown_lines.push(UnknownLine {
+            line_number: 1,
+            section: None,
+            after: None,
+            text: "# preamble".to_string(),
+        });
+        file.unknown_lines.push(UnknownLine {
+            line_number: 3,
+            section: Some("Currently".to_string()),
+            after: Some(first_uuid),
+            text: "% after first".to_string(),
+        });
+
+        let output = file.to_taskpaper();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "# preamble");
+        assert_eq!(lines[1], "Currently:");
+        assert!(lines[2].contains("| First <"));
+        assert_eq!(lines[3], "% after first");
+        assert!(lines[4].contains("| Second <"));
+
+        // Removing the anchor entry keeps the line in its section
+        file.sections.get_mut("Currently").unwrap().remove(0);
+        let output = file.to_taskpaper();
+        assert!(output.ends_with("% after first\n"));
+    }
 }
@@ -1,37 +1,77 @@
-use chrono::{DateTime, Local};
+use super::Dialect;
+use chrono::{DateTime, Duration, Local, NaiveDateTime};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// How start and finish times are written in the doing file
+pub const TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Entry {
     pub description: String,
-    pub timestamp: DateTime<Local>,
+    pub started_at: DateTime<Local>,
+    /// When the entry was finished, read from and written as `@done(...)`.
+    /// Change it with `finish` / `unfinish` so the tag follows.
+    pub finished_at: Option<DateTime<Local>>,
     pub section: String,
-    pub tags: HashMap<String, Option<String>>,
+    pub tags: IndexMap<String, Option<String>>,
     pub note: Option<String>,
     pub uuid: Uuid,
+    /// The title as read from the doing file, see [`Entry::keep_source_title`]
+    #[serde(skip)]
+    source_title: Option<SourceTitle>,
+}
+
+// How the title was written isn't part of the entry, so it isn't compared
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.description == other.description
+            && self.started_at == other.started_at
+            && self.finished_at == other.finished_at
+            && self.section == other.section
+            && self.tags == other.tags
+            && self.note == other.note
+            && self.uuid == other.uuid
+    }
+}
+
+/// A title as written in the doing file, with the description and tags it
+/// was read as
+#[derive(Debug, Clone)]
+struct SourceTitle {
+    text: String,
+    description: String,
+    tags: IndexMap<String, Option<String>>,
 }
 
 impl Entry {
     pub fn new(description: String, section: String) -> Self {
         Self {
             description,
-            timestamp: Local::now(),
+            started_at: Local::now(),
+            finished_at: None,
             section,
-            tags: HashMap::new(),
+            tags: IndexMap::new(),
             note: None,
             uuid: Uuid::new_v4(),
+            source_title: None,
         }
     }
 
-    pub fn with_timestamp(mut self, timestamp: DateTime<Local>) -> Self {
-        self.timestamp = timestamp;
+    pub fn with_started_at(mut self, started_at: DateTime<Local>) -> Self {
+        self.started_at = started_at;
         self
     }
 
     pub fn with_tag(mut self, key: String, value: Option<String>) -> Self {
         self.tags.insert(key, value);
+        self.sync_finished_at();
+        self
+    }
+
+    pub fn with_section(mut self, section: String) -> Self {
+        self.section = section;
         self
     }
 
@@ -40,39 +80,112 @@ impl Entry {
         self
     }
 
+    /// Done entries have a `@done` tag, with or without a time
     pub fn is_done(&self) -> bool {
-        self.tags.contains_key("done")
+        self.finished_at.is_some() || self.tags.contains_key("done")
+    }
+
+    /// Started and not yet done
+    pub fn is_running(&self) -> bool {
+        !self.is_done()
     }
 
     pub fn mark_done(&mut self) {
-        let timestamp = Local::now().format("%Y-%m-%d %H:%M").to_string();
-        self.tags.insert("done".to_string(), Some(timestamp));
+        self.finish(Local::now());
+    }
+
+    /// Mark the entry done at `finished_at`, keeping an existing `@done` tag in place
+    pub fn finish(&mut self, finished_at: DateTime<Local>) {
+        self.finished_at = Some(finished_at);
+        self.tags.insert(
+            "done".to_string(),
+            Some(finished_at.format(TIME_FORMAT).to_string()),
+        );
+    }
+
+    /// Remove the `@done` tag
+    pub fn unfinish(&mut self) {
+        self.finished_at = None;
+        self.tags.shift_remove("done");
+    }
+
+    /// Re-read `finished_at` from the `@done` tag after the tags were changed directly
+    pub fn sync_finished_at(&mut self) {
+        self.finished_at = self
+            .tags
+            .get("done")
+            .and_then(|value| value.as_deref())
+            .and_then(parse_time);
+    }
+
+    /// Time from start to finish, for entries finished at a known time
+    pub fn duration(&self) -> Option<Duration> {
+        self.finished_at.map(|end| end - self.started_at)
+    }
+
+    /// Time from start to finish, or to `now` while the entry is running
+    pub fn elapsed(&self, now: DateTime<Local>) -> Duration {
+        self.finished_at.unwrap_or(now) - self.started_at
+    }
+
+    /// Whether the two entries were going on at the same time; running
+    /// entries last until `now`
+    pub fn overlaps(&self, other: &Entry, now: DateTime<Local>) -> bool {
+        let end = |entry: &Entry| entry.finished_at.unwrap_or(now);
+        self.started_at < end(other) && other.started_at < end(self)
     }
 
     pub fn to_taskpaper(&self) -> String {
-        // Build description with inline tags
-        let mut desc_with_tags = self.description.clone();
+        self.to_taskpaper_as(Dialect::DailyLog)
+    }
+
+    /// Remember `text` as the title the entry was read from, so it is written
+    /// back as it was, tags in place, until the description or tags change
+    pub fn keep_source_title(&mut self, text: &str) {
+        self.source_title = Some(SourceTitle {
+            text: text.to_string(),
+            description: self.description.clone(),
+            tags: self.tags.clone(),
+        });
+    }
 
-        // Add tags to the description
+    /// The description followed by its tags, as written in the doing file
+    pub fn title(&self) -> String {
+        if let Some(source) = &self.source_title
+            && source.description == self.description
+            && source.tags == self.tags
+        {
+            return source.text.clone();
+        }
+
+        let mut title = self.description.clone();
         for (tag, value) in &self.tags {
-            desc_with_tags.push_str(&format!(" @{tag}"));
+            title.push_str(&format!(" @{tag}"));
             if let Some(v) = value {
-                desc_with_tags.push_str(&format!("({v})"));
+                title.push_str(&format!("({v})"));
             }
         }
+        title
+    }
 
+    pub fn to_taskpaper_as(&self, dialect: Dialect) -> String {
         // Format: - YYYY-MM-DD HH:MM | description @tags <uuid>
         let mut result = format!(
-            " - {} | {} <{}>",
-            self.timestamp.format("%Y-%m-%d %H:%M"),
-            desc_with_tags,
-            self.uuid.as_hyphenated()
+            "{}{} | {} <{}>",
+            dialect.entry_prefix(),
+            self.started_at.format(TIME_FORMAT),
+            self.title(),
+            dialect.format_id(&self.uuid)
         );
 
-        // Add note with proper indentation (2 spaces)
+        // Add note with the dialect's indentation
         if let Some(note) = &self.note {
             for line in note.lines() {
-                result.push_str(&format!("\n  {line}"));
+                result.push('\n');
+                if !line.is_empty() {
+                    result.push_str(dialect.note_prefix());
+                    result.push_str(line);
+                }
             }
         }
 
@@ -80,6 +193,14 @@ impl Entry {
     }
 }
 
+/// A start or finish time as written in the doing file, in local time
+pub fn parse_time(value: &str) -> Option<DateTime<Local>> {
+    NaiveDateTime::parse_from_str(value.trim(), TIME_FORMAT)
+        .ok()?
+        .and_local_timezone(Local)
+        .earliest()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,4 +230,60 @@ mod tests {
         let output = entry.to_taskpaper();
         assert!(output.contains("@done("));
     }
+
+    #[test]
+    fn test_taskpaper_doing_dialect() {
+        let entry = Entry::new("Write tests".to_string(), "Currently".to_string())
+            .with_note("Line one\n\nLine two".to_string());
+
+        let output = entry.to_taskpaper_as(Dialect::Doing);
+        assert!(output.starts_with("\t- "));
+        assert!(output.contains(&format!("<{}>", entry.uuid.as_simple())));
+        assert!(output.ends_with("\n\t\tLine one\n\n\t\tLine two"));
+    }
+
+    #[test]
+    fn test_finish_keeps_done_tag_in_sync() {
+        let start = parse_time("2025-07-28 09:00").unwrap();
+        let mut entry = Entry::new("Review".to_string(), "Currently".to_string())
+            .with_started_at(start)
+            .with_tag("done".to_string(), Some("2025-07-28 10:30".to_string()))
+            .with_tag("client".to_string(), None);
+        assert_eq!(entry.finished_at, parse_time("2025-07-28 10:30"));
+        assert_eq!(entry.duration(), Some(Duration::minutes(90)));
+
+        entry.finish(parse_time("2025-07-28 11:00").unwrap());
+        assert_eq!(entry.title(), "Review @done(2025-07-28 11:00) @client");
+
+        entry.unfinish();
+        assert!(entry.is_running());
+        assert_eq!(entry.title(), "Review @client");
+
+        // A bare @done has no time but still counts
+        let entry = entry.with_tag("done".to_string(), None);
+        assert!(entry.is_done());
+        assert_eq!(entry.duration(), None);
+    }
+
+    #[test]
+    fn test_elapsed_and_overlap() {
+        let at = |time: &str| parse_time(&format!("2025-07-28 {time}")).unwrap();
+        let now = at("12:00");
+        let mut morning =
+            Entry::new("Morning".to_string(), "Currently".to_string()).with_started_at(at("09:00"));
+        morning.finish(at("10:00"));
+        let running =
+            Entry::new("Running".to_string(), "Currently".to_string()).with_started_at(at("09:30"));
+        let later =
+            Entry::new("Later".to_string(), "Currently".to_string()).with_started_at(at("10:00"));
+
+        assert_eq!(
+            running.elapsed(now),
+            Duration::hours(2) + Duration::minutes(30)
+        );
+        assert!(morning.overlaps(&running, now));
+        assert!(running.overlaps(&later, now));
+        // Back-to-back entries don't overlap
+        assert!(!morning.overlaps(&later, now));
+    }
 }
@@ -1,7 +1,9 @@
+pub mod dialect;
 pub mod doing_file;
 pub mod entry;
 pub mod section;
 
-pub use doing_file::DoingFile;
+pub use dialect::Dialect;
+pub use doing_file::{DiskState, DoingFile, UnknownLine};
 pub use entry::Entry;
 pub use section::Section;
@@ -0,0 +1,493 @@
+use crate::models::Entry;
+use color_eyre::Result;
+use color_eyre::eyre::eyre;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    DefaultTerminal, Frame,
+    layout::{Constraint, Direction, Layout, Position},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+use std::collections::BTreeSet;
+use std::io::IsTerminal;
+use uuid::Uuid;
+
+const PAGE_SIZE: usize = 10;
+
+/// Let the user choose from `entries` in a fuzzy-filtered menu.
+///
+/// With `multi`, Tab marks several entries; otherwise (or if nothing is
+/// marked) Enter chooses the highlighted one. Returns the chosen ids in the
+/// order they were offered, or nothing if the user cancelled.
+pub fn pick_entries(entries: &[&Entry], prompt: &str, multi: bool) -> Result<Vec<Uuid>> {
+    if entries.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let items = entries
+        .iter()
+        .map(|entry| PickerItem::from_entry(entry))
+        .collect();
+    let chosen = run_picker(Picker::new(items, prompt, multi))?;
+    Ok(chosen.into_iter().map(|i| entries[i].uuid).collect())
+}
+
+/// Let the user choose one of `choices`; `None` if they cancelled
+pub fn pick_one(choices: &[String], prompt: &str) -> Result<Option<String>> {
+    if choices.is_empty() {
+        return Ok(None);
+    }
+
+    let items = choices
+        .iter()
+        .map(|choice| PickerItem::from_label(choice))
+        .collect();
+    let chosen = run_picker(Picker::new(items, prompt, false))?;
+    Ok(chosen.first().map(|&i| choices[i].clone()))
+}
+
+fn run_picker(picker: Picker) -> Result<Vec<usize>> {
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return Err(eyre!("interactive selection needs a terminal"));
+    }
+
+    let terminal = ratatui::init();
+    let result = picker.run(terminal);
+    ratatui::restore();
+    result
+}
+
+/// Score how well `query` fuzzy-matches `text`, or `None` if it doesn't.
+///
+/// Every whitespace-separated term must appear in `text` in order, though not
+/// necessarily contiguously. Consecutive characters and matches at the start
+/// of a word score higher. Terms are case-insensitive unless they contain an
+/// uppercase letter.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.chars().collect();
+    query
+        .split_whitespace()
+        .map(|term| score_term(term, &text))
+        .sum()
+}
+
+fn score_term(term: &str, text: &[char]) -> Option<i64> {
+    let case_sensitive = term.chars().any(char::is_uppercase);
+    let normalize = |c: char| {
+        if case_sensitive {
+            c
+        } else {
+            c.to_lowercase().next().unwrap_or(c)
+        }
+    };
+    let term: Vec<char> = term.chars().map(normalize).collect();
+    let text: Vec<char> = text.iter().copied().map(normalize).collect();
+
+    // Try every place the term could start and keep the best greedy match
+    (0..text.len())
+        .filter(|&start| text[start] == term[0])
+        .filter_map(|start| score_from(&term, &text, start))
+        .max()
+}
+
+fn score_from(term: &[char], text: &[char], start: usize) -> Option<i64> {
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    let mut position = start;
+
+    for &wanted in term {
+        let found = position + text[position..].iter().position(|&c| c == wanted)?;
+        score += 16;
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 10;
+        }
+        match previous {
+            Some(previous) if found == previous + 1 => score += 8,
+            Some(previous) => score -= (found - previous - 1) as i64,
+            None => {}
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+
+    Some(score)
+}
+
+/// One line offered in the picker
+#[derive(Debug)]
+pub struct PickerItem {
+    timestamp: Option<String>,
+    title: String,
+    section: Option<String>,
+    /// What the query is matched against: for entries, the title with its tags and the section
+    haystack: String,
+}
+
+impl PickerItem {
+    pub fn from_entry(entry: &Entry) -> Self {
+        let title = entry.title();
+        Self {
+            timestamp: Some(entry.started_at.format("%Y-%m-%d %H:%M").to_string()),
+            haystack: format!("{title} {}", entry.section),
+            title,
+            section: Some(entry.section.clone()),
+        }
+    }
+
+    pub fn from_label(label: &str) -> Self {
+        Self {
+            timestamp: None,
+            title: label.to_string(),
+            section: None,
+            haystack: label.to_string(),
+        }
+    }
+}
+
+/// State of the selection menu, kept apart from the terminal so it can be tested
+#[derive(Debug)]
+pub struct Picker {
+    /// Is the picker waiting for input?
+    running: bool,
+    items: Vec<PickerItem>,
+    prompt: String,
+    multi: bool,
+    query: String,
+    /// Indices into `items` that match the query, best match first
+    matches: Vec<usize>,
+    /// Highlighted position in `matches`
+    cursor: usize,
+    /// Indices into `items` marked with Tab
+    marked: BTreeSet<usize>,
+    /// Indices into `items` the user chose, in offered order; empty if they cancelled
+    chosen: Vec<usize>,
+}
+
+impl Picker {
+    pub fn new(items: Vec<PickerItem>, prompt: &str, multi: bool) -> Self {
+        let mut picker = Self {
+            running: true,
+            items,
+            prompt: prompt.to_string(),
+            multi,
+            query: String::new(),
+            matches: Vec::new(),
+            cursor: 0,
+            marked: BTreeSet::new(),
+            chosen: Vec::new(),
+        };
+        picker.update_matches();
+        picker
+    }
+
+    /// Run the picker until the user confirms or cancels
+    pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<Vec<usize>> {
+        let mut list_state = ListState::default();
+        while self.running {
+            list_state.select((!self.matches.is_empty()).then_some(self.cursor));
+            terminal.draw(|frame| self.render(frame, &mut list_state))?;
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.on_key_event(key);
+            }
+        }
+        Ok(self.chosen)
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// The chosen item indices once the picker has stopped running
+    pub fn chosen(&self) -> &[usize] {
+        &self.chosen
+    }
+
+    /// Indices of the items currently shown, best match first
+    pub fn visible(&self) -> &[usize] {
+        &self.matches
+    }
+
+    pub fn on_key_event(&mut self, key: KeyEvent) {
+        match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) | (KeyModifiers::CONTROL, KeyCode::Char('c')) => {
+                self.chosen.clear();
+                self.running = false;
+            }
+            (_, KeyCode::Enter) => self.confirm(),
+            (_, KeyCode::Up) | (KeyModifiers::CONTROL, KeyCode::Char('p' | 'k')) => {
+                self.cursor = self.cursor.saturating_sub(1);
+            }
+            (_, KeyCode::Down) | (KeyModifiers::CONTROL, KeyCode::Char('n' | 'j')) => {
+                self.move_down(1);
+            }
+            (_, KeyCode::PageUp) => self.cursor = self.cursor.saturating_sub(PAGE_SIZE),
+            (_, KeyCode::PageDown) => self.move_down(PAGE_SIZE),
+            (_, KeyCode::Tab) if self.multi => {
+                self.toggle_mark();
+                self.move_down(1);
+            }
+            (_, KeyCode::BackTab) if self.multi => {
+                self.toggle_mark();
+                self.cursor = self.cursor.saturating_sub(1);
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('a')) if self.multi => self.toggle_all(),
+            (KeyModifiers::CONTROL, KeyCode::Char('u')) => {
+                self.query.clear();
+                self.update_matches();
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('w')) => {
+                let trimmed = self.query.trim_end();
+                let word_start = trimmed.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+                self.query.truncate(word_start);
+                self.update_matches();
+            }
+            (_, KeyCode::Backspace) => {
+                self.query.pop();
+                self.update_matches();
+            }
+            (KeyModifiers::NONE | KeyModifiers::SHIFT, KeyCode::Char(c)) => {
+                self.query.push(c);
+                self.update_matches();
+            }
+            _ => {}
+        }
+    }
+
+    fn update_matches(&mut self) {
+        let mut scored: Vec<(i64, usize)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| fuzzy_score(&self.query, &item.haystack).map(|s| (s, i)))
+            .collect();
+        // Stable sort keeps the offered order among equal scores
+        scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        self.matches = scored.into_iter().map(|(_, i)| i).collect();
+        self.cursor = 0;
+    }
+
+    fn move_down(&mut self, by: usize) {
+        self.cursor = (self.cursor + by).min(self.matches.len().saturating_sub(1));
+    }
+
+    fn toggle_mark(&mut self) {
+        if let Some(&item) = self.matches.get(self.cursor)
+            && !self.marked.remove(&item)
+        {
+            self.marked.insert(item);
+        }
+    }
+
+    /// Mark every visible match, or unmark them if they're all marked already
+    fn toggle_all(&mut self) {
+        if self.matches.iter().all(|i| self.marked.contains(i)) {
+            for i in &self.matches {
+                self.marked.remove(i);
+            }
+        } else {
+            self.marked.extend(self.matches.iter().copied());
+        }
+    }
+
+    fn confirm(&mut self) {
+        self.chosen = if self.marked.is_empty() {
+            self.matches.get(self.cursor).copied().into_iter().collect()
+        } else {
+            self.marked.iter().copied().collect()
+        };
+        // Nothing matches the query: keep waiting rather than choose nothing
+        if !self.chosen.is_empty() {
+            self.running = false;
+        }
+    }
+
+    fn render(&self, frame: &mut Frame, list_state: &mut ListState) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
+            .split(frame.area());
+
+        let input = Paragraph::new(format!("> {}", self.query)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(self.prompt.as_str()),
+        );
+        frame.render_widget(input, chunks[0]);
+        let query_width = self.query.chars().count() as u16;
+        frame.set_cursor_position(Position::new(
+            chunks[0].x + 3 + query_width,
+            chunks[0].y + 1,
+        ));
+
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .map(|&i| {
+                let item = &self.items[i];
+                let marker = if self.marked.contains(&i) {
+                    "● "
+                } else {
+                    "  "
+                };
+                let mut spans = vec![Span::styled(marker, Style::default().fg(Color::Cyan))];
+                if let Some(timestamp) = &item.timestamp {
+                    spans.push(Span::styled(
+                        timestamp.as_str(),
+                        Style::default().fg(Color::Yellow),
+                    ));
+                    spans.push(Span::raw(" | "));
+                }
+                spans.push(Span::raw(item.title.as_str()));
+                if let Some(section) = &item.section {
+                    spans.push(Span::raw(" "));
+                    spans.push(Span::styled(
+                        format!("[{section}]"),
+                        Style::default().fg(Color::Magenta),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let mut title = format!("{}/{}", self.matches.len(), self.items.len());
+        if !self.marked.is_empty() {
+            title.push_str(&format!(" ({} marked)", self.marked.len()));
+        }
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            );
+        frame.render_stateful_widget(list, chunks[1], list_state);
+
+        let help = if self.multi {
+            "Enter: choose | Tab: mark | Ctrl+A: mark all | ↑/↓: navigate | Esc: cancel"
+        } else {
+            "Enter: choose | ↑/↓: navigate | Esc: cancel"
+        };
+        frame.render_widget(
+            Paragraph::new(help).style(Style::default().fg(Color::Gray)),
+            chunks[2],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<Entry> {
+        vec![
+            Entry::new("Write docs".to_string(), "Currently".to_string())
+                .with_tag("project".to_string(), Some("api".to_string())),
+            Entry::new("Review pull request".to_string(), "Currently".to_string()),
+            Entry::new("Plan sprint".to_string(), "Later".to_string())
+                .with_tag("meeting".to_string(), None),
+        ]
+    }
+
+    fn picker(entries: &[Entry], multi: bool) -> Picker {
+        let items = entries.iter().map(PickerItem::from_entry).collect();
+        Picker::new(items, "Select", multi)
+    }
+
+    fn press(picker: &mut Picker, code: KeyCode) {
+        picker.on_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn type_query(picker: &mut Picker, query: &str) {
+        for c in query.chars() {
+            press(picker, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("wd", "Write docs").is_some());
+        assert!(fuzzy_score("dw", "Write docs").is_none());
+        assert!(fuzzy_score("", "anything").is_some());
+        // Every term must match
+        assert!(fuzzy_score("write later", "Write docs Currently").is_none());
+        // Uppercase in a term makes it case-sensitive
+        assert!(fuzzy_score("Docs", "Write docs").is_none());
+
+        // Word starts and consecutive characters beat scattered matches
+        let word_start = fuzzy_score("doc", "Write docs").unwrap();
+        let scattered = fuzzy_score("doc", "do the taxes, call").unwrap();
+        assert!(word_start > scattered);
+    }
+
+    #[test]
+    fn test_query_filters_by_title_tags_and_section() {
+        let entries = entries();
+        let mut picker = picker(&entries, false);
+        assert_eq!(picker.visible().len(), 3);
+
+        type_query(&mut picker, "@meet");
+        assert_eq!(picker.visible(), &[2]);
+
+        press(&mut picker, KeyCode::Backspace);
+        picker.on_key_event(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+        type_query(&mut picker, "later");
+        assert_eq!(picker.visible(), &[2]);
+    }
+
+    #[test]
+    fn test_enter_chooses_highlighted_entry() {
+        let entries = entries();
+        let mut picker = picker(&entries, false);
+        press(&mut picker, KeyCode::Down);
+        // Tab only marks in multi-select mode
+        press(&mut picker, KeyCode::Tab);
+        press(&mut picker, KeyCode::Enter);
+
+        assert!(!picker.is_running());
+        assert_eq!(picker.chosen(), &[1]);
+    }
+
+    #[test]
+    fn test_marked_entries_are_chosen_in_offered_order() {
+        let entries = entries();
+        let mut picker = picker(&entries, true);
+        press(&mut picker, KeyCode::Down);
+        press(&mut picker, KeyCode::Down);
+        press(&mut picker, KeyCode::Tab);
+        press(&mut picker, KeyCode::Up);
+        press(&mut picker, KeyCode::Up);
+        press(&mut picker, KeyCode::Tab);
+        press(&mut picker, KeyCode::Enter);
+
+        assert_eq!(picker.chosen(), &[0, 2]);
+    }
+
+    #[test]
+    fn test_mark_all_and_cancel() {
+        let entries = entries();
+        let mut picker = picker(&entries, true);
+        type_query(&mut picker, "r");
+        picker.on_key_event(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL));
+        assert_eq!(picker.marked.len(), picker.visible().len());
+
+        press(&mut picker, KeyCode::Esc);
+        assert!(!picker.is_running());
+        assert!(picker.chosen().is_empty());
+    }
+
+    #[test]
+    fn test_enter_without_matches_keeps_running() {
+        let entries = entries();
+        let mut picker = picker(&entries, false);
+        type_query(&mut picker, "zzz");
+        press(&mut picker, KeyCode::Enter);
+        assert!(picker.is_running());
+    }
+}
@@ -0,0 +1,191 @@
+use crate::display::{SortOrder, TagSort};
+use crate::models::Entry;
+use crate::totals::Totals;
+use chrono::{DateTime, Datelike, Days, Duration, Local, Months, NaiveDate};
+use indexmap::IndexMap;
+
+/// How many tags the report lists under "Top tags"
+pub const TOP_TAGS: usize = 10;
+
+/// A summary of a period: finished entries day by day, time per tag and
+/// section, and the entries still open
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub title: String,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    /// Days with finished entries, oldest first
+    pub days: Vec<ReportDay>,
+    /// Tags and sections by time spent, most first
+    pub totals: Totals,
+    /// Unfinished entries started in the period, oldest first
+    pub open: Vec<(String, Entry)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportDay {
+    pub date: NaiveDate,
+    pub entries: Vec<(String, Entry)>,
+    /// Time of the day's entries that have a `@done` time
+    pub tracked: Duration,
+}
+
+impl Report {
+    /// Build the report from the entries already filtered down to the period
+    pub fn new(
+        title: String,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        entries: &[(String, Entry)],
+    ) -> Self {
+        let mut entries = entries.to_vec();
+        entries.sort_by_key(|(_, entry)| entry.started_at);
+
+        let (finished, open): (Vec<_>, Vec<_>) =
+            entries.into_iter().partition(|(_, entry)| entry.is_done());
+
+        let mut days: IndexMap<NaiveDate, ReportDay> = IndexMap::new();
+        for (section, entry) in &finished {
+            let date = entry.started_at.date_naive();
+            let day = days.entry(date).or_insert_with(|| ReportDay {
+                date,
+                entries: Vec::new(),
+                tracked: Duration::zero(),
+            });
+            day.tracked += entry.duration().unwrap_or_else(Duration::zero);
+            day.entries.push((section.clone(), entry.clone()));
+        }
+
+        Self {
+            title,
+            start,
+            end,
+            days: days.into_values().collect(),
+            totals: Totals::new(&finished, &TagSort::Time, &SortOrder::Desc),
+            open,
+        }
+    }
+}
+
+/// The current week, Monday to Sunday, and its title
+pub fn week_of(now: DateTime<Local>) -> (DateTime<Local>, DateTime<Local>, String) {
+    let monday = now.date_naive().week(chrono::Weekday::Mon).first_day();
+    let (start, end) = period(monday, monday + Days::new(7));
+    (
+        start,
+        end,
+        format!("Week of {}", monday.format("%B %-d, %Y")),
+    )
+}
+
+/// The current calendar month and its title
+pub fn month_of(now: DateTime<Local>) -> (DateTime<Local>, DateTime<Local>, String) {
+    let first = now.date_naive().with_day(1).unwrap_or(now.date_naive());
+    let (start, end) = period(first, first + Months::new(1));
+    (start, end, first.format("%B %Y").to_string())
+}
+
+/// The whole days a `--from` range covers: from midnight on the day it
+/// starts to the end of the day it ends, or to `now` when it has no end
+pub fn whole_days(
+    start: DateTime<Local>,
+    end: Option<DateTime<Local>>,
+    now: DateTime<Local>,
+) -> (DateTime<Local>, DateTime<Local>) {
+    let first = start.date_naive();
+    match end {
+        Some(end) => period(first, end.date_naive() + Days::new(1)),
+        None => (period(first, first + Days::new(1)).0, now),
+    }
+}
+
+/// From midnight on `first` to the last second before midnight on `next`
+pub fn period(first: NaiveDate, next: NaiveDate) -> (DateTime<Local>, DateTime<Local>) {
+    let midnight = |date: NaiveDate| {
+        date.and_hms_opt(0, 0, 0)
+            .and_then(|time| time.and_local_timezone(Local).earliest())
+            .unwrap_or_else(Local::now)
+    };
+    (midnight(first), midnight(next) - Duration::seconds(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::entry::parse_time;
+    use crate::test_utils::utils::timed_entry;
+
+    #[test]
+    fn test_days_totals_and_open_items() {
+        let entries: Vec<(String, Entry)> = [
+            timed_entry(
+                "Planning",
+                "2025-07-29 09:00",
+                Some("2025-07-29 10:00"),
+                &["meeting"],
+            ),
+            timed_entry(
+                "Write code",
+                "2025-07-28 09:00",
+                Some("2025-07-28 11:00"),
+                &["code"],
+            ),
+            timed_entry("Refactor", "2025-07-29 10:00", None, &["code"]),
+            timed_entry(
+                "Review",
+                "2025-07-28 13:00",
+                Some("2025-07-28 13:30"),
+                &["code"],
+            ),
+        ]
+        .into_iter()
+        .map(|entry| ("Currently".to_string(), entry))
+        .collect();
+        let (start, end, _) = week_of(parse_time("2025-07-30 12:00").unwrap());
+        let report = Report::new("Week".to_string(), start, end, &entries);
+
+        let days: Vec<(String, i64, usize)> = report
+            .days
+            .iter()
+            .map(|day| {
+                (
+                    day.date.to_string(),
+                    day.tracked.num_minutes(),
+                    day.entries.len(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            days,
+            vec![
+                ("2025-07-28".to_string(), 150, 2),
+                ("2025-07-29".to_string(), 60, 1)
+            ]
+        );
+        assert_eq!(report.totals.tags[0].name, "code");
+        assert_eq!(report.totals.total, Duration::minutes(210));
+        assert_eq!(report.open.len(), 1);
+        assert_eq!(report.open[0].1.description, "Refactor");
+    }
+
+    #[test]
+    fn test_week_and_month_periods() {
+        let now = parse_time("2025-07-30 12:00").unwrap();
+
+        let (start, end, title) = week_of(now);
+        assert_eq!(start, parse_time("2025-07-28 00:00").unwrap());
+        assert_eq!(
+            end.format("%Y-%m-%d %H:%M:%S").to_string(),
+            "2025-08-03 23:59:59"
+        );
+        assert_eq!(title, "Week of July 28, 2025");
+
+        let (start, end, title) = month_of(now);
+        assert_eq!(start, parse_time("2025-07-01 00:00").unwrap());
+        assert_eq!(
+            end.format("%Y-%m-%d %H:%M:%S").to_string(),
+            "2025-07-31 23:59:59"
+        );
+        assert_eq!(title, "July 2025");
+    }
+}
@@ -1,19 +1,70 @@
-use crate::models::Entry;
-use crate::storage::{Config, parse_taskpaper, save_taskpaper};
+use crate::filtering::{FilterOptions, filter_entries};
+use crate::models::{DoingFile, Entry};
+use crate::storage::{Config, FileLock, history, parse_taskpaper, save_taskpaper};
 use chrono::{DateTime, Local};
 use color_eyre::Result;
 use color_eyre::eyre::eyre;
+use indexmap::IndexMap;
 use uuid::Uuid;
 
 pub struct EntryService;
 
+/// What the TUI shows, see [`EntryService::get_tui_view`]
+#[derive(Debug, Default)]
+pub struct TuiView {
+    pub sections: Vec<(String, usize)>,
+    pub entries: Vec<Entry>,
+    /// How many entries match the list's filter in all
+    pub total: usize,
+    pub today: Vec<Entry>,
+    pub screen: Option<Vec<Entry>>,
+}
+
+/// A change made to several entries in one save, see [`EntryService::bulk_update`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkAction {
+    /// Mark running entries done now, as `finish` does
+    Finish,
+    /// Mark running entries `@done` without a time, as `cancel` does
+    Cancel,
+    Tag(IndexMap<String, Option<String>>),
+    Untag(Vec<String>),
+    /// Move the entries to another section
+    Move(String),
+    /// Move the entries to Archive, labelled with the section they came from
+    /// as `archive` does
+    Archive,
+    Delete,
+    /// Flag the entries, or unflag them when they are all flagged already
+    Flag,
+}
+
+impl BulkAction {
+    /// What the action does, as a verb for messages and undo labels
+    pub fn verb(&self) -> &'static str {
+        match self {
+            BulkAction::Finish => "finish",
+            BulkAction::Cancel => "cancel",
+            BulkAction::Tag(_) => "tag",
+            BulkAction::Untag(_) => "untag",
+            BulkAction::Move(_) => "move",
+            BulkAction::Archive => "archive",
+            BulkAction::Delete => "delete",
+            BulkAction::Flag => "flag",
+        }
+    }
+}
+
 impl EntryService {
     /// Toggle the @done status of an entry by its UUID
     /// Returns the updated entry if successful
     pub fn toggle_done_by_uuid(uuid: &Uuid) -> Result<Entry> {
-        let config = Config::load();
+        let config = Config::try_load()?;
         let doing_file_path = config.doing_file_path();
 
+        let _lock = FileLock::acquire(&doing_file_path)?;
+        history::begin_operation("tui: toggle done");
+
         let mut doing_file = parse_taskpaper(&doing_file_path)?;
 
         // Find and toggle the entry
@@ -24,13 +75,9 @@ impl EntryService {
                 if &entry.uuid == uuid {
                     // Toggle the done status
                     if entry.is_done() {
-                        entry.tags.remove("done");
+                        entry.unfinish();
                     } else {
-                        let now = Local::now();
-                        entry.tags.insert(
-                            "done".to_string(),
-                            Some(now.format("%Y-%m-%d %H:%M").to_string()),
-                        );
+                        entry.mark_done();
                     }
                     found_entry = Some(entry.clone());
                     break;
@@ -43,7 +90,7 @@ impl EntryService {
 
         if let Some(updated_entry) = found_entry {
             // Save the file
-            save_taskpaper(&doing_file)?;
+            save_taskpaper(&doing_file, &config)?;
             Ok(updated_entry)
         } else {
             Err(eyre!("Entry with UUID {} not found", uuid))
@@ -52,9 +99,12 @@ impl EntryService {
 
     /// Update an entry's description by its UUID
     pub fn update_entry_description(uuid: &Uuid, new_description: String) -> Result<Entry> {
-        let config = Config::load();
+        let config = Config::try_load()?;
         let doing_file_path = config.doing_file_path();
 
+        let _lock = FileLock::acquire(&doing_file_path)?;
+        history::begin_operation("tui: edit description");
+
         let mut doing_file = parse_taskpaper(&doing_file_path)?;
 
         // Find and update the entry
@@ -75,7 +125,7 @@ impl EntryService {
 
         if let Some(updated_entry) = found_entry {
             // Save the file
-            save_taskpaper(&doing_file)?;
+            save_taskpaper(&doing_file, &config)?;
             Ok(updated_entry)
         } else {
             Err(eyre!("Entry with UUID {} not found", uuid))
@@ -84,9 +134,12 @@ impl EntryService {
 
     /// Update an entry's note by its UUID
     pub fn update_entry_note(uuid: &Uuid, new_note: Option<String>) -> Result<Entry> {
-        let config = Config::load();
+        let config = Config::try_load()?;
         let doing_file_path = config.doing_file_path();
 
+        let _lock = FileLock::acquire(&doing_file_path)?;
+        history::begin_operation("tui: edit note");
+
         let mut doing_file = parse_taskpaper(&doing_file_path)?;
 
         // Find and update the entry
@@ -107,7 +160,7 @@ impl EntryService {
 
         if let Some(updated_entry) = found_entry {
             // Save the file
-            save_taskpaper(&doing_file)?;
+            save_taskpaper(&doing_file, &config)?;
             Ok(updated_entry)
         } else {
             Err(eyre!("Entry with UUID {} not found", uuid))
@@ -116,9 +169,12 @@ impl EntryService {
 
     /// Delete an entry by its UUID
     pub fn delete_by_uuid(uuid: &Uuid) -> Result<()> {
-        let config = Config::load();
+        let config = Config::try_load()?;
         let doing_file_path = config.doing_file_path();
 
+        let _lock = FileLock::acquire(&doing_file_path)?;
+        history::begin_operation("tui: delete entry");
+
         let mut doing_file = parse_taskpaper(&doing_file_path)?;
 
         // Find and remove the entry
@@ -134,16 +190,79 @@ impl EntryService {
         }
 
         if deleted {
-            save_taskpaper(&doing_file)?;
+            save_taskpaper(&doing_file, &config)?;
             Ok(())
         } else {
             Err(eyre!("Entry with UUID {} not found", uuid))
         }
     }
 
+    /// Add an entry from the TUI's new entry form; see [`Self::add_entry`]
+    pub fn add_tui_entry(entry: Entry, finish_last: bool) -> Result<Option<Entry>> {
+        history::begin_operation(if finish_last {
+            "tui: finish and add entry"
+        } else {
+            "tui: add entry"
+        });
+        Self::add_entry(entry, finish_last)
+    }
+
+    /// Add an entry the TUI resumed, made with [`Self::resumed_entry`]
+    pub fn add_resumed_tui_entry(entry: Entry) -> Result<()> {
+        history::begin_operation("tui: resume entry");
+        Self::add_entry(entry, false).map(|_| ())
+    }
+
+    /// Add a new entry, as `now` does. With `finish_last`, the most recent
+    /// unfinished entry in the new entry's section is marked done first, as
+    /// with `now --finish_last`; that entry is returned alongside. The change
+    /// is recorded under the current history operation, which for a command
+    /// is its command line.
+    pub fn add_entry(entry: Entry, finish_last: bool) -> Result<Option<Entry>> {
+        let config = Config::try_load()?;
+        let doing_file_path = config.doing_file_path();
+
+        let _lock = FileLock::acquire(&doing_file_path)?;
+        let mut doing_file = parse_taskpaper(&doing_file_path)?;
+
+        let mut finished = None;
+        if finish_last
+            && let Some(entries) = doing_file.sections.get_mut(&entry.section)
+            && let Some(last) = entries
+                .iter_mut()
+                .filter(|e| !e.is_done())
+                .max_by_key(|e| e.started_at)
+        {
+            last.mark_done();
+            finished = Some(last.clone());
+        }
+
+        doing_file.add_entry(entry);
+        save_taskpaper(&doing_file, &config)?;
+        Ok(finished)
+    }
+
+    /// A new entry continuing `entry`, as `again` starts it: the same title,
+    /// tags and note, without `@done`, in `section` or the entry's own section
+    pub fn resumed_entry(
+        entry: &Entry,
+        section: Option<String>,
+        started_at: DateTime<Local>,
+    ) -> Entry {
+        let mut resumed = Entry::new(
+            entry.description.clone(),
+            section.unwrap_or_else(|| entry.section.clone()),
+        )
+        .with_started_at(started_at);
+        resumed.tags = entry.tags.clone();
+        resumed.tags.shift_remove("done");
+        resumed.note = entry.note.clone();
+        resumed
+    }
+
     /// Get recent entries across all sections
     pub fn get_recent_entries(count: usize) -> Result<Vec<Entry>> {
-        let config = Config::load();
+        let config = Config::try_load()?;
         let doing_file_path = config.doing_file_path();
 
         let doing_file = parse_taskpaper(&doing_file_path)?;
@@ -151,7 +270,7 @@ impl EntryService {
         let mut owned_entries: Vec<Entry> = all_entries.into_iter().cloned().collect();
 
         // Sort by timestamp, newest first
-        owned_entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        owned_entries.sort_by_key(|e| std::cmp::Reverse(e.started_at));
 
         // Take the requested count
         owned_entries.truncate(count);
@@ -159,9 +278,17 @@ impl EntryService {
         Ok(owned_entries)
     }
 
+    /// Get entries started at or after `start`, across all sections
+    pub fn get_entries_since(start: DateTime<Local>) -> Result<Vec<Entry>> {
+        let config = Config::try_load()?;
+        let doing_file = parse_taskpaper(&config.doing_file_path())?;
+
+        Ok(entries_since(&doing_file, start))
+    }
+
     /// Get entries for a specific section
     pub fn get_section_entries(section: &str) -> Result<Vec<Entry>> {
-        let config = Config::load();
+        let config = Config::try_load()?;
         let doing_file_path = config.doing_file_path();
 
         let doing_file = parse_taskpaper(&doing_file_path)?;
@@ -173,43 +300,64 @@ impl EntryService {
             .unwrap_or_default())
     }
 
-    /// Get entries for display in TUI, optionally filtered by section
-    pub fn get_tui_entries(section_filter: Option<&str>, limit: usize) -> Result<Vec<Entry>> {
-        let config = Config::load();
+    /// Get entries for display in TUI: those matching `filter`, most recent
+    /// first, up to `limit`. Also returns how many entries match in all.
+    pub fn get_tui_entries(filter: &FilterOptions, limit: usize) -> Result<(Vec<Entry>, usize)> {
+        let config = Config::try_load()?;
         let doing_file_path = config.doing_file_path();
 
         let doing_file = parse_taskpaper(&doing_file_path)?;
 
-        let mut entries: Vec<Entry> = if let Some(section) = section_filter {
-            // Get entries from specific section
-            doing_file
-                .sections
-                .get(section)
-                .cloned()
-                .unwrap_or_default()
-        } else {
-            // Get all entries
-            doing_file.get_all_entries().into_iter().cloned().collect()
-        };
+        tui_entries(&doing_file, filter, limit)
+    }
 
-        // Sort by timestamp descending (most recent first)
-        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    /// Section names in file order, with the number of entries in each
+    pub fn get_sections() -> Result<Vec<(String, usize)>> {
+        let config = Config::try_load()?;
+        let doing_file = parse_taskpaper(&config.doing_file_path())?;
 
-        // Limit to requested count
-        entries.truncate(limit);
+        Ok(sections(&doing_file))
+    }
 
-        Ok(entries)
+    /// Everything the TUI shows, from one read of the doing file: the
+    /// sections, the list's entries as [`Self::get_tui_entries`] returns
+    /// them, the entries started since `today`, and every entry matching
+    /// `screen` when the calendar or the timeline is shown
+    pub fn get_tui_view(
+        filter: &FilterOptions,
+        limit: usize,
+        today: Option<DateTime<Local>>,
+        screen: Option<&FilterOptions>,
+    ) -> Result<TuiView> {
+        let config = Config::try_load()?;
+        let doing_file = parse_taskpaper(&config.doing_file_path())?;
+
+        let (entries, total) = tui_entries(&doing_file, filter, limit)?;
+        let screen = match screen {
+            Some(filter) => Some(tui_entries(&doing_file, filter, usize::MAX)?.0),
+            None => None,
+        };
+        Ok(TuiView {
+            sections: sections(&doing_file),
+            entries,
+            total,
+            today: today.map_or_else(Vec::new, |start| entries_since(&doing_file, start)),
+            screen,
+        })
     }
 
-    /// Update an entry's timestamp and done status by its UUID
+    /// Update an entry's start and finish times by its UUID
     pub fn update_entry_timestamp(
         uuid: &Uuid,
         new_timestamp: DateTime<Local>,
-        new_done: Option<String>,
+        new_done: Option<DateTime<Local>>,
     ) -> Result<Entry> {
-        let config = Config::load();
+        let config = Config::try_load()?;
         let doing_file_path = config.doing_file_path();
 
+        let _lock = FileLock::acquire(&doing_file_path)?;
+        history::begin_operation("tui: edit timestamp");
+
         let mut doing_file = parse_taskpaper(&doing_file_path)?;
 
         // Find and update the entry
@@ -218,15 +366,12 @@ impl EntryService {
         for (_section_name, entries) in doing_file.sections.iter_mut() {
             for entry in entries.iter_mut() {
                 if &entry.uuid == uuid {
-                    entry.timestamp = new_timestamp;
+                    entry.started_at = new_timestamp;
 
                     // Update done status
-                    if let Some(ref done_time) = new_done {
-                        entry
-                            .tags
-                            .insert("done".to_string(), Some(done_time.clone()));
-                    } else {
-                        entry.tags.remove("done");
+                    match new_done {
+                        Some(finished_at) => entry.finish(finished_at),
+                        None => entry.unfinish(),
                     }
 
                     found_entry = Some(entry.clone());
@@ -240,10 +385,129 @@ impl EntryService {
 
         if let Some(updated_entry) = found_entry {
             // Save the file
-            save_taskpaper(&doing_file)?;
+            save_taskpaper(&doing_file, &config)?;
             Ok(updated_entry)
         } else {
             Err(eyre!("Entry with UUID {} not found", uuid))
         }
     }
+
+    /// Apply `action` to the entries with the given UUIDs and save the file
+    /// once. Returns how many entries were changed; entries that no longer
+    /// exist are skipped.
+    pub fn bulk_update(uuids: &[Uuid], action: &BulkAction) -> Result<usize> {
+        let config = Config::try_load()?;
+        let doing_file_path = config.doing_file_path();
+
+        let _lock = FileLock::acquire(&doing_file_path)?;
+        history::begin_operation(match uuids.len() {
+            1 => format!("tui: {} 1 entry", action.verb()),
+            count => format!("tui: {} {count} entries", action.verb()),
+        });
+        let mut doing_file = parse_taskpaper(&doing_file_path)?;
+
+        let mut targets: Vec<Entry> = doing_file
+            .get_all_entries()
+            .into_iter()
+            .filter(|entry| uuids.contains(&entry.uuid))
+            .cloned()
+            .collect();
+        let unflag = targets
+            .iter()
+            .all(|entry| entry.tags.contains_key("flagged"));
+
+        let mut changed = 0;
+        for entry in &mut targets {
+            let before = entry.clone();
+            match action {
+                BulkAction::Finish if entry.is_running() => entry.mark_done(),
+                BulkAction::Cancel if entry.is_running() => {
+                    entry.tags.insert("done".to_string(), None);
+                }
+                BulkAction::Finish | BulkAction::Cancel => {}
+                BulkAction::Tag(tags) => {
+                    entry
+                        .tags
+                        .extend(tags.iter().map(|(tag, value)| (tag.clone(), value.clone())));
+                    entry.sync_finished_at();
+                }
+                BulkAction::Untag(tags) => {
+                    entry
+                        .tags
+                        .retain(|tag, _| !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)));
+                    entry.sync_finished_at();
+                }
+                BulkAction::Move(section) => entry.section = section.clone(),
+                BulkAction::Archive if entry.section != "Archive" => {
+                    if entry.section != "Currently" {
+                        entry
+                            .tags
+                            .insert(format!("from_{}", entry.section.to_lowercase()), None);
+                    }
+                    entry.section = "Archive".to_string();
+                }
+                BulkAction::Archive => {}
+                BulkAction::Delete => {
+                    for entries in doing_file.sections.values_mut() {
+                        entries.retain(|e| e.uuid != entry.uuid);
+                    }
+                    changed += 1;
+                    continue;
+                }
+                BulkAction::Flag if unflag => {
+                    entry.tags.shift_remove("flagged");
+                }
+                BulkAction::Flag => {
+                    entry.tags.insert("flagged".to_string(), None);
+                }
+            }
+            if *entry != before {
+                doing_file.update_entry(entry.clone());
+                changed += 1;
+            }
+        }
+
+        if changed > 0 {
+            save_taskpaper(&doing_file, &config)?;
+        }
+        Ok(changed)
+    }
+}
+
+/// The entries matching `filter`, most recent first, up to `limit`, and how
+/// many match in all
+fn tui_entries(
+    doing_file: &DoingFile,
+    filter: &FilterOptions,
+    limit: usize,
+) -> Result<(Vec<Entry>, usize)> {
+    let mut entries: Vec<Entry> = filter_entries(doing_file, filter)?
+        .into_iter()
+        .map(|(_, entry)| entry)
+        .collect();
+
+    // Sort by timestamp descending (most recent first)
+    entries.sort_by_key(|e| std::cmp::Reverse(e.started_at));
+
+    let total = entries.len();
+    entries.truncate(limit);
+
+    Ok((entries, total))
+}
+
+fn sections(doing_file: &DoingFile) -> Vec<(String, usize)> {
+    doing_file
+        .sections
+        .iter()
+        .map(|(name, entries)| (name.clone(), entries.len()))
+        .collect()
+}
+
+fn entries_since(doing_file: &DoingFile, start: DateTime<Local>) -> Vec<Entry> {
+    doing_file
+        .get_all_entries()
+        .into_iter()
+        .filter(|entry| entry.started_at >= start)
+        .cloned()
+        .collect()
 }
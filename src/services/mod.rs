@@ -1,6 +1,6 @@
 pub mod entry_service;
 
-pub use entry_service::EntryService;
+pub use entry_service::{BulkAction, EntryService, TuiView};
 
 #[cfg(test)]
 mod tests;
@@ -1,7 +1,8 @@
 #[cfg(test)]
 mod tests {
+    use crate::filtering::FilterOptions;
     use crate::models::{DoingFile, Entry};
-    use crate::services::EntryService;
+    use crate::services::{BulkAction, EntryService};
     use crate::storage::{Config, save_taskpaper};
     use crate::test_utils::utils::*;
     use chrono::Local;
@@ -21,7 +22,7 @@ mod tests {
             .entry("Currently".to_string())
             .or_default()
             .push(entry.clone());
-        save_taskpaper(&doing_file).unwrap();
+        save_taskpaper(&doing_file, &config).unwrap();
 
         // Toggle to done
         let updated = EntryService::toggle_done_by_uuid(&entry.uuid).unwrap();
@@ -32,6 +33,22 @@ mod tests {
         assert!(!updated2.is_done(), "Entry should not be marked as done");
     }
 
+    #[test]
+    fn test_broken_config_is_an_error() {
+        let ctx = TestContext::new().unwrap();
+        let entry = Entry::new("Before".to_string(), "Currently".to_string());
+        EntryService::add_entry(entry.clone(), false).unwrap();
+        let before = ctx.read_test_file().unwrap();
+
+        // A config that breaks while running must not send writes elsewhere
+        std::fs::write(&ctx.config_path, "{\"doing_file\": ").unwrap();
+        let after = Entry::new("After".to_string(), "Currently".to_string());
+        assert!(EntryService::add_entry(after, false).is_err());
+        assert!(EntryService::toggle_done_by_uuid(&entry.uuid).is_err());
+        assert!(EntryService::get_sections().is_err());
+        assert_eq!(ctx.read_test_file().unwrap(), before);
+    }
+
     #[test]
     fn test_delete_by_uuid() {
         let _ctx = TestContext::new().unwrap();
@@ -47,7 +64,7 @@ mod tests {
             .entry("Currently".to_string())
             .or_default()
             .extend(vec![entry1.clone(), entry2.clone()]);
-        save_taskpaper(&doing_file).unwrap();
+        save_taskpaper(&doing_file, &config).unwrap();
 
         // Delete entry1
         EntryService::delete_by_uuid(&entry1.uuid).unwrap();
@@ -66,7 +83,7 @@ mod tests {
         let mut entries = vec![];
         for i in 0..5 {
             let mut entry = Entry::new(format!("Entry {i}"), "Currently".to_string());
-            entry.timestamp = Local::now() - chrono::Duration::hours(i as i64);
+            entry.started_at = Local::now() - chrono::Duration::hours(i as i64);
             entries.push(entry);
         }
 
@@ -77,14 +94,279 @@ mod tests {
             .entry("Currently".to_string())
             .or_default()
             .extend(entries);
-        save_taskpaper(&doing_file).unwrap();
+        save_taskpaper(&doing_file, &config).unwrap();
 
         // Get recent 3
         let recent = EntryService::get_recent_entries(3).unwrap();
         assert_eq!(recent.len(), 3);
 
         // Should be sorted newest first
-        assert!(recent[0].timestamp > recent[1].timestamp);
-        assert!(recent[1].timestamp > recent[2].timestamp);
+        assert!(recent[0].started_at > recent[1].started_at);
+        assert!(recent[1].started_at > recent[2].started_at);
+    }
+
+    #[test]
+    fn test_add_entry_finishing_the_last() {
+        let _ctx = TestContext::new().unwrap();
+
+        let mut older = Entry::new("Older".to_string(), "Currently".to_string());
+        older.started_at = Local::now() - chrono::Duration::hours(2);
+        let mut running = Entry::new("Running".to_string(), "Currently".to_string());
+        running.started_at = Local::now() - chrono::Duration::hours(1);
+        let elsewhere = Entry::new("Elsewhere".to_string(), "Later".to_string());
+
+        let config = Config::load();
+        let mut doing_file = DoingFile::new(config.doing_file_path());
+        for entry in [&older, &running, &elsewhere] {
+            doing_file.add_entry(entry.clone());
+        }
+        save_taskpaper(&doing_file, &config).unwrap();
+
+        let new_entry = Entry::new("Next".to_string(), "Currently".to_string());
+        let finished = EntryService::add_entry(new_entry.clone(), true).unwrap();
+        assert_eq!(finished.map(|entry| entry.uuid), Some(running.uuid));
+
+        let entries = EntryService::get_section_entries("Currently").unwrap();
+        let done: Vec<&str> = entries
+            .iter()
+            .filter(|entry| entry.is_done())
+            .map(|entry| entry.description.as_str())
+            .collect();
+        assert_eq!(done, vec!["Running"]);
+        assert!(entries.iter().any(|entry| entry.uuid == new_entry.uuid));
+        assert!(!EntryService::get_section_entries("Later").unwrap()[0].is_done());
+    }
+
+    #[test]
+    fn test_resumed_entry() {
+        let mut entry = Entry::new("Review".to_string(), "Work".to_string())
+            .with_tag("code".to_string(), None)
+            .with_note("PR 42".to_string());
+        entry.mark_done();
+
+        let now = Local::now();
+        let resumed = EntryService::resumed_entry(&entry, None, now);
+        assert_ne!(resumed.uuid, entry.uuid);
+        assert_eq!(resumed.section, "Work");
+        assert_eq!(resumed.started_at, now);
+        assert!(resumed.is_running());
+        assert_eq!(resumed.tags.keys().collect::<Vec<_>>(), vec!["code"]);
+        assert_eq!(resumed.note.as_deref(), Some("PR 42"));
+
+        let moved = EntryService::resumed_entry(&entry, Some("Later".to_string()), now);
+        assert_eq!(moved.section, "Later");
+    }
+
+    #[test]
+    fn test_get_tui_entries_filtered_and_paged() {
+        let _ctx = TestContext::new().unwrap();
+        let now = Local::now();
+
+        let config = Config::load();
+        let mut doing_file = DoingFile::new(config.doing_file_path());
+        for i in 0..5 {
+            let entry = Entry::new(format!("Meeting {i}"), "Currently".to_string())
+                .with_tag("meeting".to_string(), None)
+                .with_started_at(now - chrono::Duration::hours(i));
+            doing_file
+                .sections
+                .entry("Currently".to_string())
+                .or_default()
+                .push(entry);
+        }
+        doing_file
+            .sections
+            .entry("Later".to_string())
+            .or_default()
+            .push(Entry::new("Write report".to_string(), "Later".to_string()));
+        save_taskpaper(&doing_file, &config).unwrap();
+
+        let sections = EntryService::get_sections().unwrap();
+        assert_eq!(
+            sections,
+            vec![("Currently".to_string(), 5), ("Later".to_string(), 1)]
+        );
+
+        // The newest first, and how many match in all
+        let filter = FilterOptions {
+            tags: vec!["meeting".to_string()],
+            ..Default::default()
+        };
+        let (entries, total) = EntryService::get_tui_entries(&filter, 2).unwrap();
+        assert_eq!(total, 5);
+        assert_eq!(
+            entries
+                .iter()
+                .map(|e| e.description.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Meeting 0", "Meeting 1"]
+        );
+
+        let filter = FilterOptions {
+            search: Some("report".to_string()),
+            ..Default::default()
+        };
+        let (entries, total) = EntryService::get_tui_entries(&filter, 50).unwrap();
+        assert_eq!((entries.len(), total), (1, 1));
+        assert_eq!(entries[0].section, "Later");
+
+        let filter = FilterOptions {
+            sections: vec!["Later".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(EntryService::get_tui_entries(&filter, 50).unwrap().1, 1);
+    }
+
+    #[test]
+    fn test_get_tui_view() {
+        let _ctx = TestContext::new().unwrap();
+        let now = Local::now();
+
+        let config = Config::load();
+        let mut doing_file = DoingFile::new(config.doing_file_path());
+        doing_file
+            .sections
+            .entry("Currently".to_string())
+            .or_default()
+            .extend(vec![
+                Entry::new("Old meeting".to_string(), "Currently".to_string())
+                    .with_tag("meeting".to_string(), None)
+                    .with_started_at(now - chrono::Duration::days(3)),
+                Entry::new("Meeting".to_string(), "Currently".to_string())
+                    .with_tag("meeting".to_string(), None)
+                    .with_started_at(now - chrono::Duration::minutes(30)),
+                Entry::new("Coding".to_string(), "Currently".to_string())
+                    .with_started_at(now - chrono::Duration::minutes(10)),
+            ]);
+        save_taskpaper(&doing_file, &config).unwrap();
+
+        let filter = FilterOptions {
+            tags: vec!["meeting".to_string()],
+            ..Default::default()
+        };
+        let screen = FilterOptions {
+            from: Some((now - chrono::Duration::days(1), None)),
+            ..Default::default()
+        };
+        let view = EntryService::get_tui_view(
+            &filter,
+            1,
+            Some(now - chrono::Duration::hours(1)),
+            Some(&screen),
+        )
+        .unwrap();
+        assert_eq!(view.sections, vec![("Currently".to_string(), 3)]);
+        assert_eq!((view.entries.len(), view.total), (1, 2));
+        assert_eq!(view.entries[0].description, "Meeting");
+        assert_eq!(view.today.len(), 2);
+        assert_eq!(view.screen.map(|entries| entries.len()), Some(2));
+
+        let view = EntryService::get_tui_view(&filter, 50, None, None).unwrap();
+        assert!(view.today.is_empty());
+        assert!(view.screen.is_none());
+    }
+
+    #[test]
+    fn test_get_entries_since() {
+        let _ctx = TestContext::new().unwrap();
+        let now = Local::now();
+
+        let config = Config::load();
+        let mut doing_file = DoingFile::new(config.doing_file_path());
+        doing_file
+            .sections
+            .entry("Currently".to_string())
+            .or_default()
+            .extend(vec![
+                Entry::new("Old".to_string(), "Currently".to_string())
+                    .with_started_at(now - chrono::Duration::days(2)),
+                Entry::new("Recent".to_string(), "Currently".to_string())
+                    .with_started_at(now - chrono::Duration::minutes(30)),
+            ]);
+        save_taskpaper(&doing_file, &config).unwrap();
+
+        let entries = EntryService::get_entries_since(now - chrono::Duration::hours(1)).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].description, "Recent");
+    }
+
+    #[test]
+    fn test_bulk_update() {
+        let _ctx = TestContext::new().unwrap();
+
+        let running = Entry::new("Running".to_string(), "Currently".to_string());
+        let mut done = Entry::new("Done".to_string(), "Currently".to_string());
+        done.mark_done();
+        let later = Entry::new("Later".to_string(), "Later".to_string());
+        let untouched = Entry::new("Untouched".to_string(), "Currently".to_string());
+
+        let config = Config::load();
+        let mut doing_file = DoingFile::new(config.doing_file_path());
+        for entry in [&running, &done, &later, &untouched] {
+            doing_file.add_entry(entry.clone());
+        }
+        save_taskpaper(&doing_file, &config).unwrap();
+
+        let uuids = [running.uuid, done.uuid, later.uuid];
+        let load = || crate::storage::parse_taskpaper(&config.doing_file_path()).unwrap();
+
+        // Entries already done are left as they are
+        assert_eq!(
+            EntryService::bulk_update(&uuids, &BulkAction::Cancel).unwrap(),
+            2
+        );
+        let file = load();
+        let cancelled = file.get_entry(running.uuid).unwrap();
+        assert_eq!(cancelled.tags.get("done"), Some(&None));
+        assert_eq!(file.get_entry(done.uuid).unwrap().tags, done.tags);
+        assert!(file.get_entry(untouched.uuid).unwrap().is_running());
+        // The whole change is one undo step, labelled by the service
+        let history = crate::storage::history::History::open(&config).unwrap();
+        assert_eq!(
+            history.undo_records().last().unwrap().command,
+            "tui: cancel 3 entries"
+        );
+
+        let (_, tags) = crate::storage::parse_title("@client(acme) @review");
+        EntryService::bulk_update(&uuids, &BulkAction::Tag(tags)).unwrap();
+        EntryService::bulk_update(&uuids, &BulkAction::Untag(vec!["review".to_string()])).unwrap();
+        for uuid in uuids {
+            let entry = load().get_entry(uuid).cloned().unwrap();
+            assert_eq!(entry.tags.get("client"), Some(&Some("acme".to_string())));
+            assert!(!entry.tags.contains_key("review"));
+        }
+
+        // Flagging twice unflags
+        EntryService::bulk_update(&uuids, &BulkAction::Flag).unwrap();
+        assert!(
+            load()
+                .get_entry(later.uuid)
+                .unwrap()
+                .tags
+                .contains_key("flagged")
+        );
+        EntryService::bulk_update(&uuids, &BulkAction::Flag).unwrap();
+        assert!(
+            !load()
+                .get_entry(later.uuid)
+                .unwrap()
+                .tags
+                .contains_key("flagged")
+        );
+
+        EntryService::bulk_update(&uuids, &BulkAction::Archive).unwrap();
+        let file = load();
+        assert_eq!(file.get_entries("Archive").map(Vec::len), Some(3));
+        assert!(
+            file.get_entry(later.uuid)
+                .unwrap()
+                .tags
+                .contains_key("from_later")
+        );
+
+        EntryService::bulk_update(&uuids, &BulkAction::Delete).unwrap();
+        let file = load();
+        assert_eq!(file.get_all_entries().len(), 1);
+        assert!(file.get_entry(untouched.uuid).is_some());
     }
 }
@@ -1,45 +1,702 @@
+use crate::models::Dialect;
+use color_eyre::eyre::{WrapErr, eyre};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+const DEFAULT_HISTORY_SIZE: usize = 50;
+const DEFAULT_LOCK_TIMEOUT_SECS: u64 = 10;
+
+/// File names looked up in the current directory and its parents
+pub const LOCAL_CONFIG_NAMES: [&str; 2] = [".daily-log.toml", ".doingrc"];
+/// Prefix of environment variables that override settings, e.g. `DAILY_LOG_HISTORY_SIZE=0`
+pub const ENV_PREFIX: &str = "DAILY_LOG_";
+/// Environment variable pointing at an alternative global config file
+pub const CONFIG_PATH_ENV: &str = "DAILY_LOG_CONFIG";
+
+/// Settings that hold paths; relative values in a local config are relative to that file
+const PATH_KEYS: [&str; 2] = ["doing_file", "backup_dir"];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub doing_file: PathBuf,
+    /// Where undo history snapshots are kept (defaults to the platform data dir)
+    pub backup_dir: Option<PathBuf>,
+    /// Number of changes kept for `undo`; 0 disables history
+    pub history_size: usize,
+    /// Force a file layout instead of detecting it from the file being read
+    pub dialect: Option<Dialect>,
+    /// Seconds to wait for another process to release the doing file
+    pub lock_timeout: u64,
+    /// Editor for `--editor`, used instead of `$VISUAL` / `$EDITOR`
+    pub editor_app: Option<String>,
+    /// Rules that tag entries from their titles
+    pub autotag: AutotagConfig,
+    /// Tags added to every new entry unless `--noauto` is given
+    pub default_tags: Vec<String>,
+    /// Named queries for `view`, saved with `--save`
+    pub views: IndexMap<String, View>,
+    /// Named output templates for `--config_template`; `default` applies when none is given
+    #[serde(deserialize_with = "template_map")]
+    pub templates: IndexMap<String, String>,
+    /// Rates and rounding for `invoice`
+    pub invoice: InvoiceConfig,
+}
+
+/// The `autotag` settings, named as in doing's config
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutotagConfig {
+    /// Words that become a tag of the same name when they appear in a title
+    pub whitelist: Vec<String>,
+    /// Tags added when any of their words (`*` and `?` allowed) appear in a title
+    pub synonyms: IndexMap<String, Vec<String>>,
+    /// `pattern:replacement` rules run against existing tags; a `/r` suffix
+    /// replaces the matching tag instead of adding to it
+    pub transform: Vec<String>,
+}
+
+/// The `invoice` settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InvoiceConfig {
+    /// Tag whose value names the client, as in `@client(acme)`
+    pub client_tag: String,
+    /// Hourly rate for clients without their own
+    pub rate: f64,
+    /// Minutes each entry is rounded to, to the nearest multiple; 0 bills exact times
+    pub rounding: u32,
+    /// Put in front of amounts, e.g. `$`
+    pub currency: String,
+    /// Entries with any of these tags are left out
+    pub non_billable: Vec<String>,
+    /// Rates and rounding by client (or section) name
+    pub clients: IndexMap<String, ClientRate>,
+}
+
+impl Default for InvoiceConfig {
+    fn default() -> Self {
+        Self {
+            client_tag: "client".to_string(),
+            rate: 0.0,
+            rounding: 0,
+            currency: String::new(),
+            non_billable: vec!["non-billable".to_string()],
+            clients: IndexMap::new(),
+        }
+    }
+}
+
+/// A client's own rate and rounding; unset values come from `invoice`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientRate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rounding: Option<u32>,
+}
+
+/// The command a view replays
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewCommand {
+    #[default]
+    Show,
+    Grep,
+    On,
+    Since,
+    Yesterday,
+}
+
+/// A saved query: the filter, display and template options of the command
+/// it was saved from. Views written by hand for doing (`section`, `order`,
+/// space-separated `tags`) read as `show` views.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct View {
+    pub command: ViewCommand,
+    /// The date argument of `on` and `since`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(
+        alias = "section",
+        deserialize_with = "string_or_list",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub sections: Vec<String>,
+    #[serde(
+        deserialize_with = "string_or_list",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub val: Vec<String>,
+    #[serde(alias = "tags_bool", skip_serializing_if = "Option::is_none")]
+    pub bool_op: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub case: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub exact: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub not: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub only_timed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age: Option<String>,
+    /// Maximum number of entries; 0 shows all
+    #[serde(skip_serializing_if = "is_zero")]
+    pub count: usize,
+    #[serde(alias = "order", skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag_order: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag_sort: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub duration: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub hilite: bool,
+    pub times: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub totals: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_template: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            command: ViewCommand::default(),
+            date: None,
+            sections: Vec::new(),
+            tags: Vec::new(),
+            search: None,
+            val: Vec::new(),
+            bool_op: None,
+            case: None,
+            exact: false,
+            not: false,
+            only_timed: false,
+            after: None,
+            before: None,
+            from: None,
+            age: None,
+            count: 0,
+            sort: None,
+            output: None,
+            tag_order: None,
+            tag_sort: None,
+            duration: false,
+            hilite: false,
+            // Matches the `--times` default of the display commands
+            times: true,
+            totals: false,
+            template: None,
+            config_template: None,
+            title: None,
+        }
+    }
+}
+
+fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
+/// Accept a list, or a single string of names separated by spaces or commas
+fn string_or_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrList {
+        String(String),
+        List(Vec<String>),
+    }
+
+    Ok(match StringOrList::deserialize(deserializer)? {
+        StringOrList::String(names) => names
+            .split([' ', ','])
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect(),
+        StringOrList::List(names) => names,
+    })
+}
+
+/// Read templates given as strings, or as doing-style tables with a
+/// `template` key (their other keys are ignored)
+fn template_map<'de, D>(deserializer: D) -> Result<IndexMap<String, String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum TemplateSetting {
+        Template(String),
+        Table { template: Option<String> },
+    }
+
+    Ok(
+        IndexMap::<String, TemplateSetting>::deserialize(deserializer)?
+            .into_iter()
+            .filter_map(|(name, setting)| match setting {
+                TemplateSetting::Template(template) => Some((name, template)),
+                TemplateSetting::Table { template } => template.map(|template| (name, template)),
+            })
+            .collect(),
+    )
+}
+
+/// Whether a config file applies everywhere or only below its directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigScope {
+    Global,
+    Local,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigFile {
+    pub path: PathBuf,
+    pub scope: ConfigScope,
+}
+
+impl ConfigFile {
+    /// Read the file as a JSON value, with relative paths in local files made absolute
+    pub fn read(&self) -> color_eyre::Result<Value> {
+        let mut value = self.read_raw()?;
+
+        if self.scope == ConfigScope::Local
+            && let (Some(settings), Some(dir)) = (value.as_object_mut(), self.path.parent())
+        {
+            for key in PATH_KEYS {
+                if let Some(Value::String(path)) = settings.get_mut(key)
+                    && !path.starts_with('~')
+                    && Path::new(path.as_str()).is_relative()
+                {
+                    *path = dir.join(&*path).to_string_lossy().into_owned();
+                }
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Read the file exactly as written; `.toml` files are TOML, anything else
+    /// (such as a doing `.doingrc`) is YAML. A missing file reads as `null`.
+    pub fn read_raw(&self) -> color_eyre::Result<Value> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Value::Null),
+            Err(e) => return Err(e.into()),
+        };
+
+        if self.is_toml() {
+            toml::from_str(&content)
+                .wrap_err_with(|| format!("invalid config file {}", self.path.display()))
+        } else {
+            serde_yaml::from_str(&content)
+                .wrap_err_with(|| format!("invalid config file {}", self.path.display()))
+        }
+    }
+
+    pub fn is_toml(&self) -> bool {
+        self.path.extension().is_some_and(|ext| ext == "toml")
+    }
 }
 
 impl Config {
+    /// Load the effective configuration, falling back to the defaults if a
+    /// config file is broken. Only for tests: everything else uses `try_load`,
+    /// so a broken config is an error rather than a write to the default file.
+    #[cfg(test)]
     pub fn load() -> Self {
-        // Check for test config using thread ID
-        let test_var = format!("DOING_TEST_CONFIG_{:?}", std::thread::current().id());
-        if let Ok(config_path) = std::env::var(&test_var)
-            && let Ok(content) = std::fs::read_to_string(&config_path)
-            && let Ok(config) = serde_json::from_str(&content)
-        {
-            return config;
+        Self::try_load().unwrap_or_default()
+    }
+
+    /// Load the effective configuration: defaults, then the global file, then
+    /// local files from the outermost directory inwards, then environment variables.
+    pub fn try_load() -> color_eyre::Result<Self> {
+        serde_json::from_value(Self::effective_value()?).wrap_err("invalid configuration")
+    }
+
+    /// The merged settings as a JSON object, including keys `Config` doesn't know about
+    pub fn effective_value() -> color_eyre::Result<Value> {
+        let mut value = serde_json::to_value(Self::default())?;
+        for file in Self::files() {
+            merge_values(&mut value, file.read()?);
         }
+        if test_config_path().is_none() {
+            merge_values(&mut value, env_overrides(std::env::vars()));
+        }
+        Ok(value)
+    }
 
-        Self::default()
+    /// Config files that exist and apply to the current directory, in the order they are merged
+    pub fn files() -> Vec<ConfigFile> {
+        // Tests get exactly one config file and nothing from the environment
+        if let Some(path) = test_config_path() {
+            return vec![ConfigFile {
+                path,
+                scope: ConfigScope::Global,
+            }];
+        }
+
+        let global = Self::global_path();
+        let mut files = Vec::new();
+        if global.is_file() {
+            files.push(ConfigFile {
+                path: global.clone(),
+                scope: ConfigScope::Global,
+            });
+        }
+        if let Ok(cwd) = std::env::current_dir() {
+            files.extend(
+                local_config_paths(&cwd)
+                    .into_iter()
+                    .filter(|path| *path != global)
+                    .map(|path| ConfigFile {
+                        path,
+                        scope: ConfigScope::Local,
+                    }),
+            );
+        }
+        files
+    }
+
+    /// The global config file: `$DAILY_LOG_CONFIG`, else
+    /// `~/.config/daily-log/config.toml`, else an existing `~/.doingrc`.
+    pub fn global_path() -> PathBuf {
+        if let Some(path) = test_config_path() {
+            return path;
+        }
+        if let Some(path) = std::env::var_os(CONFIG_PATH_ENV) {
+            return PathBuf::from(path);
+        }
+
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .unwrap_or_else(|| resolve_home_path(Path::new(".config")));
+        let toml_path = config_home.join("daily-log").join("config.toml");
+
+        let doingrc = resolve_home_path(Path::new(".doingrc"));
+        if !toml_path.exists() && doingrc.is_file() {
+            doingrc
+        } else {
+            toml_path
+        }
     }
 
-    pub fn from_path(path: &std::path::Path) -> color_eyre::Result<Self> {
-        let content = std::fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&content)?)
+    /// Load a single config file on top of the defaults
+    pub fn from_path(path: &Path) -> color_eyre::Result<Self> {
+        let mut value = serde_json::to_value(Self::default())?;
+        let file = ConfigFile {
+            path: path.to_path_buf(),
+            scope: ConfigScope::Global,
+        };
+        merge_values(&mut value, file.read()?);
+        serde_json::from_value(value)
+            .wrap_err_with(|| format!("invalid config file {}", path.display()))
     }
 
     pub fn doing_file_path(&self) -> PathBuf {
-        if self.doing_file.is_absolute() {
-            self.doing_file.clone()
-        } else {
-            dirs::home_dir()
-                .unwrap_or_else(|| PathBuf::from("."))
-                .join(&self.doing_file)
+        resolve_home_path(&self.doing_file)
+    }
+
+    pub fn backup_dir_path(&self) -> PathBuf {
+        match &self.backup_dir {
+            Some(dir) => resolve_home_path(dir),
+            None => dirs::data_local_dir()
+                .unwrap_or_else(|| resolve_home_path(Path::new(".local/share")))
+                .join("daily-log")
+                .join("backups"),
         }
     }
+
+    pub fn lock_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.lock_timeout)
+    }
+}
+
+fn test_config_path() -> Option<PathBuf> {
+    let test_var = format!("DOING_TEST_CONFIG_{:?}", std::thread::current().id());
+    std::env::var_os(test_var).map(PathBuf::from)
+}
+
+/// Local config files in `start` and its ancestors, outermost first
+pub fn local_config_paths(start: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = start
+        .ancestors()
+        .flat_map(|dir| LOCAL_CONFIG_NAMES.iter().map(move |name| dir.join(name)))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.reverse();
+    paths
+}
+
+/// Merge `overlay` into `base`; tables are merged key by key, anything else replaces
+pub fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (_, Value::Null) => {}
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    _ if value.is_null() => {}
+                    Some(existing) if existing.is_object() && value.is_object() => {
+                        merge_values(existing, value);
+                    }
+                    _ => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Settings from `DAILY_LOG_*` variables. `__` separates nested keys, and
+/// values are read as JSON when they parse, so numbers and lists work.
+pub fn env_overrides(vars: impl IntoIterator<Item = (String, String)>) -> Value {
+    let mut settings = Value::Object(Map::new());
+    for (name, raw) in vars {
+        let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        if name == CONFIG_PATH_ENV || key.is_empty() {
+            continue;
+        }
+
+        let key = key.to_lowercase().replace("__", ".");
+        let _ = set_value(&mut settings, &key, parse_setting(&raw));
+    }
+    settings
+}
+
+/// A value typed on the command line or in the environment: JSON if it parses, else a string
+pub fn parse_setting(raw: &str) -> Value {
+    serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+}
+
+/// Look up a dotted key such as `autotag.whitelist`
+pub fn get_value<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.')
+        .try_fold(value, |value, part| value.get(part))
+}
+
+/// Set a dotted key, creating intermediate tables as needed
+pub fn set_value(value: &mut Value, key: &str, new_value: Value) -> color_eyre::Result<()> {
+    let mut current = value;
+    let mut parts = key.split('.').peekable();
+    while let Some(part) = parts.next() {
+        if part.is_empty() {
+            return Err(eyre!("invalid setting name: {key}"));
+        }
+        if current.is_null() {
+            *current = Value::Object(Map::new());
+        }
+        let Value::Object(map) = current else {
+            return Err(eyre!("{key}: a parent of this setting is not a table"));
+        };
+        if parts.peek().is_none() {
+            map.insert(part.to_string(), new_value);
+            return Ok(());
+        }
+        current = map.entry(part.to_string()).or_insert(Value::Null);
+    }
+    Ok(())
+}
+
+/// Remove a dotted key, returning its old value
+pub fn remove_value(value: &mut Value, key: &str) -> Option<Value> {
+    let (parents, last) = match key.rsplit_once('.') {
+        Some((parents, last)) => (Some(parents), last),
+        None => (None, key),
+    };
+    let parent = match parents {
+        Some(parents) => parents
+            .split('.')
+            .try_fold(value, |value, part| value.get_mut(part))?,
+        None => value,
+    };
+    parent.as_object_mut()?.remove(last)
+}
+
+pub(crate) fn resolve_home_path(path: &Path) -> PathBuf {
+    let home = || dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    if let Ok(rest) = path.strip_prefix("~") {
+        home().join(rest)
+    } else if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        home().join(path)
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             doing_file: PathBuf::from(".doing.taskpaper"),
+            backup_dir: None,
+            history_size: DEFAULT_HISTORY_SIZE,
+            dialect: None,
+            lock_timeout: DEFAULT_LOCK_TIMEOUT_SECS,
+            editor_app: None,
+            autotag: AutotagConfig::default(),
+            default_tags: Vec::new(),
+            views: IndexMap::new(),
+            templates: IndexMap::new(),
+            invoice: InvoiceConfig::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_merge_values_is_deep() {
+        let mut base = json!({"history_size": 50, "autotag": {"whitelist": ["a"], "synonyms": {}}});
+        merge_values(
+            &mut base,
+            json!({"history_size": 10, "autotag": {"whitelist": ["b"]}, "extra": null}),
+        );
+        assert_eq!(
+            base,
+            json!({"history_size": 10, "autotag": {"whitelist": ["b"], "synonyms": {}}})
+        );
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let vars = vec![
+            ("DAILY_LOG_HISTORY_SIZE".to_string(), "0".to_string()),
+            (
+                "DAILY_LOG_DOING_FILE".to_string(),
+                "/tmp/work.md".to_string(),
+            ),
+            (
+                "DAILY_LOG_AUTOTAG__WHITELIST".to_string(),
+                r#"["rust"]"#.to_string(),
+            ),
+            (
+                "DAILY_LOG_CONFIG".to_string(),
+                "/elsewhere.toml".to_string(),
+            ),
+            ("HOME".to_string(), "/home/me".to_string()),
+        ];
+        assert_eq!(
+            env_overrides(vars),
+            json!({
+                "history_size": 0,
+                "doing_file": "/tmp/work.md",
+                "autotag": {"whitelist": ["rust"]},
+            })
+        );
+    }
+
+    #[test]
+    fn test_local_files_nearest_last_with_relative_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("project");
+        let nested = project.join("src");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(
+            temp_dir.path().join(".doingrc"),
+            "history_size: 5\ndoing_file: ~/everything.md\n",
+        )
+        .unwrap();
+        fs::write(
+            project.join(".daily-log.toml"),
+            "doing_file = \"project.taskpaper\"\n",
+        )
+        .unwrap();
+
+        let paths = local_config_paths(&nested);
+        assert_eq!(
+            paths,
+            vec![
+                temp_dir.path().join(".doingrc"),
+                project.join(".daily-log.toml")
+            ]
+        );
+
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        for path in paths {
+            let file = ConfigFile {
+                path,
+                scope: ConfigScope::Local,
+            };
+            merge_values(&mut value, file.read().unwrap());
         }
+        let config: Config = serde_json::from_value(value).unwrap();
+        assert_eq!(config.history_size, 5);
+        assert_eq!(config.doing_file_path(), project.join("project.taskpaper"));
+    }
+
+    #[test]
+    fn test_set_value_creates_tables() {
+        let mut value = json!({"history_size": 50});
+        set_value(&mut value, "autotag.synonyms.meeting", json!(["standup"])).unwrap();
+        assert_eq!(
+            get_value(&value, "autotag.synonyms.meeting"),
+            Some(&json!(["standup"]))
+        );
+        assert!(set_value(&mut value, "history_size.nested", json!(1)).is_err());
+        assert!(set_value(&mut value, "a..b", json!(1)).is_err());
+    }
+
+    #[test]
+    fn test_home_relative_paths() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(
+            resolve_home_path(Path::new("~/doing.md")),
+            home.join("doing.md")
+        );
+        assert_eq!(
+            resolve_home_path(Path::new("doing.md")),
+            home.join("doing.md")
+        );
+        assert_eq!(
+            resolve_home_path(Path::new("/tmp/doing.md")),
+            PathBuf::from("/tmp/doing.md")
+        );
+    }
+
+    #[test]
+    fn test_templates_read_doing_tables() {
+        let config: Config = serde_json::from_value(json!({
+            "templates": {
+                "brief": "%title",
+                "default": {"date_format": "%H:%M", "template": "%date | %title"},
+                "empty": {"wrap_width": 0}
+            }
+        }))
+        .unwrap();
+        assert_eq!(config.templates["brief"], "%title");
+        assert_eq!(config.templates["default"], "%date | %title");
+        assert!(!config.templates.contains_key("empty"));
     }
 }
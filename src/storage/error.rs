@@ -0,0 +1,39 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Errors raised by the storage layer that callers may want to match on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageError {
+    /// The doing file changed on disk between `parse_taskpaper` and `save_taskpaper`.
+    ConcurrentModification { path: PathBuf },
+    /// Another process held the lock on the doing file for longer than `timeout`.
+    LockTimeout { path: PathBuf, timeout: Duration },
+    /// An undo or redo found the file changed since the history record was made.
+    ChangedSinceRecorded { path: PathBuf, command: String },
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::ConcurrentModification { path } => write!(
+                f,
+                "{} was modified by another process since it was read; refusing to overwrite it",
+                path.display()
+            ),
+            StorageError::LockTimeout { path, timeout } => write!(
+                f,
+                "Timed out after {:.1}s waiting for the lock on {}; another daily-log process is still using it",
+                timeout.as_secs_f64(),
+                path.display()
+            ),
+            StorageError::ChangedSinceRecorded { path, command } => write!(
+                f,
+                "{} was changed after `{command}`; refusing to overwrite it (use --force to do it anyway)",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
@@ -0,0 +1,359 @@
+use super::config::Config;
+use super::error::StorageError;
+use super::taskpaper::{resolve_symlinks, write_atomic};
+use chrono::{DateTime, Local};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+const INDEX_FILE: &str = "history.json";
+
+/// One undoable change: the state of every file it touched, taken before the change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub id: Uuid,
+    pub timestamp: DateTime<Local>,
+    pub command: String,
+    pub files: Vec<FileSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSnapshot {
+    pub path: PathBuf,
+    /// Backup file name inside the history directory, `None` if the file didn't exist
+    pub backup: Option<String>,
+    /// The file as the change left it, so a later edit isn't silently reverted.
+    /// `None` for records written before this was tracked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<FileState>,
+}
+
+/// Fingerprint of a file's contents, stable across Rust releases since it's
+/// stored in the history index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileState {
+    Missing,
+    Present { len: u64, hash: u64 },
+}
+
+impl FileState {
+    pub fn of(contents: Option<&[u8]>) -> Self {
+        match contents {
+            Some(contents) => FileState::Present {
+                len: contents.len() as u64,
+                hash: fnv1a(contents),
+            },
+            None => FileState::Missing,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryIndex {
+    undo: Vec<HistoryRecord>,
+    redo: Vec<HistoryRecord>,
+}
+
+/// The command a save belongs to. Every save made during one operation is
+/// grouped into a single history record, so `rotate` (which writes two files)
+/// is undone in one step.
+#[derive(Debug, Clone)]
+struct Operation {
+    id: Uuid,
+    label: String,
+}
+
+thread_local! {
+    static CURRENT_OPERATION: RefCell<Option<Operation>> = const { RefCell::new(None) };
+}
+
+/// Start a new history operation; later saves on this thread are recorded under `label`.
+///
+/// Without an explicit operation, all saves made by the process are grouped
+/// under its command line.
+pub fn begin_operation(label: impl Into<String>) {
+    let operation = Operation {
+        id: Uuid::new_v4(),
+        label: label.into(),
+    };
+    CURRENT_OPERATION.with(|current| *current.borrow_mut() = Some(operation));
+}
+
+fn current_operation() -> Operation {
+    CURRENT_OPERATION.with(|current| {
+        current
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                let args: Vec<String> = std::env::args().skip(1).collect();
+                Operation {
+                    id: Uuid::new_v4(),
+                    label: if args.is_empty() {
+                        "daily-log".to_string()
+                    } else {
+                        args.join(" ")
+                    },
+                }
+            })
+            .clone()
+    })
+}
+
+/// Record `previous` (the current on-disk contents of `path`, if any) before
+/// it is overwritten with `contents`, in the history `config` sets up.
+pub(crate) fn record_change(
+    config: &Config,
+    path: &Path,
+    previous: Option<&[u8]>,
+    contents: &[u8],
+) -> Result<()> {
+    if config.history_size == 0 {
+        return Ok(());
+    }
+
+    let mut history = History::open(config)?;
+    history.record(&current_operation(), path, previous, contents)
+}
+
+/// Undo/redo stacks for the configured doing file, stored in the backup directory.
+pub struct History {
+    dir: PathBuf,
+    limit: usize,
+    index: HistoryIndex,
+}
+
+impl History {
+    pub fn open(config: &Config) -> Result<Self> {
+        let dir = history_dir(config);
+        let index = match fs::read_to_string(dir.join(INDEX_FILE)) {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HistoryIndex::default(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            dir,
+            limit: config.history_size,
+            index,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Changes that can be undone, oldest first
+    pub fn undo_records(&self) -> &[HistoryRecord] {
+        &self.index.undo
+    }
+
+    /// Undone changes that can be redone, oldest first
+    pub fn redo_records(&self) -> &[HistoryRecord] {
+        &self.index.redo
+    }
+
+    /// Revert the most recent change, making it available to `redo`.
+    ///
+    /// Fails if a file was changed since, unless `force` is set.
+    pub fn undo(&mut self, force: bool) -> Result<Option<HistoryRecord>> {
+        let Some(record) = self.index.undo.pop() else {
+            return Ok(None);
+        };
+
+        let reverse = self.restore(&record, force)?;
+        self.index.redo.push(reverse);
+        self.save_index()?;
+        Ok(Some(record))
+    }
+
+    /// Re-apply the most recently undone change.
+    ///
+    /// Fails if a file was changed since the undo, unless `force` is set.
+    pub fn redo(&mut self, force: bool) -> Result<Option<HistoryRecord>> {
+        let Some(record) = self.index.redo.pop() else {
+            return Ok(None);
+        };
+
+        let reverse = self.restore(&record, force)?;
+        self.index.undo.push(reverse);
+        self.save_index()?;
+        Ok(Some(record))
+    }
+
+    fn record(
+        &mut self,
+        operation: &Operation,
+        path: &Path,
+        previous: Option<&[u8]>,
+        contents: &[u8],
+    ) -> Result<()> {
+        let path = resolve_symlinks(path)?;
+        let after = FileState::of(Some(contents));
+
+        let same_operation = self
+            .index
+            .undo
+            .last()
+            .is_some_and(|record| record.id == operation.id);
+
+        if same_operation {
+            let saved = self
+                .index
+                .undo
+                .last_mut()
+                .and_then(|record| record.files.iter_mut().find(|file| file.path == path));
+            match saved {
+                // Keep the state before the first save, but track the latest one
+                Some(file) => file.after = Some(after),
+                None => {
+                    let snapshot = self.write_snapshot(&path, previous, after)?;
+                    if let Some(record) = self.index.undo.last_mut() {
+                        record.files.push(snapshot);
+                    }
+                }
+            }
+        } else {
+            let snapshot = self.write_snapshot(&path, previous, after)?;
+            self.index.undo.push(HistoryRecord {
+                id: operation.id,
+                timestamp: Local::now(),
+                command: operation.label.clone(),
+                files: vec![snapshot],
+            });
+        }
+
+        // A new change invalidates everything that was undone before it
+        for record in std::mem::take(&mut self.index.redo) {
+            self.remove_backups(&record);
+        }
+
+        while self.index.undo.len() > self.limit {
+            let record = self.index.undo.remove(0);
+            self.remove_backups(&record);
+        }
+
+        self.save_index()
+    }
+
+    /// Put every file of `record` back in its recorded state, returning the
+    /// record that reverses this restore.
+    ///
+    /// Unless `force` is set, nothing is touched if any file no longer matches
+    /// the state `record` left it in.
+    fn restore(&self, record: &HistoryRecord, force: bool) -> Result<HistoryRecord> {
+        let mut current_contents = Vec::new();
+        for file in &record.files {
+            let current = match fs::read(&file.path) {
+                Ok(contents) => Some(contents),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+                Err(e) => return Err(e.into()),
+            };
+            if !force
+                && let Some(expected) = file.after
+                && FileState::of(current.as_deref()) != expected
+            {
+                return Err(StorageError::ChangedSinceRecorded {
+                    path: file.path.clone(),
+                    command: record.command.clone(),
+                }
+                .into());
+            }
+            current_contents.push(current);
+        }
+
+        let mut current_files = Vec::new();
+        for (file, current) in record.files.iter().zip(&current_contents) {
+            let restored = match &file.backup {
+                Some(backup) => {
+                    let contents = fs::read(self.dir.join(backup))?;
+                    write_atomic(&file.path, &contents)?;
+                    FileState::of(Some(&contents))
+                }
+                None => {
+                    match fs::remove_file(&file.path) {
+                        Ok(()) => {}
+                        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                        Err(e) => return Err(e.into()),
+                    }
+                    FileState::Missing
+                }
+            };
+            current_files.push(self.write_snapshot(&file.path, current.as_deref(), restored)?);
+        }
+        self.remove_backups(record);
+
+        Ok(HistoryRecord {
+            id: Uuid::new_v4(),
+            timestamp: Local::now(),
+            command: record.command.clone(),
+            files: current_files,
+        })
+    }
+
+    fn write_snapshot(
+        &self,
+        path: &Path,
+        contents: Option<&[u8]>,
+        after: FileState,
+    ) -> Result<FileSnapshot> {
+        let backup = match contents {
+            Some(contents) => {
+                fs::create_dir_all(&self.dir)?;
+                let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("doing");
+                let name = format!(
+                    "{}-{}-{stem}.taskpaper",
+                    Local::now().format("%Y%m%d%H%M%S"),
+                    Uuid::new_v4().simple()
+                );
+                write_atomic(&self.dir.join(&name), contents)?;
+                Some(name)
+            }
+            None => None,
+        };
+
+        Ok(FileSnapshot {
+            path: path.to_path_buf(),
+            backup,
+            after: Some(after),
+        })
+    }
+
+    fn remove_backups(&self, record: &HistoryRecord) {
+        for backup in record.files.iter().filter_map(|file| file.backup.as_ref()) {
+            let _ = fs::remove_file(self.dir.join(backup));
+        }
+    }
+
+    fn save_index(&self) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let content = serde_json::to_string_pretty(&self.index)?;
+        write_atomic(&self.dir.join(INDEX_FILE), content.as_bytes())
+    }
+}
+
+/// Each doing file gets its own history directory inside the backup dir.
+fn history_dir(config: &Config) -> PathBuf {
+    let doing_file = config.doing_file_path();
+    let doing_file = resolve_symlinks(&doing_file).unwrap_or(doing_file);
+    let stem = doing_file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("doing");
+
+    let hash = fnv1a(doing_file.to_string_lossy().as_bytes());
+
+    config
+        .backup_dir_path()
+        .join(format!("{stem}-{:08x}", hash as u32))
+}
+
+/// FNV-1a, which unlike `DefaultHasher` stays the same across Rust releases
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325_u64, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
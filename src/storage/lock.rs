@@ -0,0 +1,164 @@
+use super::config::Config;
+use super::error::StorageError;
+use super::taskpaper::resolve_symlinks;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Exclusive advisory lock guarding a read-modify-write of a doing file.
+///
+/// The lock is taken on a sidecar `.<name>.lock` file rather than the doing
+/// file itself, because atomic saves replace the doing file's inode. It is
+/// released when the guard is dropped.
+#[derive(Debug)]
+pub struct FileLock {
+    file: File,
+    path: PathBuf,
+    doing_file_path: PathBuf,
+}
+
+impl FileLock {
+    /// Lock the given doing file, waiting up to the configured `lock_timeout`
+    pub fn acquire(doing_file_path: &Path) -> color_eyre::Result<Self> {
+        Self::acquire_with_timeout(doing_file_path, Config::try_load()?.lock_timeout())
+    }
+
+    /// Lock the given doing file, failing with `StorageError::LockTimeout` after `timeout`
+    pub fn acquire_with_timeout(
+        doing_file_path: &Path,
+        timeout: Duration,
+    ) -> color_eyre::Result<Self> {
+        let path = lock_path(&resolve_symlinks(doing_file_path)?);
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)?;
+
+        wait_for_lock(&file, doing_file_path, timeout)?;
+        Ok(Self {
+            file,
+            path,
+            doing_file_path: doing_file_path.to_path_buf(),
+        })
+    }
+
+    /// Release the lock while `f` runs, e.g. while waiting on the user, then
+    /// take it back. A doing file parsed before this still refuses to save
+    /// over changes other processes made in the meantime.
+    pub fn suspend<T>(
+        &mut self,
+        f: impl FnOnce() -> color_eyre::Result<T>,
+    ) -> color_eyre::Result<T> {
+        self.file.unlock()?;
+        let result = f();
+        wait_for_lock(
+            &self.file,
+            &self.doing_file_path,
+            Config::try_load()?.lock_timeout(),
+        )?;
+        result
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+fn wait_for_lock(file: &File, doing_file_path: &Path, timeout: Duration) -> color_eyre::Result<()> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(()),
+            Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                thread::sleep(RETRY_INTERVAL);
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(StorageError::LockTimeout {
+                    path: doing_file_path.to_path_buf(),
+                    timeout,
+                }
+                .into());
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+    }
+}
+
+fn lock_path(doing_file_path: &Path) -> PathBuf {
+    let file_name = doing_file_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("doing");
+
+    doing_file_path.with_file_name(format!(".{file_name}.lock"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lock_is_exclusive_until_dropped() {
+        let temp_dir = TempDir::new().unwrap();
+        let doing_file = temp_dir.path().join("doing.taskpaper");
+
+        let lock = FileLock::acquire(&doing_file).unwrap();
+        assert!(lock.path().ends_with(".doing.taskpaper.lock"));
+
+        let err =
+            FileLock::acquire_with_timeout(&doing_file, Duration::from_millis(100)).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<StorageError>(),
+            Some(StorageError::LockTimeout { .. })
+        ));
+
+        drop(lock);
+        assert!(FileLock::acquire_with_timeout(&doing_file, Duration::from_millis(100)).is_ok());
+    }
+
+    #[test]
+    fn test_lock_waits_for_release() {
+        let temp_dir = TempDir::new().unwrap();
+        let doing_file = temp_dir.path().join("doing.taskpaper");
+
+        let lock = FileLock::acquire(&doing_file).unwrap();
+        let releaser = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            drop(lock);
+        });
+
+        assert!(FileLock::acquire_with_timeout(&doing_file, Duration::from_secs(5)).is_ok());
+        releaser.join().unwrap();
+    }
+
+    #[test]
+    fn test_suspend_releases_the_lock() {
+        let temp_dir = TempDir::new().unwrap();
+        let doing_file = temp_dir.path().join("doing.taskpaper");
+
+        let mut lock = FileLock::acquire(&doing_file).unwrap();
+        lock.suspend(|| {
+            FileLock::acquire_with_timeout(&doing_file, Duration::from_millis(100)).map(drop)
+        })
+        .unwrap();
+
+        assert!(FileLock::acquire_with_timeout(&doing_file, Duration::from_millis(100)).is_err());
+    }
+}
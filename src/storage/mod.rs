@@ -1,6 +1,15 @@
 pub mod config;
+pub mod error;
+pub mod history;
+pub mod lock;
 pub mod taskpaper;
 
 pub use crate::models::DoingFile;
 pub use config::Config;
-pub use taskpaper::{format_taskpaper, parse_taskpaper, save_taskpaper};
+pub use error::StorageError;
+pub use history::History;
+pub use lock::FileLock;
+pub use taskpaper::{
+    format_taskpaper, parse_taskpaper, parse_taskpaper_content, parse_title, save_taskpaper,
+    write_atomic,
+};
@@ -1,91 +1,208 @@
-use crate::models::{DoingFile, Entry};
+use super::config::Config;
+use super::error::StorageError;
+use super::history;
+use crate::models::{Dialect, DiskState, DoingFile, Entry, Section, UnknownLine};
 use chrono::{Local, TimeZone};
+use indexmap::IndexMap;
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicBool, Ordering};
 use uuid::Uuid;
 
+const MAX_SYMLINK_DEPTH: usize = 40;
+
+static REPORT_WARNINGS: AtomicBool = AtomicBool::new(false);
+
+/// Namespace of the ids derived for entries written without one
+const DERIVED_ID_NAMESPACE: Uuid = Uuid::from_u128(0x6c1f_0d6e_5b3a_4f7e_9a2d_8e41_c7b0_53d9);
+
+// Tags must start a word, so e-mail addresses aren't mistaken for tags
+static TAG_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|\s)@([\w.-]*\w)(?:\(([^)]+)\))?").unwrap());
+
+/// Print a warning to stderr for every line `parse_taskpaper` doesn't recognize.
+/// Off by default so the TUI and library users keep a clean terminal.
+pub fn set_report_warnings(enabled: bool) {
+    REPORT_WARNINGS.store(enabled, Ordering::Relaxed);
+}
+
 pub fn parse_taskpaper(path: &Path) -> color_eyre::Result<DoingFile> {
+    let configured_dialect = Config::try_load()?.dialect;
+
     let content = match fs::read_to_string(path) {
         Ok(content) => content,
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            return Ok(DoingFile::new(path.to_path_buf()));
+            let mut doing_file = DoingFile::new(path.to_path_buf());
+            doing_file.disk_state = Some(DiskState::Missing);
+            if doing_file.sections.is_empty() {
+                doing_file
+                    .sections
+                    .insert(Section::Currently.as_str().to_string(), Vec::new());
+            }
+
+            doing_file.dialect = configured_dialect.unwrap_or_default();
+            return Ok(doing_file);
         }
         Err(e) => return Err(e.into()),
     };
 
+    let mut doing_file = parse_taskpaper_content(path, &content)?;
+    doing_file.dialect = configured_dialect.unwrap_or(doing_file.dialect);
+
+    if REPORT_WARNINGS.load(Ordering::Relaxed) {
+        for unknown in doing_file.unknown_lines.iter().filter(|l| !l.is_blank()) {
+            eprintln!(
+                "warning: {}:{}: unrecognized line kept as-is: {}",
+                path.display(),
+                unknown.line_number,
+                unknown.text
+            );
+        }
+    }
+
+    Ok(doing_file)
+}
+
+/// Parse doing file contents that were read from `path`, detecting the dialect.
+pub fn parse_taskpaper_content(path: &Path, content: &str) -> color_eyre::Result<DoingFile> {
     let mut doing_file = DoingFile::new(path.to_path_buf());
+    doing_file.disk_state = Some(DiskState::from_contents(content.as_bytes()));
+    // Only sections that are actually in the file, in file order
+    doing_file.sections.clear();
+    let mut detected_dialect = None;
     let mut current_section = "Currently".to_string();
+    let mut seen_header = false;
     let mut current_entry: Option<Entry> = None;
+    // Entry the next unrecognized line should be anchored after
+    let mut anchor: Option<Uuid> = None;
+    // Line numbers of the blank lines seen since the last line that wasn't
+    // blank; part of the note if it continues, kept in place otherwise
+    let mut pending_blank_lines: Vec<usize> = Vec::new();
+    // Ids derived so far, so identical entries still get different ones
+    let mut derived_ids = HashSet::new();
 
     let project_regex = Regex::new(r"^(.+):$")?;
-    // Updated regex to match: - YYYY-MM-DD HH:MM | description <uuid>
-    let task_regex =
-        Regex::new(r"^ - (\d{4}-\d{2}-\d{2} \d{2}:\d{2}) \| (.+?) <([a-f0-9-]{36})>$")?;
-    let tag_regex = Regex::new(r"@(\w+)(?:\(([^)]+)\))?")?;
-
-    for line in content.lines() {
-        if let Some(captures) = project_regex.captures(line) {
-            if let Some(entry) = current_entry.take() {
-                doing_file.add_entry(entry);
+    // Matches both daily-log (` - ... <hyphenated-uuid>`) and Ruby doing
+    // (`\t- ... <32-hex-id>`, or no id at all) entries
+    let task_regex = Regex::new(
+        r"^([ \t]*)- (\d{4}-\d{2}-\d{2} \d{2}:\d{2}) \| (.*?)(?: <([0-9a-fA-F]{32}|[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12})>)?[ \t]*$",
+    )?;
+
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            pending_blank_lines.push(index + 1);
+            continue;
+        }
+
+        // Parse timestamp as local time
+        let task = task_regex.captures(line).and_then(|captures| {
+            chrono::NaiveDateTime::parse_from_str(&captures[2], "%Y-%m-%d %H:%M")
+                .ok()
+                .and_then(|naive| Local.from_local_datetime(&naive).single())
+                .map(|timestamp| (captures, timestamp))
+        });
+
+        if task.is_none()
+            && line.starts_with([' ', '\t'])
+            && let Some(ref mut entry) = current_entry
+        {
+            let note_line = strip_note_indent(line);
+            if let Some(existing_note) = &mut entry.note {
+                for _ in 0..pending_blank_lines.len() {
+                    existing_note.push('\n');
+                }
+                existing_note.push('\n');
+                existing_note.push_str(note_line);
+            } else {
+                entry.note = Some(note_line.to_string());
             }
+            pending_blank_lines.clear();
+            continue;
+        }
+
+        if let Some(entry) = current_entry.take() {
+            doing_file.add_entry(entry);
+        }
+
+        let header = if task.is_none() {
+            project_regex.captures(line)
+        } else {
+            None
+        };
+
+        // Blank lines closing a section are its gap before the next header;
+        // any others stay where they were, after the entry before them
+        if header.is_some() && seen_header {
+            doing_file
+                .section_gaps
+                .insert(current_section.clone(), pending_blank_lines.len());
+        } else {
+            for line_number in &pending_blank_lines {
+                doing_file.unknown_lines.push(UnknownLine {
+                    line_number: *line_number,
+                    section: seen_header.then(|| current_section.clone()),
+                    after: anchor,
+                    text: String::new(),
+                });
+            }
+        }
+        pending_blank_lines.clear();
+
+        if let Some(captures) = header {
             current_section = captures[1].to_string();
+            seen_header = true;
+            anchor = None;
             // Ensure the section exists even if it's empty
             doing_file
                 .sections
                 .entry(current_section.clone())
                 .or_default();
-        } else if let Some(captures) = task_regex.captures(line) {
-            if let Some(entry) = current_entry.take() {
-                doing_file.add_entry(entry);
-            }
-
-            let timestamp_str = &captures[1];
-            let task_line = &captures[2];
-            let uuid_str = &captures[3];
-
-            // Parse timestamp as local time
-            let timestamp = chrono::NaiveDateTime::parse_from_str(timestamp_str, "%Y-%m-%d %H:%M")
-                .ok()
-                .and_then(|naive| Local.from_local_datetime(&naive).single())
-                .unwrap_or_else(Local::now);
-
-            // Parse UUID
-            let uuid = Uuid::parse_str(uuid_str).unwrap_or_else(|_| Uuid::new_v4());
-
-            let mut description = task_line.to_string();
-            let mut tags = HashMap::new();
+            continue;
+        }
 
-            // Extract tags from the description
-            for tag_capture in tag_regex.captures_iter(task_line) {
-                let tag_name = tag_capture[1].to_string();
-                let tag_value = tag_capture.get(2).map(|m| m.as_str().to_string());
-                tags.insert(tag_name, tag_value);
+        if let Some((captures, timestamp)) = task {
+            let task_line = &captures[3];
 
-                description = description.replace(&tag_capture[0], "").trim().to_string();
+            if detected_dialect.is_none() {
+                detected_dialect = Some(if captures[1].contains('\t') {
+                    Dialect::Doing
+                } else {
+                    Dialect::DailyLog
+                });
             }
 
+            // Entries written by older versions of doing have no id; they get
+            // one derived from the entry, so parsing the file again finds the
+            // same entry by id, and keep it from the next save on
+            let uuid = captures
+                .get(4)
+                .and_then(|id| Uuid::parse_str(id.as_str()).ok())
+                .unwrap_or_else(|| {
+                    derived_id(&current_section, &captures[2], task_line, &mut derived_ids)
+                });
+
+            let (description, tags) = parse_title(task_line);
             let mut entry = Entry::new(description, current_section.clone());
             entry.tags = tags;
-            entry.timestamp = timestamp;
+            entry.started_at = timestamp;
+            entry.sync_finished_at();
             entry.uuid = uuid;
+            entry.keep_source_title(task_line);
 
+            anchor = Some(uuid);
             current_entry = Some(entry);
-        } else if line.starts_with("  ") && !line.starts_with(" -") && current_entry.is_some() {
-            if let Some(ref mut entry) = current_entry {
-                let note_line = line.trim_start();
-                if let Some(existing_note) = &mut entry.note {
-                    existing_note.push('\n');
-                    existing_note.push_str(note_line);
-                } else {
-                    entry.note = Some(note_line.to_string());
-                }
-            }
-        } else if line.trim().is_empty()
-            && let Some(entry) = current_entry.take()
-        {
-            doing_file.add_entry(entry);
+        } else {
+            // Keep anything we don't understand exactly where it was
+            doing_file.unknown_lines.push(UnknownLine {
+                line_number: index + 1,
+                section: seen_header.then(|| current_section.clone()),
+                after: anchor,
+                text: line.to_string(),
+            });
         }
     }
 
@@ -93,15 +210,163 @@ pub fn parse_taskpaper(path: &Path) -> color_eyre::Result<DoingFile> {
         doing_file.add_entry(entry);
     }
 
+    if doing_file.sections.is_empty() {
+        doing_file
+            .sections
+            .insert(Section::Currently.as_str().to_string(), Vec::new());
+    }
+
+    doing_file.dialect = detected_dialect.unwrap_or_default();
+
     Ok(doing_file)
 }
 
-pub fn save_taskpaper(doing_file: &DoingFile) -> color_eyre::Result<()> {
+/// A stable id for an entry without one, from its section, start and title.
+/// Entries that are the same in all three are told apart by their order.
+fn derived_id(section: &str, started: &str, title: &str, taken: &mut HashSet<Uuid>) -> Uuid {
+    let name = format!("{section}\n{started}\n{title}");
+    let mut uuid = Uuid::new_v5(&DERIVED_ID_NAMESPACE, name.as_bytes());
+    let mut occurrence = 1;
+    while !taken.insert(uuid) {
+        occurrence += 1;
+        uuid = Uuid::new_v5(
+            &DERIVED_ID_NAMESPACE,
+            format!("{name}\n{occurrence}").as_bytes(),
+        );
+    }
+    uuid
+}
+
+/// Split an entry title into its description and its `@tag(value)` tags
+pub fn parse_title(title: &str) -> (String, IndexMap<String, Option<String>>) {
+    let mut description = String::new();
+    let mut tags = IndexMap::new();
+    let mut rest = 0;
+
+    // Each tag goes with the space before it, so none are left doubled
+    for tag_capture in TAG_REGEX.captures_iter(title) {
+        let tag = tag_capture.get(0).unwrap();
+        description.push_str(&title[rest..tag.start()]);
+        rest = tag.end();
+
+        let tag_name = tag_capture[1].to_string();
+        let tag_value = tag_capture.get(2).map(|m| m.as_str().to_string());
+        tags.insert(tag_name, tag_value);
+    }
+    description.push_str(&title[rest..]);
+
+    (description.trim().to_string(), tags)
+}
+
+/// Remove the indentation a note line was written with, keeping any extra
+/// indentation that belongs to the note itself.
+fn strip_note_indent(line: &str) -> &str {
+    ["\t\t", "\t", "  "]
+        .iter()
+        .find_map(|prefix| line.strip_prefix(prefix))
+        .unwrap_or_else(|| line.trim_start())
+}
+
+/// Save the doing file atomically, refusing to overwrite changes made by
+/// another process since the file was parsed. The previous contents go to the
+/// history `config` sets up.
+pub fn save_taskpaper(doing_file: &DoingFile, config: &Config) -> color_eyre::Result<()> {
+    let target = resolve_symlinks(&doing_file.path)?;
+    let current = read_existing(&target)?;
+
+    if let Some(expected) = doing_file.disk_state {
+        let actual = match &current {
+            Some(contents) => DiskState::from_contents(contents),
+            None => DiskState::Missing,
+        };
+        if actual != expected {
+            return Err(StorageError::ConcurrentModification {
+                path: doing_file.path.clone(),
+            }
+            .into());
+        }
+    }
+
     let content = doing_file.to_taskpaper();
-    fs::write(&doing_file.path, content)?;
+    if current.as_deref() == Some(content.as_bytes()) {
+        return Ok(());
+    }
+
+    history::record_change(config, &target, current.as_deref(), content.as_bytes())?;
+    write_atomic(&target, content.as_bytes())
+}
+
+/// Replace `path` with `content` via a synced temp file and a rename, so a
+/// crash leaves either the old or the new contents. Symlinks are followed and
+/// existing permissions are kept.
+pub fn write_atomic(path: &Path, content: &[u8]) -> color_eyre::Result<()> {
+    let target = resolve_symlinks(path)?;
+    let dir = match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let permissions = fs::metadata(&target).ok().map(|m| m.permissions());
+
+    let mut temp_file = tempfile::Builder::new()
+        .prefix(".daily-log-")
+        .suffix(".tmp")
+        .tempfile_in(dir)?;
+    temp_file.write_all(content)?;
+    if let Some(permissions) = permissions {
+        temp_file.as_file().set_permissions(permissions)?;
+    }
+    temp_file.as_file().sync_all()?;
+    temp_file.persist(&target).map_err(|e| e.error)?;
+
+    sync_dir(dir);
     Ok(())
 }
 
+fn read_existing(path: &Path) -> io::Result<Option<Vec<u8>>> {
+    match fs::read(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Follow symlinks (including dangling ones) to the path that should actually be written.
+pub(crate) fn resolve_symlinks(path: &Path) -> io::Result<PathBuf> {
+    let mut current = path.to_path_buf();
+
+    for _ in 0..MAX_SYMLINK_DEPTH {
+        match fs::symlink_metadata(&current) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                let link = fs::read_link(&current)?;
+                current = match current.parent() {
+                    Some(parent) if link.is_relative() => parent.join(link),
+                    _ => link,
+                };
+            }
+            Ok(_) => return Ok(current),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(current),
+            Err(e) => return Err(e),
+        }
+    }
+
+    Err(io::Error::other(format!(
+        "too many levels of symbolic links: {}",
+        path.display()
+    )))
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) {
+    // Persist the rename itself; failure here doesn't affect the written data
+    if let Ok(dir) = fs::File::open(dir) {
+        let _ = dir.sync_all();
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) {}
+
 pub fn format_taskpaper(doing_file: &DoingFile) -> String {
     doing_file.to_taskpaper()
 }
@@ -109,7 +374,7 @@ pub fn format_taskpaper(doing_file: &DoingFile) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Write;
+    use crate::test_utils::utils::TestContext;
     use tempfile::NamedTempFile;
 
     #[test]
@@ -146,4 +411,373 @@ mod tests {
             Some(&Some("high".to_string()))
         );
     }
+
+    #[test]
+    fn test_unknown_lines_survive_round_trip() {
+        let ctx = TestContext::new().unwrap();
+        let content = "\
+# Work log
+Currently:
+ - 2025-07-28 16:24 | Working on parser <7a1185c6-0241-52ac-0771-83f31c40acdd>
+// reviewed with team
+ - yesterday 5pm | Task with a loose date
+ - 2025-07-28 12:28 | Completed task @done(2025-07-28 13:58) <e520e775-3401-241c-b8d2-ef3ad6ba3fa7>
+28/07/2025 9am | Hand-written entry
+
+Archive:
+ - 2025-07-28 09:37 | Archived task <ff20b760-b698-fe0b-d7a8-00213cb6cc3f>
+";
+        ctx.create_test_file(content).unwrap();
+
+        let doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        let unknown: Vec<(usize, &str)> = doing_file
+            .unknown_lines
+            .iter()
+            .map(|l| (l.line_number, l.text.as_str()))
+            .collect();
+        assert_eq!(
+            unknown,
+            vec![
+                (1, "# Work log"),
+                (4, "// reviewed with team"),
+                (5, " - yesterday 5pm | Task with a loose date"),
+                (7, "28/07/2025 9am | Hand-written entry"),
+            ]
+        );
+        assert_eq!(doing_file.get_all_entries().len(), 3);
+
+        assert_eq!(doing_file.to_taskpaper(), content);
+    }
+
+    #[test]
+    fn test_note_with_blank_lines_is_kept_together() {
+        let ctx = TestContext::new().unwrap();
+        let content = "\
+Currently:
+ - 2025-07-28 16:24 | Working on parser <7a1185c6-0241-52ac-0771-83f31c40acdd>
+  First paragraph
+
+  Second paragraph
+";
+        ctx.create_test_file(content).unwrap();
+
+        let doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        let entry = &doing_file.get_entries("Currently").unwrap()[0];
+        assert_eq!(
+            entry.note.as_deref(),
+            Some("First paragraph\n\nSecond paragraph")
+        );
+        assert!(doing_file.unknown_lines.is_empty());
+        assert_eq!(doing_file.to_taskpaper(), content);
+    }
+
+    #[test]
+    fn test_blank_lines_between_entries_survive_save() {
+        let ctx = TestContext::new().unwrap();
+        let content = "\
+Currently:
+
+ - 2025-07-28 16:24 | Working on parser <7a1185c6-0241-52ac-0771-83f31c40acdd>
+  A note
+
+ - 2025-07-28 12:28 | Completed task @done(2025-07-28 13:58) <e520e775-3401-241c-b8d2-ef3ad6ba3fa7>
+
+
+Archive:
+ - 2025-07-28 09:37 | Archived task <ff20b760-b698-fe0b-d7a8-00213cb6cc3f>
+ - 2025-07-27 09:37 | Older task <0a1b2c3d-4e5f-4071-8293-a4b5c6d7e8f9>
+";
+        ctx.create_test_file(content).unwrap();
+        let config = Config::load();
+
+        let doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        assert!(doing_file.unknown_lines.iter().all(|line| line.is_blank()));
+        save_taskpaper(&doing_file, &config).unwrap();
+        assert_eq!(ctx.read_test_file().unwrap(), content);
+
+        // A new entry goes after the last one, before the gap to Archive
+        let mut doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        let entry = Entry::new("New thing".to_string(), "Currently".to_string());
+        let line = entry.to_taskpaper_as(doing_file.dialect);
+        doing_file.add_entry(entry);
+        save_taskpaper(&doing_file, &config).unwrap();
+        assert_eq!(
+            ctx.read_test_file().unwrap(),
+            content.replace("\n\n\nArchive:", &format!("\n{line}\n\n\nArchive:"))
+        );
+    }
+
+    #[test]
+    fn test_titles_with_tags_inside_survive_save() {
+        let ctx = TestContext::new().unwrap();
+        let content = "\
+Currently:
+ - 2025-07-28 16:24 | Fix @bug in parser <7a1185c6-0241-52ac-0771-83f31c40acdd>
+ - 2025-07-28 12:28 | Pair with @client(acme) on @api docs <e520e775-3401-241c-b8d2-ef3ad6ba3fa7>
+";
+        ctx.create_test_file(content).unwrap();
+        let config = Config::load();
+
+        let mut doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        let entries = doing_file.get_entries("Currently").unwrap();
+        assert_eq!(entries[0].description, "Fix in parser");
+        assert_eq!(entries[1].description, "Pair with on docs");
+        save_taskpaper(&doing_file, &config).unwrap();
+        assert_eq!(ctx.read_test_file().unwrap(), content);
+
+        // Only the entry that changed is written anew
+        let mut entry = doing_file.get_entries("Currently").unwrap()[1].clone();
+        entry.finish(crate::models::entry::parse_time("2025-07-28 13:00").unwrap());
+        doing_file.update_entry(entry);
+        save_taskpaper(&doing_file, &config).unwrap();
+        assert_eq!(
+            ctx.read_test_file().unwrap(),
+            content.replace(
+                "Pair with @client(acme) on @api docs",
+                "Pair with on docs @client(acme) @api @done(2025-07-28 13:00)"
+            )
+        );
+    }
+
+    fn fixture(name: &str) -> String {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name);
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn test_parse_doing_v2_file() {
+        let ctx = TestContext::new().unwrap();
+        ctx.create_test_file(&fixture("doing_v2.md")).unwrap();
+
+        let doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        assert_eq!(doing_file.dialect, Dialect::Doing);
+        assert!(doing_file.unknown_lines.is_empty());
+
+        let currently = doing_file.get_entries("Currently").unwrap();
+        assert_eq!(currently.len(), 3);
+        assert_eq!(currently[0].description, "Writing release notes for v2.1");
+        assert_eq!(
+            currently[0].uuid,
+            Uuid::parse_str("1c2d3e4f5a6b47c8d9e0f1a2b3c4d5e6").unwrap()
+        );
+        assert_eq!(
+            currently[0].note.as_deref(),
+            Some("Draft lives in the wiki\n- changelog\n\t- breaking changes")
+        );
+        assert_eq!(
+            currently[1].tags.get("done"),
+            Some(&Some("2023-09-15 10:20".to_string()))
+        );
+        assert_eq!(
+            currently[1].finished_at,
+            crate::models::entry::parse_time("2023-09-15 10:20")
+        );
+        assert_eq!(
+            currently[2].description,
+            "Pairing with jane@example.com on the client"
+        );
+        assert!(currently[2].tags.contains_key("api-v2"));
+        assert!(!currently[2].tags.contains_key("example.com"));
+
+        assert_eq!(doing_file.get_entries("Later").unwrap().len(), 1);
+        assert_eq!(doing_file.get_entries("Archive").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_doing_v2_entries_keep_their_style() {
+        let ctx = TestContext::new().unwrap();
+        let content = fixture("doing_v2.md");
+        ctx.create_test_file(&content).unwrap();
+
+        let doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        assert_eq!(doing_file.to_taskpaper(), content);
+    }
+
+    #[test]
+    fn test_unchanged_file_saves_with_zero_diff() {
+        let ctx = TestContext::new().unwrap();
+        let content = "\
+Later:
+ - 2025-07-28 09:00 | Plan sprint @zeta @alpha @project(api) <7a1185c6-0241-52ac-0771-83f31c40acdd>
+
+Currently:
+ - 2025-07-28 16:24 | Working on parser @wip @bug <e520e775-3401-241c-b8d2-ef3ad6ba3fa7>
+
+Archive:
+ - 2025-07-28 09:37 | Archived task @done(2025-07-28 10:00) @client(acme) <ff20b760-b698-fe0b-d7a8-00213cb6cc3f>
+";
+        ctx.create_test_file(content).unwrap();
+
+        let doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        let sections: Vec<&str> = doing_file.sections.keys().map(String::as_str).collect();
+        assert_eq!(sections, vec!["Later", "Currently", "Archive"]);
+        let tags: Vec<&str> = doing_file.get_entries("Later").unwrap()[0]
+            .tags
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(tags, vec!["zeta", "alpha", "project"]);
+
+        assert_eq!(doing_file.to_taskpaper(), content);
+    }
+
+    #[test]
+    fn test_new_tags_and_sections_are_appended() {
+        let ctx = TestContext::new().unwrap();
+        ctx.create_test_file(
+            "Later:\n - 2025-07-28 09:00 | Plan sprint @zeta @alpha <7a1185c6-0241-52ac-0771-83f31c40acdd>\n",
+        )
+        .unwrap();
+
+        let mut doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        let entry = &mut doing_file.sections.get_mut("Later").unwrap()[0];
+        entry.tags.insert("beta".to_string(), None);
+        entry.tags.shift_remove("zeta");
+        doing_file.add_entry(Entry::new("Next".to_string(), "Ideas".to_string()));
+
+        let output = doing_file.to_taskpaper();
+        assert!(output.starts_with("Later:\n - 2025-07-28 09:00 | Plan sprint @alpha @beta <"));
+        assert!(output.contains("\n\nIdeas:\n - "));
+        assert!(!output.contains("Currently:"));
+    }
+
+    #[test]
+    fn test_doing_v1_entries_get_ids_on_save() {
+        let ctx = TestContext::new().unwrap();
+        ctx.create_test_file(&fixture("doing_v1.md")).unwrap();
+
+        let doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        assert_eq!(doing_file.dialect, Dialect::Doing);
+        assert_eq!(doing_file.get_all_entries().len(), 3);
+        // The ids are derived from the entries, so they are found again
+        // when the file is read before it was saved
+        assert_eq!(
+            parse_taskpaper(&ctx.doing_file_path).unwrap().sections,
+            doing_file.sections
+        );
+        assert_eq!(
+            doing_file.get_entries("Currently").unwrap()[1]
+                .note
+                .as_deref(),
+            Some("Agenda: velocity, backlog grooming")
+        );
+        save_taskpaper(&doing_file, &Config::load()).unwrap();
+
+        let saved = ctx.read_test_file().unwrap();
+        let id_regex = Regex::new(r"^\t- .* <[0-9a-f]{32}>$").unwrap();
+        let entry_lines: Vec<&str> = saved.lines().filter(|l| l.starts_with("\t- ")).collect();
+        assert_eq!(entry_lines.len(), 3);
+        assert!(entry_lines.iter().all(|l| id_regex.is_match(l)));
+        assert!(saved.contains("\t\tAgenda: velocity, backlog grooming"));
+
+        let reloaded = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        let mut before: Vec<Uuid> = doing_file
+            .get_all_entries()
+            .iter()
+            .map(|e| e.uuid)
+            .collect();
+        let mut after: Vec<Uuid> = reloaded.get_all_entries().iter().map(|e| e.uuid).collect();
+        before.sort();
+        after.sort();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_configured_dialect_overrides_detection() {
+        let ctx = TestContext::new().unwrap();
+        let mut config = Config::load();
+        config.dialect = Some(Dialect::DailyLog);
+        fs::write(&ctx.config_path, serde_json::to_string(&config).unwrap()).unwrap();
+        ctx.create_test_file(&fixture("doing_v1.md")).unwrap();
+
+        let doing_file = parse_taskpaper(&ctx.doing_file_path).unwrap();
+        assert_eq!(doing_file.dialect, Dialect::DailyLog);
+        assert!(
+            doing_file
+                .to_taskpaper()
+                .contains("\n - 2016-03-02 09:15 | Answering email")
+        );
+    }
+
+    #[test]
+    fn test_save_round_trip() {
+        let ctx = TestContext::new().unwrap();
+        let path = ctx.doing_file_path.clone();
+
+        let mut doing_file = parse_taskpaper(&path).unwrap();
+        doing_file.add_entry(Entry::new("First".to_string(), "Currently".to_string()));
+        save_taskpaper(&doing_file, &Config::load()).unwrap();
+
+        let reloaded = parse_taskpaper(&path).unwrap();
+        assert_eq!(reloaded.get_entries("Currently").unwrap().len(), 1);
+
+        // No temp files are left behind
+        let leftovers: Vec<_> = fs::read_dir(ctx.temp_dir.path())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[test]
+    fn test_save_detects_concurrent_modification() {
+        let ctx = TestContext::new().unwrap();
+        let path = ctx.doing_file_path.clone();
+        fs::write(&path, "Currently:\n").unwrap();
+
+        let mut doing_file = parse_taskpaper(&path).unwrap();
+        doing_file.add_entry(Entry::new("Mine".to_string(), "Currently".to_string()));
+
+        let external =
+            "Currently:\n - 2025-07-28 16:24 | Theirs <7a1185c6-0241-52ac-0771-83f31c40acdd>\n";
+        fs::write(&path, external).unwrap();
+
+        let err = save_taskpaper(&doing_file, &Config::load()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<StorageError>(),
+            Some(&StorageError::ConcurrentModification { path: path.clone() })
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), external);
+    }
+
+    #[test]
+    fn test_save_detects_file_created_after_parse() {
+        let ctx = TestContext::new().unwrap();
+        let path = ctx.doing_file_path.clone();
+
+        let doing_file = parse_taskpaper(&path).unwrap();
+        fs::write(&path, "Currently:\n").unwrap();
+
+        assert!(save_taskpaper(&doing_file, &Config::load()).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_save_keeps_permissions_and_symlinks() {
+        use std::os::unix::fs::{PermissionsExt, symlink};
+
+        let ctx = TestContext::new().unwrap();
+        let target = ctx.temp_dir.path().join("real.taskpaper");
+        let link = ctx.temp_dir.path().join("link.taskpaper");
+        fs::write(&target, "Currently:\n").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o640)).unwrap();
+        symlink(&target, &link).unwrap();
+
+        let mut doing_file = parse_taskpaper(&link).unwrap();
+        doing_file.add_entry(Entry::new("Linked".to_string(), "Currently".to_string()));
+        save_taskpaper(&doing_file, &Config::load()).unwrap();
+
+        assert!(
+            fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert!(fs::read_to_string(&target).unwrap().contains("Linked"));
+        let mode = fs::metadata(&target).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
 }
@@ -0,0 +1,371 @@
+use crate::display::{calculate_duration, format_date, format_duration, format_tags};
+use crate::models::Entry;
+use crate::storage::Config;
+use chrono::{DateTime, Local};
+use color_eyre::Result;
+use color_eyre::eyre::eyre;
+use std::io::IsTerminal;
+
+/// Width of `%hr` and `%hr_under` when the placeholder doesn't give one
+const DEFAULT_RULE_WIDTH: usize = 40;
+
+/// A doing-style output template such as `%shortdate | %title%note`.
+///
+/// Placeholders may carry a width between the `%` and the name: `%10section`
+/// pads to 10 characters on the left, `%-10section` on the right, and
+/// `%-10.20title` also cuts the value down to 20 characters. `%%` is a literal `%`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    tokens: Vec<Token>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Text(String),
+    /// An SGR parameter, e.g. `1;36` for `%boldcyan`
+    Color(String),
+    Field {
+        field: Field,
+        width: Option<usize>,
+        left: bool,
+        max: Option<usize>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Date,
+    ShortDate,
+    Title,
+    Section,
+    Note,
+    OutdentedNote,
+    ChompNote,
+    Interval,
+    Duration,
+    Tags,
+    Rule(char),
+}
+
+const FIELDS: [(&str, Field); 12] = [
+    ("date", Field::Date),
+    ("shortdate", Field::ShortDate),
+    ("title", Field::Title),
+    ("section", Field::Section),
+    ("note", Field::Note),
+    ("idnote", Field::Note),
+    ("odnote", Field::OutdentedNote),
+    ("chompnote", Field::ChompNote),
+    ("interval", Field::Interval),
+    ("duration", Field::Duration),
+    ("tags", Field::Tags),
+    ("hr", Field::Rule('-')),
+];
+
+const COLORS: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+impl Template {
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut tokens = Vec::new();
+        let mut text = String::new();
+        let mut rest = template;
+
+        while let Some(pos) = rest.find('%') {
+            text.push_str(&rest[..pos]);
+            rest = &rest[pos + 1..];
+
+            if let Some(after) = rest.strip_prefix('%') {
+                text.push('%');
+                rest = after;
+                continue;
+            }
+
+            let left = rest.starts_with('-');
+            let spec = rest.trim_start_matches('-');
+            let (width, spec) = leading_number(spec);
+            let (max, spec) = match spec.strip_prefix('.') {
+                Some(after) => leading_number(after),
+                None => (None, spec),
+            };
+            let name_len = spec
+                .find(|c: char| !c.is_ascii_alphabetic() && c != '_')
+                .unwrap_or(spec.len());
+            if name_len == 0 {
+                // A lone `%`, as in "50% done"
+                text.push('%');
+                continue;
+            }
+
+            let (token, len) = parse_placeholder(&spec[..name_len])
+                .ok_or_else(|| eyre!("unknown placeholder '%{}' in template", &spec[..name_len]))?;
+            let token = match token {
+                Token::Field { field, .. } => Token::Field {
+                    field,
+                    width,
+                    left,
+                    max,
+                },
+                other => other,
+            };
+
+            if let Token::Text(newline_or_tab) = &token {
+                text.push_str(newline_or_tab);
+            } else {
+                if !text.is_empty() {
+                    tokens.push(Token::Text(std::mem::take(&mut text)));
+                }
+                tokens.push(token);
+            }
+            rest = &spec[len..];
+        }
+        text.push_str(rest);
+        if !text.is_empty() {
+            tokens.push(Token::Text(text));
+        }
+
+        Ok(Self { tokens })
+    }
+
+    /// Fill in the template for one entry; colour placeholders only produce
+    /// escape codes when `color` is set.
+    pub fn render(&self, section: &str, entry: &Entry, color: bool) -> String {
+        let now = Local::now();
+        let mut output = String::new();
+
+        for token in &self.tokens {
+            match token {
+                Token::Text(text) => output.push_str(text),
+                Token::Color(code) => {
+                    if color {
+                        output.push_str(&format!("\x1b[{code}m"));
+                    }
+                }
+                Token::Field {
+                    field,
+                    width,
+                    left,
+                    max,
+                } => {
+                    let value = field_value(*field, section, entry, &now, *width);
+                    output.push_str(&fit(value, *width, *left, *max));
+                }
+            }
+        }
+
+        if color && self.tokens.iter().any(|t| matches!(t, Token::Color(_))) {
+            output.push_str("\x1b[0m");
+        }
+        output
+    }
+}
+
+/// The template to display entries with: `--template`, else the named
+/// `--config_template`, else the `default` template from the config, if any.
+pub fn resolve(
+    template: Option<&str>,
+    config_template: Option<&str>,
+    config: &Config,
+) -> Result<Option<Template>> {
+    if let Some(template) = template {
+        return Template::parse(template).map(Some);
+    }
+    if let Some(name) = config_template {
+        let template = config
+            .templates
+            .get(name)
+            .ok_or_else(|| eyre!("no template named '{name}' in the config"))?;
+        return Template::parse(template).map(Some);
+    }
+    config
+        .templates
+        .get("default")
+        .map(|template| Template::parse(template))
+        .transpose()
+}
+
+/// Whether template colours should be written to standard output
+pub fn use_color() -> bool {
+    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// The token for a placeholder name, and how much of `name` it used.
+/// The longest known name wins, so `%titles` is `%title` followed by "s".
+fn parse_placeholder(name: &str) -> Option<(Token, usize)> {
+    let field = |field| Token::Field {
+        field,
+        width: None,
+        left: false,
+        max: None,
+    };
+
+    let mut candidates: Vec<(String, Token)> = vec![
+        ("hr_under".to_string(), field(Field::Rule('_'))),
+        ("n".to_string(), Token::Text("\n".to_string())),
+        ("t".to_string(), Token::Text("\t".to_string())),
+        ("reset".to_string(), Token::Color("0".to_string())),
+        ("default".to_string(), Token::Color("0".to_string())),
+        ("bold".to_string(), Token::Color("1".to_string())),
+    ];
+    candidates.extend(FIELDS.iter().map(|(n, f)| (n.to_string(), field(*f))));
+    for (i, color) in COLORS.iter().enumerate() {
+        candidates.push((color.to_string(), Token::Color(format!("3{i}"))));
+        candidates.push((format!("bold{color}"), Token::Color(format!("1;3{i}"))));
+        candidates.push((format!("bg{color}"), Token::Color(format!("4{i}"))));
+    }
+
+    candidates
+        .into_iter()
+        .filter(|(candidate, _)| name.starts_with(candidate.as_str()))
+        .max_by_key(|(candidate, _)| candidate.len())
+        .map(|(candidate, token)| (token, candidate.len()))
+}
+
+fn leading_number(s: &str) -> (Option<usize>, &str) {
+    let len = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    (s[..len].parse().ok(), &s[len..])
+}
+
+fn field_value(
+    field: Field,
+    section: &str,
+    entry: &Entry,
+    now: &DateTime<Local>,
+    width: Option<usize>,
+) -> String {
+    let note_lines = |indent: &str| {
+        entry
+            .note
+            .iter()
+            .flat_map(|note| note.lines())
+            .map(|line| format!("\n{indent}{line}"))
+            .collect::<String>()
+    };
+
+    match field {
+        Field::Date => entry.started_at.format("%Y-%m-%d %H:%M").to_string(),
+        Field::ShortDate => format!(
+            "{} {}",
+            format_date(&entry.started_at, now),
+            entry.started_at.format("%H:%M")
+        ),
+        Field::Title => entry.title(),
+        Field::Section => section.to_string(),
+        Field::Note => note_lines("\t"),
+        Field::OutdentedNote => note_lines(""),
+        Field::ChompNote => entry
+            .note
+            .iter()
+            .flat_map(|note| note.lines())
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" "),
+        Field::Interval => calculate_duration(entry)
+            .1
+            .map(|duration| format_duration(&duration))
+            .unwrap_or_default(),
+        Field::Duration if !entry.is_done() => format_duration(&(*now - entry.started_at)),
+        Field::Duration => String::new(),
+        Field::Tags => format_tags(&entry.tags),
+        Field::Rule(c) => c.to_string().repeat(width.unwrap_or(DEFAULT_RULE_WIDTH)),
+    }
+}
+
+/// Pad `value` to `width` characters and cut it down to `max`
+fn fit(value: String, width: Option<usize>, left: bool, max: Option<usize>) -> String {
+    let value = match max {
+        Some(max) if value.chars().count() > max => {
+            let mut cut: String = value.chars().take(max.saturating_sub(1)).collect();
+            cut.push('…');
+            cut
+        }
+        _ => value,
+    };
+
+    match width {
+        Some(width) if left => format!("{value:<width$}"),
+        Some(width) => format!("{value:>width$}"),
+        None => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry() -> Entry {
+        Entry::new("Write docs".to_string(), "Work".to_string())
+            .with_started_at(Local.with_ymd_and_hms(2025, 7, 28, 9, 30, 0).unwrap())
+            .with_tag("docs".to_string(), None)
+            .with_tag("done".to_string(), Some("2025-07-28 11:00".to_string()))
+            .with_note("First line\nSecond line".to_string())
+    }
+
+    #[test]
+    fn test_render_placeholders() {
+        let template =
+            Template::parse("%date | %title [%section] %interval%note%n%chompnote").unwrap();
+        assert_eq!(
+            template.render("Work", &entry(), false),
+            "2025-07-28 09:30 | Write docs @docs @done(2025-07-28 11:00) [Work] 1h30m\
+             \n\tFirst line\n\tSecond line\nFirst line Second line"
+        );
+    }
+
+    #[test]
+    fn test_width_modifiers() {
+        let template = Template::parse("%-8section|%8section|%.6title|%-8.4tags|").unwrap();
+        assert_eq!(
+            template.render("Work", &entry(), false),
+            "Work    |    Work|Write…|@do…    |"
+        );
+    }
+
+    #[test]
+    fn test_colors_only_when_enabled() {
+        let template = Template::parse("%boldcyan%section%reset 100%% %t").unwrap();
+        assert_eq!(template.render("Work", &entry(), false), "Work 100% \t");
+        assert_eq!(
+            template.render("Work", &entry(), true),
+            "\x1b[1;36mWork\x1b[0m 100% \t\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_unknown_placeholder_is_an_error() {
+        assert!(Template::parse("%title %bogus").is_err());
+        // A `%` that doesn't start a name is just text
+        assert!(Template::parse("50% of %title").is_ok());
+    }
+
+    #[test]
+    fn test_resolve_prefers_flags_over_config() {
+        let mut config = Config::default();
+        assert_eq!(resolve(None, None, &config).unwrap(), None);
+
+        config
+            .templates
+            .insert("default".to_string(), "%title".to_string());
+        config
+            .templates
+            .insert("short".to_string(), "%section".to_string());
+        let render = |template: Option<Template>| template.unwrap().render("Work", &entry(), false);
+
+        assert_eq!(
+            render(resolve(None, None, &config).unwrap()),
+            "Write docs @docs @done(2025-07-28 11:00)"
+        );
+        assert_eq!(
+            render(resolve(None, Some("short"), &config).unwrap()),
+            "Work"
+        );
+        assert_eq!(
+            render(resolve(Some("%date"), Some("short"), &config).unwrap()),
+            "2025-07-28 09:30"
+        );
+        assert!(resolve(None, Some("missing"), &config).is_err());
+    }
+}
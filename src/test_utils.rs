@@ -1,8 +1,10 @@
 #[cfg(test)]
 pub mod utils {
+    use crate::models::entry::parse_time;
+    use crate::models::{DoingFile, Entry};
     use crate::storage::Config;
     use chrono::{DateTime, Duration, Local};
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
     use std::env;
     use std::fs;
     use std::path::PathBuf;
@@ -37,6 +39,8 @@ pub mod utils {
             // Create test config
             let config = Config {
                 doing_file: doing_file_path.clone(),
+                backup_dir: Some(temp_dir.path().join("backups")),
+                ..Config::default()
             };
 
             let config_content = serde_json::to_string_pretty(&config)?;
@@ -98,7 +102,7 @@ Archive:
             &self,
             entries: Vec<TestEntry>,
         ) -> color_eyre::Result<()> {
-            let mut sections: HashMap<String, Vec<String>> = HashMap::new();
+            let mut sections: IndexMap<String, Vec<String>> = IndexMap::new();
 
             for entry in entries {
                 let section = entry.section.unwrap_or("Currently".to_string());
@@ -141,6 +145,15 @@ Archive:
 
             self.create_test_file(&content)
         }
+
+        /// Write `entries` to the doing file, each in its own section
+        pub fn create_doing_file_from_entries(&self, entries: &[Entry]) -> color_eyre::Result<()> {
+            let mut doing_file = DoingFile::new(self.doing_file_path.clone());
+            for entry in entries {
+                doing_file.add_entry(entry.clone());
+            }
+            self.create_test_file(&doing_file.to_taskpaper())
+        }
     }
 
     pub struct TestEntry {
@@ -197,6 +210,24 @@ Archive:
         }
     }
 
+    /// An entry in Currently started at `start` (as `parse_time` reads it),
+    /// done at `end` if given, with `tags` written as `name` or `name(value)`
+    pub fn timed_entry(description: &str, start: &str, end: Option<&str>, tags: &[&str]) -> Entry {
+        let mut entry = Entry::new(description.to_string(), "Currently".to_string())
+            .with_started_at(parse_time(start).unwrap());
+        for tag in tags {
+            let (name, value) = match tag.split_once('(') {
+                Some((name, value)) => (name, Some(value.trim_end_matches(')').to_string())),
+                None => (*tag, None),
+            };
+            entry.tags.insert(name.to_string(), value);
+        }
+        if let Some(end) = end {
+            entry.finish(parse_time(end).unwrap());
+        }
+        entry
+    }
+
     fn format_date(date: DateTime<Local>) -> String {
         date.format("%Y-%m-%d %H:%M").to_string()
     }
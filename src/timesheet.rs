@@ -0,0 +1,300 @@
+use crate::models::Entry;
+use crate::storage::config::InvoiceConfig;
+use chrono::{DateTime, Duration, Local};
+use indexmap::IndexMap;
+
+/// What entries are billed to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupBy {
+    /// The value of the client tag, or the section for entries without one
+    Client,
+    Section,
+}
+
+/// Billable time in a period, by client
+#[derive(Debug, Clone)]
+pub struct Timesheet {
+    pub title: String,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub currency: String,
+    /// Clients in the order they first appear
+    pub clients: Vec<ClientSheet>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientSheet {
+    pub name: String,
+    /// Hourly rate
+    pub rate: f64,
+    /// Minutes each line was rounded to; 0 when not rounded
+    pub rounding: u32,
+    /// Oldest first
+    pub lines: Vec<TimesheetLine>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TimesheetLine {
+    pub section: String,
+    pub entry: Entry,
+    /// Time from start to `@done`
+    pub worked: Duration,
+    /// Worked time after rounding
+    pub billed: Duration,
+}
+
+impl Timesheet {
+    /// Build the timesheet from the entries already filtered down to the
+    /// period. Only finished entries count, and entries with a non-billable
+    /// tag are left out.
+    pub fn new(
+        title: String,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        entries: &[(String, Entry)],
+        config: &InvoiceConfig,
+        group_by: GroupBy,
+    ) -> Self {
+        let mut entries = entries.to_vec();
+        entries.sort_by_key(|(_, entry)| entry.started_at);
+
+        let mut clients: IndexMap<String, ClientSheet> = IndexMap::new();
+        for (section, entry) in entries {
+            let Some(worked) = entry.duration() else {
+                continue;
+            };
+            let non_billable = entry.tags.keys().any(|tag| {
+                config
+                    .non_billable
+                    .iter()
+                    .any(|excluded| excluded.eq_ignore_ascii_case(tag))
+            });
+            if non_billable {
+                continue;
+            }
+
+            let name = match group_by {
+                GroupBy::Client => entry
+                    .tags
+                    .get(&config.client_tag)
+                    .cloned()
+                    .flatten()
+                    .filter(|client| !client.trim().is_empty())
+                    .unwrap_or_else(|| section.clone()),
+                GroupBy::Section => section.clone(),
+            };
+            let client = clients
+                .entry(name.to_lowercase())
+                .or_insert_with(|| client_sheet(name, config));
+            client.lines.push(TimesheetLine {
+                billed: round(worked, client.rounding),
+                section,
+                entry,
+                worked,
+            });
+        }
+
+        Self {
+            title,
+            start,
+            end,
+            currency: config.currency.clone(),
+            clients: clients.into_values().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    pub fn billed(&self) -> Duration {
+        self.clients.iter().map(ClientSheet::billed).sum()
+    }
+
+    /// Sum of the lines' worked hours as shown
+    pub fn worked_hours(&self) -> f64 {
+        self.clients.iter().map(ClientSheet::worked_hours).sum()
+    }
+
+    /// Sum of the lines' billed hours as shown
+    pub fn billed_hours(&self) -> f64 {
+        self.clients.iter().map(ClientSheet::billed_hours).sum()
+    }
+
+    pub fn amount(&self) -> f64 {
+        self.clients.iter().map(ClientSheet::amount).sum()
+    }
+}
+
+impl ClientSheet {
+    pub fn billed(&self) -> Duration {
+        self.lines.iter().map(|line| line.billed).sum()
+    }
+
+    pub fn worked_hours(&self) -> f64 {
+        self.lines.iter().map(TimesheetLine::worked_hours).sum()
+    }
+
+    pub fn billed_hours(&self) -> f64 {
+        self.lines.iter().map(TimesheetLine::billed_hours).sum()
+    }
+
+    /// Sum of the line amounts, so that the lines add up to the total
+    pub fn amount(&self) -> f64 {
+        self.lines.iter().map(|line| self.line_amount(line)).sum()
+    }
+
+    /// The line's billed hours as shown times the rate, to the cent
+    pub fn line_amount(&self, line: &TimesheetLine) -> f64 {
+        cents(line.billed_hours() * self.rate)
+    }
+}
+
+impl TimesheetLine {
+    /// Worked hours to two decimals, as shown
+    pub fn worked_hours(&self) -> f64 {
+        cents(hours(self.worked))
+    }
+
+    /// Billed hours to two decimals, as shown
+    pub fn billed_hours(&self) -> f64 {
+        cents(hours(self.billed))
+    }
+}
+
+/// Hours as a fraction, e.g. 1.5 for 1:30
+fn hours(duration: Duration) -> f64 {
+    duration.num_seconds() as f64 / 3600.0
+}
+
+/// Rounded to two decimals
+fn cents(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// A client's rate and rounding, from its own settings when it has them.
+/// Client names match case-insensitively.
+fn client_sheet(name: String, config: &InvoiceConfig) -> ClientSheet {
+    let own = config
+        .clients
+        .iter()
+        .find(|(client, _)| client.eq_ignore_ascii_case(&name))
+        .map(|(_, rate)| rate);
+    ClientSheet {
+        rate: own.and_then(|own| own.rate).unwrap_or(config.rate),
+        rounding: own.and_then(|own| own.rounding).unwrap_or(config.rounding),
+        name,
+        lines: Vec::new(),
+    }
+}
+
+/// Round to the nearest multiple of `minutes`, halves rounding up
+fn round(duration: Duration, minutes: u32) -> Duration {
+    if minutes == 0 {
+        return duration;
+    }
+    let step = i64::from(minutes) * 60;
+    let seconds = duration.num_seconds();
+    Duration::seconds((seconds + step / 2) / step * step)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::config::ClientRate;
+    use crate::test_utils::utils::timed_entry;
+
+    #[test]
+    fn test_groups_rates_and_rounding() {
+        let mut config = InvoiceConfig {
+            rate: 100.0,
+            rounding: 15,
+            ..Default::default()
+        };
+        config.clients.insert(
+            "acme".to_string(),
+            ClientRate {
+                rate: Some(150.0),
+                rounding: Some(6),
+            },
+        );
+        let entries: Vec<(String, Entry)> = [
+            (
+                "Work",
+                "2025-07-28 09:00",
+                Some("2025-07-28 09:52"),
+                &["client(Acme)"][..],
+            ),
+            (
+                "Work",
+                "2025-07-28 10:00",
+                Some("2025-07-28 10:20"),
+                &["client(acme)"],
+            ),
+            (
+                "Internal",
+                "2025-07-28 11:00",
+                Some("2025-07-28 11:08"),
+                &[],
+            ),
+            (
+                "Internal",
+                "2025-07-28 12:00",
+                Some("2025-07-28 13:00"),
+                &["non-billable"],
+            ),
+            ("Internal", "2025-07-28 14:00", None, &[]),
+        ]
+        .into_iter()
+        .map(|(section, start, end, tags)| {
+            (section.to_string(), timed_entry("Work", start, end, tags))
+        })
+        .collect();
+        let (start, end) = (entries[0].1.started_at, entries[3].1.started_at);
+
+        let sheet = Timesheet::new(
+            "July".to_string(),
+            start,
+            end,
+            &entries,
+            &config,
+            GroupBy::Client,
+        );
+        assert_eq!(sheet.clients.len(), 2);
+
+        let acme = &sheet.clients[0];
+        assert_eq!(
+            (acme.name.as_str(), acme.rate, acme.rounding),
+            ("Acme", 150.0, 6)
+        );
+        // 52 minutes to 54, 20 minutes to 18
+        assert_eq!(acme.billed(), Duration::minutes(72));
+        assert!((acme.amount() - 180.0).abs() < 1e-9);
+
+        let internal = &sheet.clients[1];
+        assert_eq!(internal.name, "Internal");
+        assert_eq!(internal.lines.len(), 1);
+        assert_eq!(internal.billed(), Duration::minutes(15));
+        assert!((sheet.amount() - 205.0).abs() < 1e-9);
+
+        let by_section = Timesheet::new(
+            "July".to_string(),
+            start,
+            end,
+            &entries,
+            &config,
+            GroupBy::Section,
+        );
+        assert_eq!(by_section.clients[0].name, "Work");
+        assert_eq!(by_section.clients[0].rate, 100.0);
+    }
+
+    #[test]
+    fn test_round_to_nearest() {
+        assert_eq!(round(Duration::minutes(7), 15), Duration::minutes(0));
+        assert_eq!(round(Duration::seconds(450), 15), Duration::minutes(15));
+        assert_eq!(round(Duration::minutes(23), 15), Duration::minutes(30));
+        assert_eq!(round(Duration::minutes(23), 6), Duration::minutes(24));
+        assert_eq!(round(Duration::seconds(61), 0), Duration::seconds(61));
+    }
+}
@@ -0,0 +1,221 @@
+use crate::display::{SortOrder, TagSort};
+use crate::models::Entry;
+use chrono::{Duration, NaiveDate};
+use indexmap::IndexMap;
+
+/// Time spent on finished entries, grouped by tag, section and day.
+///
+/// Only entries with a `@done` time count; running entries and a bare `@done`
+/// have no interval yet. An entry's whole interval counts towards each of its
+/// tags, so tag percentages can add up to more than 100%.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Totals {
+    pub tags: Vec<Total>,
+    pub sections: Vec<Total>,
+    pub days: Vec<Total>,
+    pub total: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Total {
+    pub name: String,
+    pub duration: Duration,
+}
+
+impl Totals {
+    /// Add up `entries`, sorting each group by name or by time
+    pub fn new(entries: &[(String, Entry)], sort: &TagSort, order: &SortOrder) -> Self {
+        let mut tags: IndexMap<String, Duration> = IndexMap::new();
+        let mut sections: IndexMap<String, Duration> = IndexMap::new();
+        let mut days: IndexMap<NaiveDate, Duration> = IndexMap::new();
+        let mut total = Duration::zero();
+
+        for (section, entry) in entries {
+            let Some(duration) = entry.duration() else {
+                continue;
+            };
+
+            for tag in entry.tags.keys().filter(|tag| *tag != "done") {
+                *tags.entry(tag.clone()).or_insert_with(Duration::zero) += duration;
+            }
+            *sections
+                .entry(section.clone())
+                .or_insert_with(Duration::zero) += duration;
+            *days
+                .entry(entry.started_at.date_naive())
+                .or_insert_with(Duration::zero) += duration;
+            total += duration;
+        }
+
+        let days = days
+            .into_iter()
+            .map(|(day, duration)| (day.format("%Y-%m-%d").to_string(), duration));
+
+        Self {
+            tags: sorted(tags, sort, order),
+            sections: sorted(sections, sort, order),
+            days: sorted(days, sort, order),
+            total,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total.is_zero()
+    }
+
+    /// `duration` as a whole percentage of the total time
+    pub fn percent(&self, duration: Duration) -> u32 {
+        if self.total.is_zero() {
+            return 0;
+        }
+        (duration.num_seconds() as f64 * 100.0 / self.total.num_seconds() as f64).round() as u32
+    }
+
+    /// The groups with the heading they're shown under
+    pub fn groups(&self) -> [(&'static str, &[Total]); 3] {
+        [
+            ("Tag", &self.tags),
+            ("Section", &self.sections),
+            ("Day", &self.days),
+        ]
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let group = |totals: &[Total]| {
+            totals
+                .iter()
+                .map(|total| {
+                    serde_json::json!({
+                        "name": total.name,
+                        "seconds": total.duration.num_seconds(),
+                        "percent": self.percent(total.duration),
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+
+        serde_json::json!({
+            "tags": group(&self.tags),
+            "sections": group(&self.sections),
+            "days": group(&self.days),
+            "seconds": self.total.num_seconds(),
+        })
+    }
+}
+
+fn sorted(
+    totals: impl IntoIterator<Item = (String, Duration)>,
+    sort: &TagSort,
+    order: &SortOrder,
+) -> Vec<Total> {
+    let mut totals: Vec<Total> = totals
+        .into_iter()
+        .map(|(name, duration)| Total { name, duration })
+        .collect();
+
+    match sort {
+        TagSort::Name => totals.sort_by_key(|total| total.name.to_lowercase()),
+        // Ties are broken by name so the output doesn't depend on entry order
+        TagSort::Time => totals.sort_by(|a, b| {
+            a.duration
+                .cmp(&b.duration)
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        }),
+    }
+    if *order == SortOrder::Desc {
+        totals.reverse();
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::utils::timed_entry;
+
+    fn entries() -> Vec<(String, Entry)> {
+        vec![
+            (
+                "Currently".to_string(),
+                timed_entry(
+                    "Work",
+                    "2025-07-28 09:00",
+                    Some("2025-07-28 10:30"),
+                    &["code"],
+                ),
+            ),
+            (
+                "Meetings".to_string(),
+                timed_entry(
+                    "Work",
+                    "2025-07-28 11:00",
+                    Some("2025-07-28 11:30"),
+                    &["meeting", "code"],
+                ),
+            ),
+            (
+                "Currently".to_string(),
+                timed_entry(
+                    "Work",
+                    "2025-07-29 09:00",
+                    Some("2025-07-29 10:00"),
+                    &["meeting"],
+                ),
+            ),
+            // Still running, so it isn't counted
+            (
+                "Currently".to_string(),
+                timed_entry("Work", "2025-07-29 10:00", None, &["admin"]),
+            ),
+        ]
+    }
+
+    fn names(totals: &[Total]) -> Vec<(&str, i64)> {
+        totals
+            .iter()
+            .map(|total| (total.name.as_str(), total.duration.num_minutes()))
+            .collect()
+    }
+
+    #[test]
+    fn test_groups_by_tag_section_and_day() {
+        let totals = Totals::new(&entries(), &TagSort::Name, &SortOrder::Asc);
+
+        assert_eq!(totals.total, Duration::hours(3));
+        assert_eq!(names(&totals.tags), vec![("code", 120), ("meeting", 90)]);
+        assert_eq!(
+            names(&totals.sections),
+            vec![("Currently", 150), ("Meetings", 30)]
+        );
+        assert_eq!(
+            names(&totals.days),
+            vec![("2025-07-28", 120), ("2025-07-29", 60)]
+        );
+        assert_eq!(totals.percent(totals.tags[0].duration), 67);
+    }
+
+    #[test]
+    fn test_sort_by_time_descending() {
+        let totals = Totals::new(&entries(), &TagSort::Time, &SortOrder::Desc);
+
+        assert_eq!(names(&totals.tags), vec![("code", 120), ("meeting", 90)]);
+        assert_eq!(
+            names(&totals.sections),
+            vec![("Currently", 150), ("Meetings", 30)]
+        );
+
+        let totals = Totals::new(&entries(), &TagSort::Time, &SortOrder::Asc);
+        assert_eq!(names(&totals.tags), vec![("meeting", 90), ("code", 120)]);
+    }
+
+    #[test]
+    fn test_running_entries_only_have_no_totals() {
+        let entries = vec![(
+            "Currently".to_string(),
+            timed_entry("Work", "2025-07-29 10:00", None, &["admin"]),
+        )];
+        let totals = Totals::new(&entries, &TagSort::Name, &SortOrder::Asc);
+        assert!(totals.is_empty());
+        assert!(totals.tags.is_empty());
+    }
+}